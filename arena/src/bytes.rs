@@ -0,0 +1,101 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Arena of aligned byte buffers.
+//!
+//! The [`ByteArena`] hands out byte buffers of any size and alignment from
+//! its statically allocated storage, for example for DMA buffers that
+//! must be aligned to a cache line.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use foundation_arena::bytes::ByteArena;
+//!
+//! let arena: ByteArena<192> = ByteArena::new();
+//! let display: &mut [u8] = arena.alloc_bytes(100, 32).unwrap();
+//! let scanner: &mut [u8] = arena.alloc_bytes(64, 32).unwrap();
+//!
+//! assert_eq!(display.as_ptr() as usize % 32, 0);
+//! assert_eq!(scanner.as_ptr() as usize % 32, 0);
+//! assert!(arena.alloc_bytes(64, 32).is_none());
+//! ```
+
+use core::cell::{Cell, UnsafeCell};
+
+/// Alignment of the storage of a [`ByteArena`].
+///
+/// Buffers with a bigger alignment may need padding even when they are the
+/// first ones allocated.
+pub const STORAGE_ALIGN: usize = 32;
+
+#[repr(C, align(32))]
+struct Storage<const N: usize>([u8; N]);
+
+/// An arena of byte buffers of `N` bytes in total.
+pub struct ByteArena<const N: usize> {
+    storage: UnsafeCell<Storage<N>>,
+    offset: Cell<usize>,
+}
+
+impl<const N: usize> ByteArena<N> {
+    /// Construct a new arena.
+    pub const fn new() -> Self {
+        Self {
+            storage: UnsafeCell::new(Storage([0; N])),
+            offset: Cell::new(0),
+        }
+    }
+
+    /// Returns the size of the storage of the arena.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of bytes not allocated yet, including the ones
+    /// that may be used as padding.
+    pub fn remaining(&self) -> usize {
+        N - self.offset.get()
+    }
+
+    /// Allocates a zeroed buffer of `len` bytes aligned to `align` bytes.
+    ///
+    /// Returns `None` if there's not enough space left in the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two.
+    pub fn alloc_bytes(&self, len: usize, align: usize) -> Option<&mut [u8]> {
+        assert!(align.is_power_of_two(), "align must be a power of two");
+
+        let offset = self.offset.get();
+        let base = self.storage.get() as *mut u8;
+        let padding = unsafe { base.add(offset) }.align_offset(align);
+        let start = offset.checked_add(padding)?;
+        let end = start.checked_add(len)?;
+        if end > N {
+            return None;
+        }
+
+        self.offset.set(end);
+
+        // SAFETY: `start..end` is in bounds and was never handed out
+        // before, as the offset only grows while the arena is borrowed.
+        Some(unsafe { core::slice::from_raw_parts_mut(base.add(start), len) })
+    }
+
+    /// Frees all the buffers, zeroing the storage.
+    ///
+    /// Requires a mutable reference so no buffer can be in use.
+    pub fn reset(&mut self) {
+        let offset = self.offset.get();
+        self.storage.get_mut().0[..offset].fill(0);
+        self.offset.set(0);
+    }
+}
+
+impl<const N: usize> Default for ByteArena<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
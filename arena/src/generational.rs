@@ -0,0 +1,175 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Arena with deallocation.
+//!
+//! The [`Arena`](crate::Arena) never frees its items, the
+//! [`GenerationalArena`] instead hands out [`Handle`]s that can be removed
+//! to make room for new items. Each slot has a generation that is increased
+//! when its item is removed, so a stale [`Handle`] doesn't give access to a
+//! newer item stored in the same slot.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use foundation_arena::generational::GenerationalArena;
+//!
+//! let mut arena: GenerationalArena<u32, 1> = GenerationalArena::new();
+//! let one = arena.insert(1).unwrap();
+//! assert_eq!(arena.insert(2), Err(2));
+//!
+//! assert_eq!(arena.remove(one), Some(1));
+//! let two = arena.insert(2).unwrap();
+//!
+//! assert_eq!(arena.get(one), None);
+//! assert_eq!(arena.get(two), Some(&2));
+//! ```
+
+/// A reference to an item of a [`GenerationalArena`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: usize,
+    generation: u32,
+}
+
+impl Handle {
+    /// Index of the slot of the item.
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Generation of the slot when the item was inserted.
+    pub const fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+enum Slot<T> {
+    Occupied {
+        generation: u32,
+        item: T,
+    },
+    Vacant {
+        generation: u32,
+        next: Option<usize>,
+    },
+}
+
+/// An arena of objects of type `T` that can be removed.
+pub struct GenerationalArena<T, const N: usize> {
+    slots: [Slot<T>; N],
+    /// Number of slots used at least once.
+    used: usize,
+    /// First slot of the list of removed slots.
+    free: Option<usize>,
+    len: usize,
+}
+
+impl<T, const N: usize> GenerationalArena<T, N> {
+    const ELEM: Slot<T> = Slot::Vacant {
+        generation: 0,
+        next: None,
+    };
+    const INIT: [Slot<T>; N] = [Self::ELEM; N];
+
+    /// Construct a new arena.
+    pub const fn new() -> Self {
+        Self {
+            slots: Self::INIT,
+            used: 0,
+            free: None,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of items in the arena.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the arena has no items.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum number of items of the arena.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Inserts an item in the arena, returning a handle to that item.
+    ///
+    /// If there's not enough space left in the arena, then the item is
+    /// returned as-is.
+    pub fn insert(&mut self, item: T) -> Result<Handle, T> {
+        let index = match self.free {
+            Some(index) => index,
+            None if self.used < N => {
+                self.used += 1;
+                self.used - 1
+            }
+            None => return Err(item),
+        };
+
+        let slot = &mut self.slots[index];
+        let generation = match *slot {
+            Slot::Vacant { generation, next } => {
+                self.free = next;
+                generation
+            }
+            Slot::Occupied { .. } => unreachable!("free list contains an occupied slot"),
+        };
+
+        *slot = Slot::Occupied { generation, item };
+        self.len += 1;
+
+        Ok(Handle { index, generation })
+    }
+
+    /// Removes an item from the arena, returning it.
+    ///
+    /// Returns `None` if the item was already removed.
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        self.get(handle)?;
+
+        let vacant = Slot::Vacant {
+            generation: handle.generation.wrapping_add(1),
+            next: self.free,
+        };
+        let slot = core::mem::replace(&mut self.slots[handle.index], vacant);
+        self.free = Some(handle.index);
+        self.len -= 1;
+
+        match slot {
+            Slot::Occupied { item, .. } => Some(item),
+            Slot::Vacant { .. } => unreachable!(),
+        }
+    }
+
+    /// Returns a reference to an item, or `None` if it was removed.
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        match self.slots.get(handle.index)? {
+            Slot::Occupied { generation, item } if *generation == handle.generation => Some(item),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to an item, or `None` if it was removed.
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        match self.slots.get_mut(handle.index)? {
+            Slot::Occupied { generation, item } if *generation == handle.generation => Some(item),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the item of `handle` is in the arena.
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+}
+
+impl<T, const N: usize> Default for GenerationalArena<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -23,12 +23,18 @@
 //!
 //! println!("{one} {two}");
 //! ```
+//!
+//! Items of the [`Arena`] live as long as the arena itself, see
+//! [`generational::GenerationalArena`] for an arena whose items can be
+//! removed, and [`bytes::ByteArena`] for an arena of aligned byte buffers.
 
 #![no_std]
 
 use core::{cell::RefCell, mem::MaybeUninit};
 
 pub mod boxed;
+pub mod bytes;
+pub mod generational;
 
 /// An arena of objects of type `T`.
 pub struct Arena<T, const N: usize> {
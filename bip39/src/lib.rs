@@ -0,0 +1,424 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! # Foundation BIP-39.
+//!
+//! [BIP-39] mnemonic codes without allocations:
+//!
+//!  - [`ENGLISH`] and [`lookup`]: the English word list.
+//!  - [`Mnemonic`]: conversion between entropy, word indexes and words,
+//!    with checksum validation.
+//!  - [`Mnemonic::to_seed`]: derivation of the BIP-32 seed.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use foundation_bip39::Mnemonic;
+//!
+//! let mnemonic: Mnemonic = "abandon abandon abandon abandon abandon abandon \
+//!                           abandon abandon abandon abandon abandon about"
+//!     .parse()
+//!     .unwrap();
+//! assert_eq!(mnemonic.entropy(), &[0; 16]);
+//!
+//! let seed = mnemonic.to_seed("TREZOR");
+//! assert_eq!(seed[..4], [0xc5, 0x52, 0x57, 0xc3]);
+//! ```
+//!
+//! [BIP-39]: https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod wordlist;
+
+pub use self::wordlist::ENGLISH;
+
+use core::{fmt, str::FromStr};
+
+use bitcoin_hashes::{sha256, sha512};
+use foundation_crypto::{pbkdf2, Zeroizing};
+
+/// Minimum number of words of a mnemonic.
+pub const MIN_WORDS: usize = 12;
+
+/// Maximum number of words of a mnemonic.
+pub const MAX_WORDS: usize = 24;
+
+/// Maximum length of the entropy, in bytes.
+pub const MAX_ENTROPY_LEN: usize = 32;
+
+/// Length of the seed derived from a mnemonic, in bytes.
+pub const SEED_LEN: usize = 64;
+
+/// Number of bits encoded by each word.
+const BITS_PER_WORD: usize = 11;
+
+/// Length of the longest word in the word list.
+const MAX_WORD_LEN: usize = 8;
+
+/// Length of the longest mnemonic sentence, with the words separated by
+/// spaces.
+const MAX_SENTENCE_LEN: usize = MAX_WORDS * (MAX_WORD_LEN + 1) - 1;
+
+/// PBKDF2 rounds used to derive the seed.
+const PBKDF2_ROUNDS: u32 = 2048;
+
+/// Find the index of `word` in the [`ENGLISH`] word list.
+pub fn lookup(word: &str) -> Option<u16> {
+    ENGLISH
+        .binary_search(&word)
+        .ok()
+        .map(|index| u16::try_from(index).expect("word list has 2048 words"))
+}
+
+/// Errors that can happen when creating a [`Mnemonic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The number of words is not 12, 15, 18, 21 or 24.
+    InvalidWordCount(usize),
+    /// The entropy length is not 16, 20, 24, 28 or 32 bytes.
+    InvalidEntropyLength(usize),
+    /// The word at this position is not in the word list.
+    UnknownWord(usize),
+    /// The word index at this position is out of range.
+    InvalidIndex(usize),
+    /// The checksum of the mnemonic is not valid.
+    InvalidChecksum,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidWordCount(count) => {
+                write!(f, "invalid number of words: {count}")
+            }
+            Error::InvalidEntropyLength(len) => {
+                write!(f, "invalid entropy length: {len} bytes")
+            }
+            Error::UnknownWord(position) => {
+                write!(f, "word {position} is not in the word list")
+            }
+            Error::InvalidIndex(position) => {
+                write!(f, "word index {position} is out of range")
+            }
+            Error::InvalidChecksum => write!(f, "invalid mnemonic checksum"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// A BIP-39 mnemonic.
+///
+/// The entropy is cleared from memory when the mnemonic is dropped.
+#[derive(Clone)]
+pub struct Mnemonic {
+    /// The entropy followed by the checksum byte.
+    data: Zeroizing<[u8; MAX_ENTROPY_LEN + 1]>,
+    word_count: usize,
+}
+
+impl Mnemonic {
+    /// Create a mnemonic from `entropy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidEntropyLength`] if the length of `entropy`
+    /// is not 16, 20, 24, 28 or 32 bytes.
+    pub fn from_entropy(entropy: &[u8]) -> Result<Self, Error> {
+        let len = entropy.len();
+        if !(16..=MAX_ENTROPY_LEN).contains(&len) || len % 4 != 0 {
+            return Err(Error::InvalidEntropyLength(len));
+        }
+
+        let mut data = Zeroizing::new([0; MAX_ENTROPY_LEN + 1]);
+        data[..len].copy_from_slice(entropy);
+        data[len] = checksum(entropy);
+
+        Ok(Self {
+            data,
+            word_count: len * 3 / 4,
+        })
+    }
+
+    /// Create a mnemonic from the indexes of its words in the word list.
+    ///
+    /// This is the representation used, for example, by SeedQR.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidWordCount`] if the number of indexes is not 12,
+    ///   15, 18, 21 or 24.
+    /// - [`Error::InvalidIndex`] if an index is not lower than 2048.
+    /// - [`Error::InvalidChecksum`] if the checksum is not valid.
+    pub fn from_indices(indices: &[u16]) -> Result<Self, Error> {
+        let word_count = indices.len();
+        if !(MIN_WORDS..=MAX_WORDS).contains(&word_count) || word_count % 3 != 0 {
+            return Err(Error::InvalidWordCount(word_count));
+        }
+
+        let mut data = Zeroizing::new([0; MAX_ENTROPY_LEN + 1]);
+        for (i, &index) in indices.iter().enumerate() {
+            if usize::from(index) >= ENGLISH.len() {
+                return Err(Error::InvalidIndex(i));
+            }
+
+            for bit in 0..BITS_PER_WORD {
+                if index & (1 << (BITS_PER_WORD - 1 - bit)) != 0 {
+                    let position = i * BITS_PER_WORD + bit;
+                    data[position / 8] |= 0x80 >> (position % 8);
+                }
+            }
+        }
+
+        let len = word_count * 4 / 3;
+        let checksum_shift = 8 - word_count / 3;
+        let expected = checksum(&data[..len]);
+        if data[len] >> checksum_shift != expected >> checksum_shift {
+            return Err(Error::InvalidChecksum);
+        }
+        data[len] = expected;
+
+        Ok(Self { data, word_count })
+    }
+
+    /// Parse a mnemonic from its words separated by whitespace.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidWordCount`] if the number of words is not 12, 15,
+    ///   18, 21 or 24.
+    /// - [`Error::UnknownWord`] if a word is not in the [`ENGLISH`] word
+    ///   list.
+    /// - [`Error::InvalidChecksum`] if the checksum is not valid.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let mut indices = [0; MAX_WORDS];
+        let mut word_count = 0;
+        for (i, word) in s.split_whitespace().enumerate() {
+            if i >= MAX_WORDS {
+                return Err(Error::InvalidWordCount(s.split_whitespace().count()));
+            }
+
+            indices[i] = lookup(word).ok_or(Error::UnknownWord(i))?;
+            word_count += 1;
+        }
+
+        Self::from_indices(&indices[..word_count])
+    }
+
+    /// Returns the number of words.
+    pub fn word_count(&self) -> usize {
+        self.word_count
+    }
+
+    /// Returns the entropy encoded by the mnemonic.
+    pub fn entropy(&self) -> &[u8] {
+        &self.data[..self.word_count * 4 / 3]
+    }
+
+    /// Returns the index in the word list of the word at `position`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `position` is not lower than
+    /// [`Mnemonic::word_count`].
+    pub fn index(&self, position: usize) -> u16 {
+        assert!(position < self.word_count, "word position out of range");
+
+        let bit = position * BITS_PER_WORD;
+        let byte = |i: usize| u32::from(self.data.get(i).copied().unwrap_or(0));
+        let bits = (byte(bit / 8) << 16) | (byte(bit / 8 + 1) << 8) | byte(bit / 8 + 2);
+        let shift = 24 - BITS_PER_WORD - bit % 8;
+
+        u16::try_from((bits >> shift) & 0x7FF).expect("index has 11 bits")
+    }
+
+    /// Returns an iterator over the indexes of the words in the word list.
+    pub fn indices(&self) -> impl Iterator<Item = u16> + '_ {
+        (0..self.word_count).map(|position| self.index(position))
+    }
+
+    /// Returns an iterator over the words.
+    pub fn words(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.indices().map(|index| ENGLISH[usize::from(index)])
+    }
+
+    /// Derive the BIP-32 seed using `passphrase`.
+    ///
+    /// The passphrase must be in Unicode NFKD form, as required by BIP-39.
+    /// This is not done here, but it only matters for non-ASCII
+    /// passphrases.
+    pub fn to_seed(&self, passphrase: &str) -> Zeroizing<[u8; SEED_LEN]> {
+        let mut sentence = Zeroizing::new([0; MAX_SENTENCE_LEN]);
+        let mut len = 0;
+        for (i, word) in self.words().enumerate() {
+            if i > 0 {
+                sentence[len] = b' ';
+                len += 1;
+            }
+
+            sentence[len..len + word.len()].copy_from_slice(word.as_bytes());
+            len += word.len();
+        }
+
+        let mut seed = Zeroizing::new([0; SEED_LEN]);
+        pbkdf2::<sha512::Hash>(
+            &sentence[..len],
+            &[b"mnemonic", passphrase.as_bytes()],
+            PBKDF2_ROUNDS,
+            &mut *seed,
+        );
+        seed
+    }
+}
+
+impl PartialEq for Mnemonic {
+    fn eq(&self, other: &Self) -> bool {
+        self.entropy() == other.entropy()
+    }
+}
+
+impl Eq for Mnemonic {}
+
+impl FromStr for Mnemonic {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, word) in self.words().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            f.write_str(word)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mnemonic")
+            .field("word_count", &self.word_count)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The first byte of the SHA-256 of `entropy`, whose first bits are the
+/// mnemonic checksum.
+fn checksum(entropy: &[u8]) -> u8 {
+    sha256::Hash::hash(entropy).to_byte_array()[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use faster_hex::{hex_decode, hex_string};
+
+    // Vectors from the reference implementation, all with the "TREZOR"
+    // passphrase.
+    const TEST_VECTORS: &[(&str, &str, &str)] = &[
+        (
+            "00000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        ),
+        (
+            "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+        ),
+        (
+            "808080808080808080808080808080808080808080808080",
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic avoid letter always",
+            "107d7c02a5aa6f38c58083ff74f04c607c2d2c0ecc55501dadd72d025b751bc27fe913ffb796f841c49b1d33b610cf0e91d3aa239027f5e99fe4ce9e5088cd65",
+        ),
+        (
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+            "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+        ),
+    ];
+
+    #[test]
+    fn test_vectors() {
+        for &(entropy, words, seed) in TEST_VECTORS {
+            let mut bytes = [0; MAX_ENTROPY_LEN];
+            let bytes = &mut bytes[..entropy.len() / 2];
+            hex_decode(entropy.as_bytes(), bytes).unwrap();
+
+            let mnemonic = Mnemonic::from_entropy(bytes).unwrap();
+            assert!(mnemonic.words().eq(words.split(' ')));
+            assert_eq!(hex_string(mnemonic.to_seed("TREZOR").as_ref()), seed);
+
+            let parsed = Mnemonic::parse(words).unwrap();
+            assert_eq!(parsed.entropy(), bytes);
+            assert_eq!(parsed, mnemonic);
+
+            let mut indices = [0; MAX_WORDS];
+            for (index, i) in mnemonic.indices().zip(indices.iter_mut()) {
+                *i = index;
+            }
+            let from_indices = Mnemonic::from_indices(&indices[..mnemonic.word_count()]);
+            assert_eq!(from_indices.unwrap(), mnemonic);
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup("abandon"), Some(0));
+        assert_eq!(lookup("zoo"), Some(2047));
+        assert_eq!(lookup("satoshi"), Some(1531));
+        assert_eq!(lookup("bitcoin"), None);
+        assert!(ENGLISH.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Mnemonic::from_entropy(&[0; 15]).unwrap_err(),
+            Error::InvalidEntropyLength(15)
+        );
+        assert_eq!(
+            Mnemonic::from_entropy(&[0; 36]).unwrap_err(),
+            Error::InvalidEntropyLength(36)
+        );
+        assert_eq!(
+            Mnemonic::parse("abandon abandon abandon").unwrap_err(),
+            Error::InvalidWordCount(3)
+        );
+        assert_eq!(
+            Mnemonic::parse(&"abandon ".repeat(27)).unwrap_err(),
+            Error::InvalidWordCount(27)
+        );
+        assert_eq!(
+            Mnemonic::parse(
+                "abandon abandon abandon abandon abandon abandon \
+                 abandon abandon abandon abandon bitcoin about"
+            )
+            .unwrap_err(),
+            Error::UnknownWord(10)
+        );
+        assert_eq!(
+            Mnemonic::parse(
+                "abandon abandon abandon abandon abandon abandon \
+                 abandon abandon abandon abandon abandon abandon"
+            )
+            .unwrap_err(),
+            Error::InvalidChecksum
+        );
+
+        let mut indices = [0; 12];
+        indices[3] = 2048;
+        assert_eq!(
+            Mnemonic::from_indices(&indices).unwrap_err(),
+            Error::InvalidIndex(3)
+        );
+    }
+}
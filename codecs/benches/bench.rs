@@ -6,11 +6,17 @@ use criterion::{
     Criterion, Throughput,
 };
 
-use foundation_codecs::{nostr::encode_npub, nostr::encode_nsec, seedqr::encode_to_slice};
+use foundation_bip39::lookup;
+use foundation_codecs::{
+    nostr::encode_npub,
+    nostr::encode_nsec,
+    seedqr::{encode_to_slice, MAX_DIGITS_LEN},
+};
 use foundation_test_vectors::{NIP19Vector, SeedQRVector};
 
 pub fn benchmark(c: &mut Criterion) {
     nip19_benchmark(c.benchmark_group("NIP-19"));
+    seedqr_benchmark(c.benchmark_group("SeedQR"));
 }
 
 pub fn nip19_benchmark(mut group: BenchmarkGroup<WallTime>) {
@@ -20,12 +26,13 @@ pub fn nip19_benchmark(mut group: BenchmarkGroup<WallTime>) {
         let function_name = match &*vector.kind {
             "npub" => "encode_npub",
             "nsec" => "encode_nsec",
-            _ => panic!("invalid kind {}", vector.kind),
+            _ => continue,
         };
 
+        let bytes: [u8; 32] = vector.bytes.as_slice().try_into().unwrap();
         group.bench_with_input(
             BenchmarkId::new(function_name, &vector.name),
-            &vector.bytes,
+            &bytes,
             |b, i| match &*vector.kind {
                 "npub" => b.iter(|| encode_npub(i)),
                 "nsec" => b.iter(|| encode_nsec(i)),
@@ -35,5 +42,27 @@ pub fn nip19_benchmark(mut group: BenchmarkGroup<WallTime>) {
     }
 }
 
+pub fn seedqr_benchmark(mut group: BenchmarkGroup<WallTime>) {
+    let vectors = SeedQRVector::new();
+
+    for vector in vectors.iter() {
+        let indices: Vec<u16> = vector
+            .seed
+            .words()
+            .map(|word| lookup(word).unwrap())
+            .collect();
+
+        group.throughput(Throughput::Elements(indices.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("encode_to_slice", &vector.name),
+            &indices,
+            |b, i| {
+                let mut digits = [0; MAX_DIGITS_LEN];
+                b.iter(|| encode_to_slice(black_box(i), &mut digits))
+            },
+        );
+    }
+}
+
 criterion_group!(benches, benchmark);
 criterion_main!(benches);
@@ -24,3 +24,128 @@ pub const fn base32_len(len: usize) -> usize {
         (bits / 5) + 1
     }
 }
+
+/// The Bech32 character set.
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Errors that can happen when decoding Bech32.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The string has no separator.
+    MissingSeparator,
+    /// The string mixes upper and lower case characters.
+    MixedCase,
+    /// The character at this position is not valid.
+    InvalidChar(usize),
+    /// The checksum is not valid.
+    InvalidChecksum,
+    /// The padding bits are not valid.
+    InvalidPadding,
+    /// The output buffer is too small.
+    BufferTooSmall,
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::MissingSeparator => write!(f, "missing separator"),
+            DecodeError::MixedCase => write!(f, "mixed case"),
+            DecodeError::InvalidChar(position) => {
+                write!(f, "invalid character at position {position}")
+            }
+            DecodeError::InvalidChecksum => write!(f, "invalid checksum"),
+            DecodeError::InvalidPadding => write!(f, "invalid padding"),
+            DecodeError::BufferTooSmall => write!(f, "output buffer is too small"),
+        }
+    }
+}
+
+/// Decode a Bech32 string to `result`.
+///
+/// Unlike BIP-173 there's no limit on the length of the string, as some
+/// formats like the NIP-19 entities can be longer than 90 characters.
+///
+/// Returns the Human-Readable-Part and the number of bytes written to
+/// `result`.
+pub fn decode_to_slice<'a>(s: &'a str, result: &mut [u8]) -> Result<(&'a str, usize), DecodeError> {
+    let has_lower = s.bytes().any(|c| c.is_ascii_lowercase());
+    let has_upper = s.bytes().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err(DecodeError::MixedCase);
+    }
+
+    let separator = s.rfind('1').ok_or(DecodeError::MissingSeparator)?;
+    let (hrp, data) = (&s[..separator], &s.as_bytes()[separator + 1..]);
+    if hrp.is_empty() || data.len() < CHECKSUM_LEN {
+        return Err(DecodeError::MissingSeparator);
+    }
+    if let Some(position) = hrp.bytes().position(|c| !(33..=126).contains(&c)) {
+        return Err(DecodeError::InvalidChar(position));
+    }
+
+    let mut checksum = Polymod::new(hrp);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut len = 0;
+    let payload_len = data.len() - CHECKSUM_LEN;
+    for (i, &c) in data.iter().enumerate() {
+        let value = CHARSET
+            .iter()
+            .position(|&x| x == c.to_ascii_lowercase())
+            .ok_or(DecodeError::InvalidChar(separator + 1 + i))?;
+        let value = u8::try_from(value).expect("charset has 32 characters");
+        checksum.input(value);
+
+        if i < payload_len {
+            acc = (acc << 5) | u32::from(value);
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                *result.get_mut(len).ok_or(DecodeError::BufferTooSmall)? = (acc >> bits) as u8;
+                len += 1;
+            }
+        }
+    }
+
+    if checksum.finish() != 1 {
+        return Err(DecodeError::InvalidChecksum);
+    }
+    if bits >= 5 || acc & ((1 << bits) - 1) != 0 {
+        return Err(DecodeError::InvalidPadding);
+    }
+
+    Ok((hrp, len))
+}
+
+/// The Bech32 checksum.
+struct Polymod(u32);
+
+impl Polymod {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+    fn new(hrp: &str) -> Self {
+        let mut polymod = Self(1);
+        for c in hrp.bytes() {
+            polymod.input(c.to_ascii_lowercase() >> 5);
+        }
+        polymod.input(0);
+        for c in hrp.bytes() {
+            polymod.input(c.to_ascii_lowercase() & 0x1F);
+        }
+        polymod
+    }
+
+    fn input(&mut self, value: u8) {
+        let b = self.0 >> 25;
+        self.0 = ((self.0 & 0x1FFFFFF) << 5) ^ u32::from(value);
+        for (i, generator) in Self::GENERATOR.iter().enumerate() {
+            if (b >> i) & 1 == 1 {
+                self.0 ^= generator;
+            }
+        }
+    }
+
+    fn finish(self) -> u32 {
+        self.0
+    }
+}
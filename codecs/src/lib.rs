@@ -11,3 +11,5 @@
 mod bech32;
 
 pub mod nostr;
+pub mod qr;
+pub mod seedqr;
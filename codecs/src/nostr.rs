@@ -12,15 +12,146 @@
 //!
 //! Also the functions [`encode_npub_to_fmt`] and [`encode_nsec_to_fmt`] can
 //! write directly to a [`fmt::Write`] without allocating.
+//!
+//! The TLV entities ([`Profile`], [`Event`], [`Address`] and relays) are
+//! stored in fixed size buffers, see [`MAX_RELAYS`], [`MAX_RELAY_LEN`] and
+//! [`MAX_IDENTIFIER_LEN`]. All the entities can be decoded with [`decode`].
 
 use core::fmt;
 
 use bech32::{Bech32Writer, ToBase32, Variant};
 
-use crate::bech32::bech32_len;
+pub use crate::bech32::DecodeError as Bech32Error;
+use crate::bech32::{bech32_len, decode_to_slice};
 
 const NPUB: &str = "npub";
 const NSEC: &str = "nsec";
+const NOTE: &str = "note";
+const NPROFILE: &str = "nprofile";
+const NEVENT: &str = "nevent";
+const NADDR: &str = "naddr";
+const NRELAY: &str = "nrelay";
+
+/// TLV type of the main value of an entity, its meaning depends on it.
+const TLV_SPECIAL: u8 = 0;
+/// TLV type of a relay hint.
+const TLV_RELAY: u8 = 1;
+/// TLV type of the public key of the author.
+const TLV_AUTHOR: u8 = 2;
+/// TLV type of the event kind.
+const TLV_KIND: u8 = 3;
+
+/// Maximum number of relays of an entity.
+pub const MAX_RELAYS: usize = 4;
+
+/// Maximum length of a relay URL, in bytes.
+pub const MAX_RELAY_LEN: usize = 128;
+
+/// Maximum length of the identifier of an [`Address`], in bytes.
+pub const MAX_IDENTIFIER_LEN: usize = 128;
+
+/// Maximum length of the TLV data of an entity.
+const MAX_TLV_LEN: usize =
+    (2 + MAX_IDENTIFIER_LEN) + MAX_RELAYS * (2 + MAX_RELAY_LEN) + (2 + 32) + (2 + 4);
+
+/// A relay URL.
+pub type Relay = heapless::String<MAX_RELAY_LEN>;
+
+/// The relays where an entity is likely to be found.
+pub type Relays = heapless::Vec<Relay, MAX_RELAYS>;
+
+/// A Nostr profile (`nprofile`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// The public key of the profile.
+    pub public_key: [u8; 32],
+    /// The relays where the profile is likely to be found.
+    pub relays: Relays,
+}
+
+/// A Nostr event (`nevent`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// The event identifier.
+    pub id: [u8; 32],
+    /// The relays where the event is likely to be found.
+    pub relays: Relays,
+    /// The public key of the event author.
+    pub author: Option<[u8; 32]>,
+    /// The event kind.
+    pub kind: Option<u32>,
+}
+
+/// A Nostr replaceable event coordinate (`naddr`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    /// The `d` tag of the event.
+    pub identifier: heapless::String<MAX_IDENTIFIER_LEN>,
+    /// The relays where the event is likely to be found.
+    pub relays: Relays,
+    /// The public key of the event author.
+    pub author: [u8; 32],
+    /// The event kind.
+    pub kind: u32,
+}
+
+/// A decoded NIP-19 entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entity {
+    /// A public key (`npub`).
+    PublicKey([u8; 32]),
+    /// A secret key (`nsec`).
+    SecretKey([u8; 32]),
+    /// An event identifier (`note`).
+    Note([u8; 32]),
+    /// A profile (`nprofile`).
+    Profile(Profile),
+    /// An event (`nevent`).
+    Event(Event),
+    /// A replaceable event coordinate (`naddr`).
+    Address(Address),
+    /// A relay URL (`nrelay`).
+    Relay(Relay),
+}
+
+/// Errors that can happen when decoding a NIP-19 entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The Bech32 encoding is not valid.
+    Bech32(Bech32Error),
+    /// The prefix is not a NIP-19 one.
+    UnknownPrefix,
+    /// The length of the key or identifier is not 32 bytes.
+    InvalidLength,
+    /// The TLV data is truncated.
+    InvalidTlv,
+    /// A required TLV field is missing.
+    MissingField(u8),
+    /// The value of a TLV field is not valid or too long.
+    InvalidField(u8),
+    /// There are more than [`MAX_RELAYS`] relays.
+    TooManyRelays,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Bech32(e) => write!(f, "invalid bech32: {e}"),
+            DecodeError::UnknownPrefix => write!(f, "unknown prefix"),
+            DecodeError::InvalidLength => write!(f, "invalid length"),
+            DecodeError::InvalidTlv => write!(f, "truncated TLV data"),
+            DecodeError::MissingField(t) => write!(f, "missing TLV field {t}"),
+            DecodeError::InvalidField(t) => write!(f, "invalid TLV field {t}"),
+            DecodeError::TooManyRelays => write!(f, "too many relays"),
+        }
+    }
+}
+
+impl From<Bech32Error> for DecodeError {
+    fn from(e: Bech32Error) -> Self {
+        Self::Bech32(e)
+    }
+}
 
 /// Length of an encoded `npub`.
 pub const NPUB_LEN: usize = bech32_len(NPUB, 32);
@@ -87,6 +218,181 @@ pub fn encode_nsec_to_fmt(
     encode(NSEC, public_key, fmt)
 }
 
+/// Encode a Nostr event identifier to a [`fmt::Write`].
+pub fn encode_note_to_fmt(id: &[u8; 32], fmt: &mut dyn fmt::Write) -> Result<(), fmt::Error> {
+    encode(NOTE, id, fmt)
+}
+
+/// Encode a Nostr profile to a [`fmt::Write`].
+pub fn encode_nprofile_to_fmt(
+    profile: &Profile,
+    fmt: &mut dyn fmt::Write,
+) -> Result<(), fmt::Error> {
+    let mut tlv = Tlv::new();
+    tlv.push(TLV_SPECIAL, &profile.public_key);
+    tlv.push_relays(&profile.relays);
+    encode(NPROFILE, &tlv.0, fmt)
+}
+
+/// Encode a Nostr event to a [`fmt::Write`].
+pub fn encode_nevent_to_fmt(event: &Event, fmt: &mut dyn fmt::Write) -> Result<(), fmt::Error> {
+    let mut tlv = Tlv::new();
+    tlv.push(TLV_SPECIAL, &event.id);
+    tlv.push_relays(&event.relays);
+    if let Some(author) = &event.author {
+        tlv.push(TLV_AUTHOR, author);
+    }
+    if let Some(kind) = event.kind {
+        tlv.push(TLV_KIND, &kind.to_be_bytes());
+    }
+    encode(NEVENT, &tlv.0, fmt)
+}
+
+/// Encode a Nostr replaceable event coordinate to a [`fmt::Write`].
+pub fn encode_naddr_to_fmt(address: &Address, fmt: &mut dyn fmt::Write) -> Result<(), fmt::Error> {
+    let mut tlv = Tlv::new();
+    tlv.push(TLV_SPECIAL, address.identifier.as_bytes());
+    tlv.push_relays(&address.relays);
+    tlv.push(TLV_AUTHOR, &address.author);
+    tlv.push(TLV_KIND, &address.kind.to_be_bytes());
+    encode(NADDR, &tlv.0, fmt)
+}
+
+/// Encode a Nostr relay URL to a [`fmt::Write`].
+pub fn encode_nrelay_to_fmt(relay: &Relay, fmt: &mut dyn fmt::Write) -> Result<(), fmt::Error> {
+    let mut tlv = Tlv::new();
+    tlv.push(TLV_SPECIAL, relay.as_bytes());
+    encode(NRELAY, &tlv.0, fmt)
+}
+
+/// Decode any NIP-19 entity.
+///
+/// Unknown TLV fields are ignored, as required by NIP-19.
+///
+/// # Example
+///
+/// ```
+/// # use foundation_codecs::nostr::{decode, Entity};
+/// let entity = decode("npub180cvv07tjdrrgpa0j7j7tmnyl2yr6yr7l8j4s3evf6u64th6gkwsyjh6w6").unwrap();
+/// assert!(matches!(entity, Entity::PublicKey(_)));
+/// ```
+pub fn decode(s: &str) -> Result<Entity, DecodeError> {
+    let mut data = [0; MAX_TLV_LEN];
+    let (hrp, len) = decode_to_slice(s, &mut data)?;
+    entity(hrp, &data[..len])
+}
+
+fn entity(hrp: &str, data: &[u8]) -> Result<Entity, DecodeError> {
+    let is = |prefix: &str| hrp.eq_ignore_ascii_case(prefix);
+
+    if is(NPUB) {
+        return Ok(Entity::PublicKey(key(data, DecodeError::InvalidLength)?));
+    } else if is(NSEC) {
+        return Ok(Entity::SecretKey(key(data, DecodeError::InvalidLength)?));
+    } else if is(NOTE) {
+        return Ok(Entity::Note(key(data, DecodeError::InvalidLength)?));
+    } else if !is(NPROFILE) && !is(NEVENT) && !is(NADDR) && !is(NRELAY) {
+        return Err(DecodeError::UnknownPrefix);
+    }
+
+    let mut special = None;
+    let mut relays = Relays::new();
+    let mut author = None;
+    let mut kind = None;
+    let mut i = data;
+    while !i.is_empty() {
+        let (t, value, rest) = split_tlv(i).ok_or(DecodeError::InvalidTlv)?;
+        i = rest;
+
+        match t {
+            TLV_SPECIAL => special = Some(value),
+            TLV_RELAY => relays
+                .push(string(value, TLV_RELAY)?)
+                .map_err(|_| DecodeError::TooManyRelays)?,
+            TLV_AUTHOR => author = Some(key(value, DecodeError::InvalidField(TLV_AUTHOR))?),
+            TLV_KIND => {
+                let kind_bytes = value
+                    .try_into()
+                    .map_err(|_| DecodeError::InvalidField(TLV_KIND))?;
+                kind = Some(u32::from_be_bytes(kind_bytes));
+            }
+            _ => (),
+        }
+    }
+
+    let special = special.ok_or(DecodeError::MissingField(TLV_SPECIAL))?;
+    let entity = if is(NPROFILE) {
+        Entity::Profile(Profile {
+            public_key: key(special, DecodeError::InvalidField(TLV_SPECIAL))?,
+            relays,
+        })
+    } else if is(NEVENT) {
+        Entity::Event(Event {
+            id: key(special, DecodeError::InvalidField(TLV_SPECIAL))?,
+            relays,
+            author,
+            kind,
+        })
+    } else if is(NADDR) {
+        Entity::Address(Address {
+            identifier: string(special, TLV_SPECIAL)?,
+            relays,
+            author: author.ok_or(DecodeError::MissingField(TLV_AUTHOR))?,
+            kind: kind.ok_or(DecodeError::MissingField(TLV_KIND))?,
+        })
+    } else {
+        Entity::Relay(string(special, TLV_SPECIAL)?)
+    };
+
+    Ok(entity)
+}
+
+fn key(data: &[u8], error: DecodeError) -> Result<[u8; 32], DecodeError> {
+    data.try_into().map_err(|_| error)
+}
+
+fn string<const N: usize>(data: &[u8], t: u8) -> Result<heapless::String<N>, DecodeError> {
+    let s = core::str::from_utf8(data).map_err(|_| DecodeError::InvalidField(t))?;
+    s.try_into().map_err(|_| DecodeError::InvalidField(t))
+}
+
+/// Split the TLV field at the start of `i`, returns `None` if it is
+/// truncated.
+fn split_tlv(i: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&t, i) = i.split_first()?;
+    let (&len, i) = i.split_first()?;
+    let len = usize::from(len);
+    if len > i.len() {
+        return None;
+    }
+
+    let (value, rest) = i.split_at(len);
+    Some((t, value, rest))
+}
+
+/// TLV data of an entity being encoded.
+struct Tlv(heapless::Vec<u8, MAX_TLV_LEN>);
+
+impl Tlv {
+    fn new() -> Self {
+        Self(heapless::Vec::new())
+    }
+
+    fn push(&mut self, t: u8, value: &[u8]) {
+        let len = u8::try_from(value.len()).expect("TLV values are shorter than 256 bytes");
+        self.0
+            .extend_from_slice(&[t, len])
+            .and_then(|_| self.0.extend_from_slice(value))
+            .expect("MAX_TLV_LEN should be big enough to hold the entity");
+    }
+
+    fn push_relays(&mut self, relays: &Relays) {
+        for relay in relays {
+            self.push(TLV_RELAY, relay.as_bytes());
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use foundation_test_vectors::NIP19Vector;
@@ -116,4 +422,99 @@ pub mod tests {
             assert_eq!(&encoded, &*vector.encoded);
         }
     }
+
+    #[test]
+    pub fn test_decode() {
+        for vector in NIP19Vector::new() {
+            let decoded = decode(&vector.encoded).unwrap();
+            let expected = entity(&vector.kind, &vector.bytes).unwrap();
+            assert_eq!(decoded, expected, "{}", vector.name);
+
+            let mut encoded = heapless::String::<1024>::new();
+            match &decoded {
+                Entity::PublicKey(key) => encode_npub_to_fmt(key, &mut encoded),
+                Entity::SecretKey(key) => encode_nsec_to_fmt(key, &mut encoded),
+                Entity::Profile(profile) => encode_nprofile_to_fmt(profile, &mut encoded),
+                _ => panic!("unexpected kind {}", vector.kind),
+            }
+            .unwrap();
+            assert_eq!(&encoded, &*vector.encoded, "{}", vector.name);
+        }
+    }
+
+    #[test]
+    pub fn test_roundtrip() {
+        let mut relays = Relays::new();
+        relays
+            .push("wss://relay.example.com".try_into().unwrap())
+            .unwrap();
+        relays.push("wss://nos.lol".try_into().unwrap()).unwrap();
+
+        let event = Event {
+            id: [0xAB; 32],
+            relays: relays.clone(),
+            author: Some([0xCD; 32]),
+            kind: Some(1),
+        };
+        let mut encoded = heapless::String::<1024>::new();
+        encode_nevent_to_fmt(&event, &mut encoded).unwrap();
+        assert!(encoded.starts_with("nevent1"));
+        assert_eq!(decode(&encoded), Ok(Entity::Event(event)));
+
+        let address = Address {
+            identifier: "article".try_into().unwrap(),
+            relays,
+            author: [0xEF; 32],
+            kind: 30023,
+        };
+        let mut encoded = heapless::String::<1024>::new();
+        encode_naddr_to_fmt(&address, &mut encoded).unwrap();
+        assert!(encoded.starts_with("naddr1"));
+        assert_eq!(decode(&encoded), Ok(Entity::Address(address)));
+
+        let relay = Relay::try_from("wss://relay.example.com").unwrap();
+        let mut encoded = heapless::String::<1024>::new();
+        encode_nrelay_to_fmt(&relay, &mut encoded).unwrap();
+        assert_eq!(decode(&encoded), Ok(Entity::Relay(relay)));
+
+        let mut encoded = heapless::String::<1024>::new();
+        encode_note_to_fmt(&[0x12; 32], &mut encoded).unwrap();
+        assert_eq!(decode(&encoded), Ok(Entity::Note([0x12; 32])));
+    }
+
+    #[test]
+    pub fn test_decode_errors() {
+        // An `naddr` with the author but without the kind.
+        let mut tlv = Tlv::new();
+        tlv.push(TLV_SPECIAL, b"article");
+        tlv.push(TLV_AUTHOR, &[0xEF; 32]);
+        assert_eq!(
+            entity(NADDR, &tlv.0),
+            Err(DecodeError::MissingField(TLV_KIND))
+        );
+
+        // Unknown TLV fields are ignored.
+        let mut tlv = Tlv::new();
+        tlv.push(TLV_SPECIAL, &[0x01; 32]);
+        tlv.push(0x7F, b"unknown");
+        assert!(entity(NPROFILE, &tlv.0).is_ok());
+
+        let mut relays = Tlv::new();
+        relays.push(TLV_SPECIAL, &[0x01; 32]);
+        for _ in 0..=MAX_RELAYS {
+            relays.push(TLV_RELAY, b"wss://nos.lol");
+        }
+        assert_eq!(entity(NPROFILE, &relays.0), Err(DecodeError::TooManyRelays));
+
+        assert_eq!(
+            entity(NPROFILE, &[TLV_SPECIAL, 32, 0x01]),
+            Err(DecodeError::InvalidTlv)
+        );
+        assert_eq!(entity("nfoo", &[]), Err(DecodeError::UnknownPrefix));
+        assert_eq!(entity(NPUB, &[0; 31]), Err(DecodeError::InvalidLength));
+        assert_eq!(
+            decode("npub180cvv07tjdrrgpa0j7j7tmnyl2yr6yr7l8j4s3evf6u64th6gkwsyjh6w7"),
+            Err(DecodeError::Bech32(Bech32Error::InvalidChecksum))
+        );
+    }
 }
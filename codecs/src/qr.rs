@@ -0,0 +1,118 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! # QR code capacities.
+//!
+//! Capacity of the QR code versions for each error correction level and
+//! encoding mode, as in ISO/IEC 18004, and a chooser of the smallest QR code
+//! for a payload.
+//!
+//! The capacities are the ones of [`foundation_ur::qr`].
+//!
+//! For example, a Uniform Resource in uppercase can be displayed with the
+//! alphanumeric mode, and a CompactSeedQR with the byte mode:
+//!
+//! ```
+//! # use foundation_codecs::qr::{choose, Candidate, ErrorCorrection, Mode};
+//! let ur = Candidate::new(Mode::Alphanumeric, 120);
+//! let choice = choose(&[ur], ErrorCorrection::Low).unwrap();
+//! assert_eq!(choice.version, 5);
+//!
+//! let compact_seedqr = Candidate::new(Mode::Byte, 32);
+//! let choice = choose(&[compact_seedqr], ErrorCorrection::Low).unwrap();
+//! assert_eq!(choice.version, 2);
+//! ```
+
+pub use foundation_ur::qr::{capacity, data_bits, ErrorCorrection, Mode, MAX_VERSION, MIN_VERSION};
+
+/// Returns the smallest version that fits `len` characters, if any.
+pub fn min_version(len: usize, ecc: ErrorCorrection, mode: Mode) -> Option<u8> {
+    (MIN_VERSION..=MAX_VERSION).find(|&version| capacity(version, ecc, mode) >= len)
+}
+
+/// A way to encode a payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candidate {
+    /// The encoding mode.
+    pub mode: Mode,
+    /// Length of the payload in this mode, in characters.
+    pub len: usize,
+}
+
+impl Candidate {
+    /// Construct a new [`Candidate`].
+    pub const fn new(mode: Mode, len: usize) -> Self {
+        Self { mode, len }
+    }
+}
+
+/// The smallest QR code for a payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Choice {
+    /// The QR code version.
+    pub version: u8,
+    /// The highest error correction level that fits in the version.
+    pub ecc: ErrorCorrection,
+    /// The chosen encoding.
+    pub candidate: Candidate,
+}
+
+/// Choose the smallest QR code for a payload.
+///
+/// Each candidate is a different encoding of the same payload, for example
+/// a Uniform Resource in uppercase in [`Mode::Alphanumeric`] and its CBOR
+/// in [`Mode::Byte`]. The candidate that needs the smallest version with at
+/// least `min_ecc` wins, the first one in case of a tie. The error correction
+/// level is then raised as long as the payload fits in the same version.
+///
+/// Returns `None` if none of the candidates fit in a QR code.
+pub fn choose(candidates: &[Candidate], min_ecc: ErrorCorrection) -> Option<Choice> {
+    let (version, candidate) = candidates
+        .iter()
+        .filter_map(|&c| min_version(c.len, min_ecc, c.mode).map(|version| (version, c)))
+        .min_by_key(|&(version, _)| version)?;
+
+    let ecc = ErrorCorrection::ALL
+        .into_iter()
+        .filter(|&ecc| ecc >= min_ecc && capacity(version, ecc, candidate.mode) >= candidate.len)
+        .max()
+        .unwrap_or(min_ecc);
+
+    Some(Choice {
+        version,
+        ecc,
+        candidate,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose() {
+        // A standard SeedQR of 24 words.
+        let digits = Candidate::new(Mode::Numeric, 96);
+        assert_eq!(
+            choose(&[digits], ErrorCorrection::Low),
+            Some(Choice {
+                version: 3,
+                ecc: ErrorCorrection::Medium,
+                candidate: digits,
+            })
+        );
+
+        // The binary payload wins over its bytewords.
+        let ur = Candidate::new(Mode::Alphanumeric, 20 + 2 * 300);
+        let cbor = Candidate::new(Mode::Byte, 300);
+        let choice = choose(&[ur, cbor], ErrorCorrection::Low).unwrap();
+        assert_eq!(choice.candidate, cbor);
+        assert_eq!(choice.version, 11);
+
+        assert_eq!(choose(&[], ErrorCorrection::Low), None);
+        assert_eq!(
+            choose(&[Candidate::new(Mode::Byte, 3000)], ErrorCorrection::Low),
+            None
+        );
+    }
+}
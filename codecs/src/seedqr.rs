@@ -0,0 +1,260 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! # SeedQR.
+//!
+//! This module implements the [SeedQR] formats to store a BIP-39 mnemonic
+//! in a QR code:
+//!
+//! - Standard SeedQR: the index of each word in the word list as 4 decimal
+//!   digits.
+//! - CompactSeedQR: the entropy of the mnemonic as bytes.
+//!
+//! The mnemonics are given as the indexes of their words in the BIP-39
+//! English word list, see [`foundation_bip39::lookup`]. The checksum of the
+//! mnemonic is verified in all the conversions.
+//!
+//! [SeedQR]: https://github.com/SeedSigner/seedsigner/blob/dev/docs/seed_qr/README.md
+
+use core::fmt;
+
+use foundation_bip39::{Mnemonic, MAX_ENTROPY_LEN, MAX_WORDS};
+
+/// Number of digits used for each word.
+const DIGITS_PER_WORD: usize = 4;
+
+/// Maximum length of a Standard SeedQR, in digits.
+pub const MAX_DIGITS_LEN: usize = MAX_WORDS * DIGITS_PER_WORD;
+
+/// Maximum length of a CompactSeedQR, in bytes.
+pub const MAX_COMPACT_LEN: usize = MAX_ENTROPY_LEN;
+
+/// Errors that can happen when encoding or decoding a SeedQR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The output buffer is too small.
+    BufferTooSmall {
+        /// The needed length.
+        needed: usize,
+    },
+    /// The number of digits is not a multiple of 4.
+    InvalidLength(usize),
+    /// The character at this position is not a decimal digit.
+    InvalidDigit(usize),
+    /// The mnemonic is not valid.
+    Mnemonic(foundation_bip39::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::BufferTooSmall { needed } => {
+                write!(
+                    f,
+                    "output buffer is too small, {needed} elements are needed"
+                )
+            }
+            Error::InvalidLength(len) => write!(f, "invalid number of digits: {len}"),
+            Error::InvalidDigit(position) => {
+                write!(f, "invalid digit at position {position}")
+            }
+            Error::Mnemonic(e) => write!(f, "invalid mnemonic: {e}"),
+        }
+    }
+}
+
+impl From<foundation_bip39::Error> for Error {
+    fn from(e: foundation_bip39::Error) -> Self {
+        Self::Mnemonic(e)
+    }
+}
+
+/// Encode a mnemonic as a Standard SeedQR to `result`, as ASCII digits.
+///
+/// Returns the number of digits written.
+///
+/// # Example
+///
+/// ```
+/// # use foundation_codecs::seedqr::{encode_to_slice, MAX_DIGITS_LEN};
+/// let indices = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3];
+/// let mut digits = [0; MAX_DIGITS_LEN];
+/// let len = encode_to_slice(&indices, &mut digits).unwrap();
+/// assert_eq!(&digits[..len], b"000000000000000000000000000000000000000000000003");
+/// ```
+pub fn encode_to_slice(indices: &[u16], result: &mut [u8]) -> Result<usize, Error> {
+    Mnemonic::from_indices(indices)?;
+
+    let needed = indices.len() * DIGITS_PER_WORD;
+    let result = result
+        .get_mut(..needed)
+        .ok_or(Error::BufferTooSmall { needed })?;
+
+    for (&index, digits) in indices.iter().zip(result.chunks_exact_mut(DIGITS_PER_WORD)) {
+        let mut index = index;
+        for digit in digits.iter_mut().rev() {
+            *digit = b'0' + (index % 10) as u8;
+            index /= 10;
+        }
+    }
+
+    Ok(needed)
+}
+
+/// Encode a mnemonic as a Standard SeedQR to a fixed size string buffer.
+pub fn encode(indices: &[u16]) -> Result<heapless::String<MAX_DIGITS_LEN>, Error> {
+    let mut digits = [0; MAX_DIGITS_LEN];
+    let len = encode_to_slice(indices, &mut digits)?;
+
+    let mut result = heapless::String::new();
+    for &digit in &digits[..len] {
+        result
+            .push(char::from(digit))
+            .expect("MAX_DIGITS_LEN should be big enough to hold the result");
+    }
+
+    Ok(result)
+}
+
+/// Decode a Standard SeedQR from its ASCII `digits` to `result`.
+///
+/// Returns the number of words written.
+pub fn decode_to_slice(digits: &[u8], result: &mut [u16]) -> Result<usize, Error> {
+    if digits.len() % DIGITS_PER_WORD != 0 {
+        return Err(Error::InvalidLength(digits.len()));
+    }
+
+    let needed = digits.len() / DIGITS_PER_WORD;
+    let result = result
+        .get_mut(..needed)
+        .ok_or(Error::BufferTooSmall { needed })?;
+
+    for (i, (word, index)) in digits
+        .chunks_exact(DIGITS_PER_WORD)
+        .zip(result.iter_mut())
+        .enumerate()
+    {
+        *index = 0;
+        for (j, &digit) in word.iter().enumerate() {
+            if !digit.is_ascii_digit() {
+                return Err(Error::InvalidDigit(i * DIGITS_PER_WORD + j));
+            }
+
+            *index = *index * 10 + u16::from(digit - b'0');
+        }
+    }
+
+    Mnemonic::from_indices(result)?;
+    Ok(needed)
+}
+
+/// Encode a mnemonic as a CompactSeedQR to `result`.
+///
+/// Returns the number of bytes written.
+pub fn encode_compact_to_slice(indices: &[u16], result: &mut [u8]) -> Result<usize, Error> {
+    let mnemonic = Mnemonic::from_indices(indices)?;
+    let entropy = mnemonic.entropy();
+
+    let needed = entropy.len();
+    result
+        .get_mut(..needed)
+        .ok_or(Error::BufferTooSmall { needed })?
+        .copy_from_slice(entropy);
+
+    Ok(needed)
+}
+
+/// Decode a CompactSeedQR from its `bytes` to `result`.
+///
+/// Returns the number of words written.
+pub fn decode_compact_to_slice(bytes: &[u8], result: &mut [u16]) -> Result<usize, Error> {
+    let mnemonic = Mnemonic::from_entropy(bytes)?;
+
+    let needed = mnemonic.word_count();
+    let result = result
+        .get_mut(..needed)
+        .ok_or(Error::BufferTooSmall { needed })?;
+    for (index, i) in mnemonic.indices().zip(result.iter_mut()) {
+        *i = index;
+    }
+
+    Ok(needed)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use foundation_bip39::lookup;
+    use foundation_test_vectors::SeedQRVector;
+
+    use super::*;
+
+    fn indices(vector: &SeedQRVector) -> heapless::Vec<u16, MAX_WORDS> {
+        vector
+            .seed
+            .words()
+            .map(|word| lookup(word).unwrap())
+            .collect()
+    }
+
+    #[test]
+    pub fn test_standard() {
+        for vector in SeedQRVector::new() {
+            let indices = indices(&vector);
+
+            let encoded = encode(&indices).unwrap();
+            assert_eq!(&encoded, &*vector.as_digits, "{}", vector.name);
+
+            let mut decoded = [0; MAX_WORDS];
+            let len = decode_to_slice(vector.as_digits.as_bytes(), &mut decoded).unwrap();
+            assert_eq!(&decoded[..len], &indices, "{}", vector.name);
+        }
+    }
+
+    #[test]
+    pub fn test_compact() {
+        for vector in SeedQRVector::new() {
+            let indices = indices(&vector);
+
+            let mut encoded = [0; MAX_COMPACT_LEN];
+            let len = encode_compact_to_slice(&indices, &mut encoded).unwrap();
+            assert_eq!(&encoded[..len], &vector.as_compact_bytes, "{}", vector.name);
+
+            let mut decoded = [0; MAX_WORDS];
+            let len = decode_compact_to_slice(&vector.as_compact_bytes, &mut decoded).unwrap();
+            assert_eq!(&decoded[..len], &indices, "{}", vector.name);
+        }
+    }
+
+    #[test]
+    pub fn test_errors() {
+        let mut indices = [0; MAX_WORDS];
+        assert_eq!(
+            decode_to_slice(b"00000", &mut indices),
+            Err(Error::InvalidLength(5))
+        );
+        assert_eq!(
+            decode_to_slice(b"00000a00", &mut indices),
+            Err(Error::InvalidDigit(5))
+        );
+        assert_eq!(
+            decode_to_slice(&[b'0'; 48], &mut indices),
+            Err(Error::Mnemonic(foundation_bip39::Error::InvalidChecksum))
+        );
+        assert_eq!(
+            decode_to_slice(&[b'0'; 48], &mut indices[..11]),
+            Err(Error::BufferTooSmall { needed: 12 })
+        );
+
+        let mut digits = [0; MAX_DIGITS_LEN];
+        assert_eq!(
+            encode_to_slice(&[0; 12], &mut digits),
+            Err(Error::Mnemonic(foundation_bip39::Error::InvalidChecksum))
+        );
+        assert_eq!(
+            decode_compact_to_slice(&[0; 17], &mut indices),
+            Err(Error::Mnemonic(
+                foundation_bip39::Error::InvalidEntropyLength(17)
+            ))
+        );
+    }
+}
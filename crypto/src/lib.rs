@@ -0,0 +1,193 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! # Foundation Crypto.
+//!
+//! Key derivation primitives on top of [`bitcoin_hashes`]:
+//!
+//!  - [`hmac`]: HMAC with any of the [`bitcoin_hashes`] hash functions.
+//!  - [`hkdf`]: HKDF (RFC 5869) extract and expand.
+//!  - [`pbkdf2`]: PBKDF2 (RFC 8018), as used by BIP-39 seeds.
+//!
+//! The secrets returned are wrapped in [`Zeroizing`] so they are cleared
+//! from memory when dropped.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use foundation_crypto::{hmac, pbkdf2};
+//! use bitcoin_hashes::sha512;
+//!
+//! let chain_code = hmac::<sha512::Hash>(b"Bitcoin seed", &[b"seed"]);
+//! assert_eq!(chain_code.len(), 64);
+//!
+//! let mut seed = [0; 64];
+//! pbkdf2::<sha512::Hash>(b"password", &[b"mnemonic"], 2048, &mut seed);
+//! ```
+
+#![no_std]
+
+mod zeroize;
+
+pub use bitcoin_hashes;
+
+pub use self::zeroize::{Zeroize, Zeroizing};
+
+use bitcoin_hashes::{hkdf::Hkdf, GeneralHash, Hash, HashEngine, HmacEngine, IsByteArray};
+
+/// Length of the longest hash output supported by [`pbkdf2`].
+const MAX_HASH_LEN: usize = 64;
+
+/// Calculate the HMAC of the concatenation of `data` using `key`.
+pub fn hmac<T: GeneralHash>(key: &[u8], data: &[&[u8]]) -> Zeroizing<T::Bytes>
+where
+    T::Engine: Default,
+    T::Bytes: Zeroize,
+{
+    let mut engine = HmacEngine::<T>::new(key);
+    for chunk in data {
+        engine.input(chunk);
+    }
+
+    Zeroizing::new(bitcoin_hashes::Hmac::from_engine(engine).to_byte_array())
+}
+
+/// Derive `okm.len()` bytes of key material using HKDF.
+///
+/// # Errors
+///
+/// Returns an error if `okm` is longer than 255 times the hash length.
+pub fn hkdf<T: GeneralHash>(
+    salt: &[u8],
+    ikm: &[u8],
+    info: &[u8],
+    okm: &mut [u8],
+) -> Result<(), bitcoin_hashes::hkdf::MaxLengthError>
+where
+    T::Engine: Default,
+{
+    Hkdf::<T>::new(salt, ikm).expand(info, okm)
+}
+
+/// Derive `out.len()` bytes of key material from a `password` and the
+/// concatenation of `salt` using PBKDF2 with HMAC-`T` as the pseudo-random
+/// function.
+///
+/// # Panics
+///
+/// This function panics if `rounds` is zero or if the output of `T` is
+/// longer than 64 bytes.
+pub fn pbkdf2<T: GeneralHash>(password: &[u8], salt: &[&[u8]], rounds: u32, out: &mut [u8])
+where
+    T::Engine: Default,
+    T::Bytes: Zeroize,
+{
+    assert!(rounds > 0, "PBKDF2 needs at least one round");
+    assert!(T::Bytes::LEN <= MAX_HASH_LEN);
+
+    // Keying the engine hashes the password only once.
+    let prf = HmacEngine::<T>::new(password);
+
+    for (i, block) in out.chunks_mut(T::Bytes::LEN).enumerate() {
+        let index = u32::try_from(i + 1).expect("PBKDF2 output too long");
+
+        let mut engine = prf.clone();
+        for chunk in salt {
+            engine.input(chunk);
+        }
+        engine.input(&index.to_be_bytes());
+        let mut u = Zeroizing::new(bitcoin_hashes::Hmac::<T>::from_engine(engine).to_byte_array());
+        let mut t = Zeroizing::new([0; MAX_HASH_LEN]);
+        let t = &mut t[..T::Bytes::LEN];
+        t.copy_from_slice(u.as_ref());
+
+        for _ in 1..rounds {
+            let mut engine = prf.clone();
+            engine.input(u.as_ref());
+            *u = bitcoin_hashes::Hmac::<T>::from_engine(engine).to_byte_array();
+
+            for (t, u) in t.iter_mut().zip(u.as_ref()) {
+                *t ^= u;
+            }
+        }
+
+        block.copy_from_slice(&t[..block.len()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin_hashes::{sha1, sha256, sha512};
+    use faster_hex::hex_string;
+
+    // RFC 6070.
+    #[test]
+    fn test_pbkdf2_sha1() {
+        let mut out = [0; 20];
+        pbkdf2::<sha1::Hash>(b"password", &[b"salt"], 1, &mut out);
+        assert_eq!(hex_string(&out), "0c60c80f961f0e71f3a9b524af6012062fe037a6");
+
+        pbkdf2::<sha1::Hash>(b"password", &[b"salt"], 4096, &mut out);
+        assert_eq!(hex_string(&out), "4b007901b765489abead49d926f721d065a429c1");
+
+        let mut out = [0; 25];
+        pbkdf2::<sha1::Hash>(
+            b"passwordPASSWORDpassword",
+            &[b"saltSALTsaltSALTsaltSALTsaltSALTsalt"],
+            4096,
+            &mut out,
+        );
+        assert_eq!(
+            hex_string(&out),
+            "3d2eec4fe41c849b80c8d83662c0e44a8b291a964cf2f07038"
+        );
+    }
+
+    // BIP-39 seed of "abandon abandon ... about" with the "TREZOR" passphrase.
+    #[test]
+    fn test_pbkdf2_bip39() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon \
+                        abandon abandon abandon abandon abandon about";
+        let mut seed = [0; 64];
+        pbkdf2::<sha512::Hash>(
+            mnemonic.as_bytes(),
+            &[b"mnemonic", b"TREZOR"],
+            2048,
+            &mut seed,
+        );
+        assert_eq!(
+            hex_string(&seed),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e5349553\
+             1f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+    }
+
+    // RFC 4231, test case 2.
+    #[test]
+    fn test_hmac() {
+        let mac = hmac::<sha256::Hash>(b"Jefe", &[b"what do ya want ", b"for nothing?"]);
+        assert_eq!(
+            hex_string(mac.as_ref()),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    // RFC 5869, test case 1.
+    #[test]
+    fn test_hkdf() {
+        let mut okm = [0; 42];
+        hkdf::<sha256::Hash>(
+            &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+            &[0x0b; 22],
+            &[0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9],
+            &mut okm,
+        )
+        .unwrap();
+        assert_eq!(
+            hex_string(&okm),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf\
+             34007208d5b887185865"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Clearing of secrets from memory.
+
+use core::{
+    ops::{Deref, DerefMut},
+    ptr,
+    sync::atomic::{compiler_fence, Ordering},
+};
+
+/// Types whose memory can be securely cleared.
+pub trait Zeroize {
+    /// Overwrite the value with zeroes.
+    fn zeroize(&mut self);
+}
+
+impl Zeroize for [u8] {
+    fn zeroize(&mut self) {
+        for b in self.iter_mut() {
+            // SAFETY: `b` is a valid and aligned reference. The volatile
+            // write keeps the compiler from eliding the store even though
+            // the value is never read again.
+            unsafe { ptr::write_volatile(b, 0) };
+        }
+
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl<const N: usize> Zeroize for [u8; N] {
+    fn zeroize(&mut self) {
+        self.as_mut_slice().zeroize();
+    }
+}
+
+/// Wrapper that zeroizes the inner value when dropped.
+#[derive(Clone, Default)]
+pub struct Zeroizing<T: Zeroize>(T);
+
+impl<T: Zeroize> Zeroizing<T> {
+    /// Wrap `value`.
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Zeroize> Deref for Zeroizing<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Zeroize> DerefMut for Zeroizing<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Zeroize> Drop for Zeroizing<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> core::fmt::Debug for Zeroizing<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Zeroizing(..)")
+    }
+}
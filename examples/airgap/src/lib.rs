@@ -0,0 +1,165 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! # Air-gapped payload example
+//!
+//! Shows the complete path a scanned QR code takes until it becomes a typed
+//! payload, using only the public APIs of the crates in this workspace:
+//!
+//! 1. Parse the UR string scanned from each QR code ([`UR::parse`]).
+//! 2. Fountain decode the parts until the message is complete
+//!    ([`Decoder`]).
+//! 3. Dispatch on the UR type to the registry types ([`Value::from_ur`]).
+//! 4. Validate the payload, for example deserializing a PSBT.
+//!
+//! The [`Scanner`] ties the first three steps together and [`validate`]
+//! performs the last one.
+
+use std::fmt;
+
+use bitcoin::psbt::Psbt;
+use foundation_ur::{bytewords, bytewords::Style, decoder, Decoder, ParseURError, UR};
+use foundation_urtypes::value::{self, Value};
+
+/// Receives the scanned parts of a UR.
+#[derive(Default)]
+pub struct Scanner {
+    decoder: Decoder,
+    single_part: Option<(String, Vec<u8>)>,
+}
+
+impl Scanner {
+    /// Construct a new [`Scanner`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receive a scanned UR string.
+    ///
+    /// Returns `true` when the message is complete.
+    pub fn receive(&mut self, part: &str) -> Result<bool, Error> {
+        let ur = UR::parse(part)?;
+
+        if ur.is_single_part() {
+            let bytewords = ur
+                .as_bytewords()
+                .expect("parsed resource should contain bytewords");
+            let message = bytewords::decode(bytewords, Style::Minimal)?;
+            self.single_part = Some((ur.as_type().to_string(), message));
+        } else {
+            self.decoder.receive(ur)?;
+        }
+
+        Ok(self.is_complete())
+    }
+
+    /// Returns `true` if the message is complete.
+    pub fn is_complete(&self) -> bool {
+        self.single_part.is_some() || self.decoder.is_complete()
+    }
+
+    /// Estimated percentage of completion.
+    pub fn progress(&self) -> f64 {
+        if self.single_part.is_some() {
+            1.0
+        } else {
+            self.decoder.estimated_percent_complete()
+        }
+    }
+
+    /// Returns the typed value of the message if it is complete.
+    pub fn value(&self) -> Result<Option<Value<'_>>, Error> {
+        let (ur_type, message) = match self.single_part {
+            Some((ref ur_type, ref message)) => (ur_type.as_str(), message.as_slice()),
+            None => match (self.decoder.ur_type(), self.decoder.message()?) {
+                (Some(ur_type), Some(message)) => (ur_type, message),
+                _ => return Ok(None),
+            },
+        };
+
+        Ok(Some(Value::from_ur(ur_type, message)?))
+    }
+
+    /// Clear the scanner to receive a new message.
+    pub fn clear(&mut self) {
+        self.decoder.clear();
+        self.single_part = None;
+    }
+}
+
+/// A validated payload.
+#[derive(Debug)]
+pub enum Payload {
+    /// A Partially Signed Bitcoin Transaction.
+    Psbt(Psbt),
+}
+
+/// Validate a received value.
+pub fn validate(value: &Value) -> Result<Payload, Error> {
+    match value {
+        Value::Psbt(psbt) => Ok(Payload::Psbt(Psbt::deserialize(psbt.as_bytes())?)),
+        _ => Err(Error::UnexpectedType(value.ur_type())),
+    }
+}
+
+/// Errors that can happen when receiving a payload.
+#[derive(Debug)]
+pub enum Error {
+    /// The scanned string is not a UR.
+    Parse(ParseURError),
+    /// The single-part UR payload is not valid bytewords.
+    Bytewords(bytewords::DecodeError),
+    /// The multi-part UR could not be decoded.
+    Decoder(decoder::Error),
+    /// The message is not a valid registry type.
+    Value(value::Error),
+    /// The PSBT is not valid.
+    Psbt(bitcoin::psbt::Error),
+    /// The message type is not expected.
+    UnexpectedType(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(e) => write!(f, "failed to parse UR: {e}"),
+            Error::Bytewords(e) => write!(f, "failed to decode bytewords: {e}"),
+            Error::Decoder(e) => write!(f, "failed to decode UR: {e}"),
+            Error::Value(e) => write!(f, "failed to decode value: {e}"),
+            Error::Psbt(e) => write!(f, "invalid PSBT: {e}"),
+            Error::UnexpectedType(ur_type) => write!(f, "unexpected UR type {ur_type}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseURError> for Error {
+    fn from(e: ParseURError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl From<bytewords::DecodeError> for Error {
+    fn from(e: bytewords::DecodeError) -> Self {
+        Self::Bytewords(e)
+    }
+}
+
+impl From<decoder::Error> for Error {
+    fn from(e: decoder::Error) -> Self {
+        Self::Decoder(e)
+    }
+}
+
+impl From<value::Error> for Error {
+    fn from(e: value::Error) -> Self {
+        Self::Value(e)
+    }
+}
+
+impl From<bitcoin::psbt::Error> for Error {
+    fn from(e: bitcoin::psbt::Error) -> Self {
+        Self::Psbt(e)
+    }
+}
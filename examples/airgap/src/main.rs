@@ -0,0 +1,39 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Reads scanned UR strings, one per line, from the standard input until
+//! the message is complete and prints the validated payload.
+
+use std::io::{self, BufRead};
+
+use foundation_airgap_example::{validate, Payload, Scanner};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut scanner = Scanner::new();
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if scanner.receive(line)? {
+            break;
+        }
+
+        eprintln!("{:.0}% complete", scanner.progress() * 100.0);
+    }
+
+    let value = scanner.value()?.ok_or("message is incomplete")?;
+    match validate(&value)? {
+        Payload::Psbt(psbt) => {
+            let tx = &psbt.unsigned_tx;
+            println!("txid: {}", tx.txid());
+            println!("inputs: {}", tx.input.len());
+            println!("outputs: {}", tx.output.len());
+        }
+    }
+
+    Ok(())
+}
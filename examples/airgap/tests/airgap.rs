@@ -0,0 +1,83 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use foundation_airgap_example::{validate, Error, Payload, Scanner};
+use foundation_test_vectors::psbt::TestVectors;
+use foundation_ur::Encoder;
+use foundation_urtypes::{registry::Psbt, value::Value};
+
+const MAX_FRAGMENT_LENGTH: usize = 32;
+
+#[test]
+fn psbt_multi_part() {
+    let vectors = TestVectors::new();
+
+    for vector in vectors.valid {
+        let value = Value::Psbt(Psbt(&vector.data));
+        let cbor = minicbor::to_vec(&value).unwrap();
+
+        let mut encoder = Encoder::new();
+        encoder
+            .start(value.ur_type(), &cbor, MAX_FRAGMENT_LENGTH)
+            .unwrap();
+
+        let mut scanner = Scanner::new();
+        while !scanner.receive(&encoder.next_part().to_string()).unwrap() {}
+
+        let received = scanner.value().unwrap().unwrap();
+        assert_eq!(received, value, "{}", vector.description);
+
+        let Payload::Psbt(psbt) = validate(&received).unwrap();
+        assert_eq!(psbt.serialize(), vector.data, "{}", vector.description);
+    }
+}
+
+#[test]
+fn psbt_single_part() {
+    let vectors = TestVectors::new();
+    let vector = &vectors.valid[0];
+
+    let value = Value::Psbt(Psbt(&vector.data));
+    let cbor = minicbor::to_vec(&value).unwrap();
+    let ur = foundation_ur::to_string(value.ur_type(), &cbor);
+
+    let mut scanner = Scanner::new();
+    assert!(scanner.receive(&ur).unwrap());
+    assert!(matches!(
+        validate(&scanner.value().unwrap().unwrap()),
+        Ok(Payload::Psbt(_))
+    ));
+}
+
+#[test]
+fn invalid_psbt() {
+    let vectors = TestVectors::new();
+    let vector = &vectors.invalid[0];
+
+    let value = Value::Psbt(Psbt(&vector.data));
+    let cbor = minicbor::to_vec(&value).unwrap();
+
+    let mut scanner = Scanner::new();
+    scanner
+        .receive(&foundation_ur::to_string(value.ur_type(), &cbor))
+        .unwrap();
+    assert!(matches!(
+        validate(&scanner.value().unwrap().unwrap()),
+        Err(Error::Psbt(_))
+    ));
+}
+
+#[test]
+fn unexpected_type() {
+    let value = Value::Bytes(b"not a psbt");
+    let cbor = minicbor::to_vec(&value).unwrap();
+
+    let mut scanner = Scanner::new();
+    scanner
+        .receive(&foundation_ur::to_string(value.ur_type(), &cbor))
+        .unwrap();
+    assert!(matches!(
+        validate(&scanner.value().unwrap().unwrap()),
+        Err(Error::UnexpectedType("bytes"))
+    ));
+}
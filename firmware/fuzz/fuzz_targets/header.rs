@@ -0,0 +1,13 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+#![no_main]
+
+use foundation_firmware::header;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((_, header)) = header(data) {
+        header.verify().ok();
+    }
+});
@@ -0,0 +1,35 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+#![no_main]
+
+use foundation_firmware::{header, Header, Information, Signature};
+use libfuzzer_sys::fuzz_target;
+
+fn serialize(header: &Header) -> [u8; Information::LEN + Signature::LEN] {
+    let mut buf = [0; Information::LEN + Signature::LEN];
+    buf[..Information::LEN].copy_from_slice(&header.information.serialize());
+    buf[Information::LEN..].copy_from_slice(&header.signature.serialize());
+    buf
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok((_, first)) = header(data) else {
+        return;
+    };
+
+    let serialized = serialize(&first);
+    let (rest, second) = header(&serialized[..]).expect("serialized header must parse");
+    assert!(rest.is_empty());
+
+    assert_eq!(first.information.magic, second.information.magic);
+    assert_eq!(first.information.timestamp, second.information.timestamp);
+    assert_eq!(first.information.date, second.information.date);
+    assert_eq!(first.information.version, second.information.version);
+    assert_eq!(first.information.length, second.information.length);
+    assert_eq!(first.signature.public_key1, second.signature.public_key1);
+    assert_eq!(first.signature.signature1, second.signature.signature1);
+    assert_eq!(first.signature.public_key2, second.signature.public_key2);
+    assert_eq!(first.signature.signature2, second.signature.signature2);
+    assert_eq!(serialize(&second), serialized);
+});
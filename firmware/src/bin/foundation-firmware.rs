@@ -2,10 +2,10 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use anyhow::{anyhow, bail, Context, Result};
-use bitcoin_hashes::{sha256, sha256d, HashEngine};
+use bitcoin_hashes::{sha256, HashEngine};
 use clap::{command, value_parser, Arg, ArgAction};
 use faster_hex::hex_string;
-use foundation_firmware::{header, Header, Information, HEADER_LEN};
+use foundation_firmware::{header, Header, Information, KeySet, Verifier, HEADER_LEN};
 use nom::Finish;
 use secp256k1::{global::SECP256K1, PublicKey};
 use std::{fs, path::PathBuf};
@@ -38,14 +38,24 @@ fn main() -> Result<()> {
     let file_buf = fs::read(file_name).context("failed to read firmware")?;
 
     let header_len = usize::try_from(HEADER_LEN).unwrap();
-    let header = match header(&file_buf[..header_len]).finish() {
-        Ok((_, hdr)) => hdr,
+    let (padding, header) = match header(&file_buf[..header_len]).finish() {
+        Ok(v) => v,
         Err(_) => bail!("failed to parse firmware header"),
     };
 
     print_header(&header);
 
     header.verify().context("header verification failed")?;
+    let extensions =
+        foundation_firmware::extensions(padding).context("header padding verification failed")?;
+    for extension in extensions {
+        println!(
+            "{:>17}: {:#06X} ({} bytes)",
+            "Extension",
+            extension.tag,
+            extension.value.len()
+        );
+    }
 
     if !matches.get_flag("header-only") {
         let user_public_key = matches
@@ -99,13 +109,12 @@ fn verify_signature(
 ) -> anyhow::Result<()> {
     let header_len = usize::try_from(HEADER_LEN).unwrap();
 
-    let download_hash = sha256::Hash::hash(&file_buf);
+    let download_hash = sha256::Hash::hash(file_buf);
     let build_hash = sha256::Hash::hash(&file_buf[header_len..]);
 
-    let mut engine = sha256d::Hash::engine();
-    engine.input(&header.information.serialize());
-    engine.input(&file_buf[header_len..]);
-    let validation_hash = sha256d::Hash::from_engine(engine);
+    let mut verifier = Verifier::new(header);
+    verifier.update(file_buf);
+    let validation_hash = verifier.hash();
 
     // This one is just for debugging.
     let mut engine = sha256::Hash::engine();
@@ -137,7 +146,8 @@ fn verify_signature(
     );
     println!();
 
-    foundation_firmware::verify_signature(&SECP256K1, &header, &validation_hash, user_public_key)
+    verifier
+        .finalize(SECP256K1, &KeySet::PRODUCTION, user_public_key)
         .context("firmware signature verification failed.")?;
 
     println!("Firmware signature is valid!");
@@ -163,7 +173,7 @@ fn parse_public_key(file_name: &PathBuf) -> anyhow::Result<PublicKey> {
         .map(|buf| {
             let mut pk = [0; 65];
             pk[0] = 0x04;
-            (&mut pk[1..]).copy_from_slice(&buf[24..]);
+            pk[1..].copy_from_slice(&buf[24..]);
             pk
         })
         .and_then(|buf| PublicKey::from_slice(&buf).context("failed to parse public key"))
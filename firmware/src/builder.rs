@@ -0,0 +1,405 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Construction of firmware headers.
+//!
+//! The builders validate the fields when calling `build` so that a
+//! [`Header`](crate::Header) that doesn't pass [`Header::verify`] can't be
+//! created with them.
+//!
+//! [`Header::verify`]: crate::Header::verify
+//!
+//! With the `signing` feature the [`SignatureBuilder`] can also sign the
+//! firmware, see [`SignatureBuilder::sign1`].
+
+use core::fmt;
+
+use bitcoin_hashes::{sha256d, HashEngine};
+use heapless::String;
+use secp256k1::ecdsa;
+#[cfg(feature = "signing")]
+use secp256k1::{Message, Secp256k1, SecretKey, Signing};
+
+use crate::{Information, Signature, VerifyHeaderError};
+
+/// Builder for [`Information`].
+///
+/// # Examples
+///
+/// ```
+/// use foundation_firmware::{builder::InformationBuilder, Information};
+///
+/// let information = InformationBuilder::new()
+///     .magic(Information::MAGIC_COLOR)
+///     .timestamp(1708473600)
+///     .date("Feb 21, 2024")
+///     .version("2.3.0")
+///     .length(1_000_000)
+///     .build()
+///     .unwrap();
+/// assert_eq!(information.version, "2.3.0");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct InformationBuilder<'a> {
+    magic: Option<u32>,
+    timestamp: Option<u32>,
+    date: Option<&'a str>,
+    version: Option<&'a str>,
+    length: Option<u32>,
+}
+
+impl<'a> InformationBuilder<'a> {
+    /// Construct a new [`InformationBuilder`] with no fields set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the magic bytes value.
+    pub fn magic(mut self, magic: u32) -> Self {
+        self.magic = Some(magic);
+        self
+    }
+
+    /// Set the time stamp of the firmware.
+    pub fn timestamp(mut self, timestamp: u32) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Set the date string of the firmware.
+    pub fn date(mut self, date: &'a str) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    /// Set the version string of the firmware.
+    pub fn version(mut self, version: &'a str) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Set the length of the firmware, in bytes, excluding the header.
+    pub fn length(mut self, length: u32) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Build and verify the [`Information`].
+    pub fn build(self) -> Result<Information, BuildError> {
+        let information = Information {
+            magic: self.magic.ok_or(BuildError::MissingField("magic"))?,
+            timestamp: self
+                .timestamp
+                .ok_or(BuildError::MissingField("timestamp"))?,
+            date: header_string(self.date.ok_or(BuildError::MissingField("date"))?)
+                .ok_or(BuildError::InvalidString("date"))?,
+            version: header_string(self.version.ok_or(BuildError::MissingField("version"))?)
+                .ok_or(BuildError::InvalidString("version"))?,
+            length: self.length.ok_or(BuildError::MissingField("length"))?,
+        };
+
+        information.verify()?;
+        Ok(information)
+    }
+}
+
+/// Builder for [`Signature`].
+///
+/// Both the public key indexes and the signatures must be set. For user
+/// signed firmware use [`USER_KEY`](crate::USER_KEY) as the first public
+/// key index.
+#[derive(Debug, Default, Clone)]
+pub struct SignatureBuilder {
+    public_key1: Option<u32>,
+    signature1: Option<ecdsa::Signature>,
+    public_key2: Option<u32>,
+    signature2: Option<ecdsa::Signature>,
+}
+
+impl SignatureBuilder {
+    /// Construct a new [`SignatureBuilder`] with no fields set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the first public key index and its signature.
+    pub fn signature1(mut self, public_key: u32, signature: ecdsa::Signature) -> Self {
+        self.public_key1 = Some(public_key);
+        self.signature1 = Some(signature);
+        self
+    }
+
+    /// Set the second public key index and its signature.
+    pub fn signature2(mut self, public_key: u32, signature: ecdsa::Signature) -> Self {
+        self.public_key2 = Some(public_key);
+        self.signature2 = Some(signature);
+        self
+    }
+
+    /// Sign the firmware `hash` with `secret_key` and set it as the first
+    /// signature with the `public_key` index.
+    ///
+    /// The `hash` is calculated with [`firmware_hash`].
+    #[cfg(feature = "signing")]
+    pub fn sign1<C: Signing>(
+        self,
+        secp: &Secp256k1<C>,
+        public_key: u32,
+        secret_key: &SecretKey,
+        hash: &sha256d::Hash,
+    ) -> Self {
+        self.signature1(public_key, sign(secp, secret_key, hash))
+    }
+
+    /// Sign the firmware `hash` with `secret_key` and set it as the second
+    /// signature with the `public_key` index.
+    ///
+    /// The `hash` is calculated with [`firmware_hash`].
+    #[cfg(feature = "signing")]
+    pub fn sign2<C: Signing>(
+        self,
+        secp: &Secp256k1<C>,
+        public_key: u32,
+        secret_key: &SecretKey,
+        hash: &sha256d::Hash,
+    ) -> Self {
+        self.signature2(public_key, sign(secp, secret_key, hash))
+    }
+
+    /// Build and verify the [`Signature`].
+    pub fn build(self) -> Result<Signature, BuildError> {
+        let signature = Signature {
+            public_key1: self
+                .public_key1
+                .ok_or(BuildError::MissingField("signature1"))?,
+            signature1: self
+                .signature1
+                .ok_or(BuildError::MissingField("signature1"))?,
+            public_key2: self
+                .public_key2
+                .ok_or(BuildError::MissingField("signature2"))?,
+            signature2: self
+                .signature2
+                .ok_or(BuildError::MissingField("signature2"))?,
+        };
+
+        signature.verify()?;
+        Ok(signature)
+    }
+}
+
+/// Calculate the hash of the firmware that is signed.
+///
+/// `firmware` is the firmware image without the header.
+pub fn firmware_hash(information: &Information, firmware: &[u8]) -> sha256d::Hash {
+    let mut engine = sha256d::Hash::engine();
+    engine.input(&information.serialize());
+    engine.input(firmware);
+    sha256d::Hash::from_engine(engine)
+}
+
+#[cfg(feature = "signing")]
+fn sign<C: Signing>(
+    secp: &Secp256k1<C>,
+    secret_key: &SecretKey,
+    hash: &sha256d::Hash,
+) -> ecdsa::Signature {
+    let message = Message::from_digest(hash.to_byte_array());
+    secp.sign_ecdsa(&message, secret_key)
+}
+
+/// Convert `s` to a string that fits in the header.
+///
+/// The header strings are NUL terminated so the length must be less than
+/// `N` and only ASCII characters are allowed.
+fn header_string<const N: usize>(s: &str) -> Option<String<N>> {
+    if s.len() >= N || !s.is_ascii() || s.contains('\0') {
+        return None;
+    }
+
+    String::try_from(s).ok()
+}
+
+/// Errors that can happen when building a header.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BuildError {
+    /// A required field was not set.
+    MissingField(&'static str),
+    /// The string field doesn't fit in the header, including the NUL
+    /// terminator, or contains characters other than ASCII.
+    InvalidString(&'static str),
+    /// The field values don't form a valid header.
+    Header(VerifyHeaderError),
+}
+
+impl From<VerifyHeaderError> for BuildError {
+    fn from(e: VerifyHeaderError) -> Self {
+        BuildError::Header(e)
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MissingField(field) => write!(f, "the {field} field is missing"),
+            BuildError::InvalidString(field) => {
+                write!(f, "the {field} field is too long or is not ASCII")
+            }
+            BuildError::Header(e) => write!(f, "invalid header: {e}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::Header(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Date, Information, MAX_LEN, USER_KEY};
+
+    fn valid_information() -> InformationBuilder<'static> {
+        InformationBuilder::new()
+            .magic(Information::MAGIC_MONO)
+            .timestamp(1708473600)
+            .date("Feb 21, 2024")
+            .version("2.3.0")
+            .length(MAX_LEN)
+    }
+
+    fn signature() -> ecdsa::Signature {
+        ecdsa::Signature::from_compact(&[1; 64]).unwrap()
+    }
+
+    #[test]
+    fn test_information_builder() {
+        let information = valid_information().build().unwrap();
+        assert_eq!(information.magic, Information::MAGIC_MONO);
+        assert_eq!(information.date, "Feb 21, 2024");
+
+        assert_eq!(
+            valid_information()
+                .version("123456789")
+                .build()
+                .unwrap_err(),
+            BuildError::InvalidString("version")
+        );
+        assert_eq!(
+            valid_information()
+                .date("Fév 21, 2024")
+                .build()
+                .unwrap_err(),
+            BuildError::InvalidString("date")
+        );
+        assert_eq!(
+            InformationBuilder::new().build().unwrap_err(),
+            BuildError::MissingField("magic")
+        );
+        assert_eq!(
+            valid_information().length(MAX_LEN + 1).build().unwrap_err(),
+            BuildError::Header(VerifyHeaderError::FirmwareTooBig(MAX_LEN + 1))
+        );
+        assert_eq!(
+            valid_information()
+                .date("Feb 25, 2024")
+                .build()
+                .unwrap_err(),
+            BuildError::Header(VerifyHeaderError::DateMismatch {
+                date: Date {
+                    year: 2024,
+                    month: 2,
+                    day: 25
+                },
+                timestamp: Date {
+                    year: 2024,
+                    month: 2,
+                    day: 21
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_signature_builder() {
+        SignatureBuilder::new()
+            .signature1(0, signature())
+            .signature2(3, signature())
+            .build()
+            .unwrap();
+
+        SignatureBuilder::new()
+            .signature1(USER_KEY, signature())
+            .signature2(0, signature())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            SignatureBuilder::new()
+                .signature1(0, signature())
+                .signature2(4, signature())
+                .build()
+                .unwrap_err(),
+            BuildError::Header(VerifyHeaderError::InvalidPublicKey2Index(4))
+        );
+        assert_eq!(
+            SignatureBuilder::new()
+                .signature1(1, signature())
+                .signature2(1, signature())
+                .build()
+                .unwrap_err(),
+            BuildError::Header(VerifyHeaderError::SamePublicKeys(1))
+        );
+        assert_eq!(
+            SignatureBuilder::new()
+                .signature1(1, signature())
+                .build()
+                .unwrap_err(),
+            BuildError::MissingField("signature2")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "signing")]
+    fn test_sign() {
+        use crate::{Header, KeySet, Verifier, HEADER_LEN};
+        use secp256k1::SecretKey;
+
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[0xCD; 32]).unwrap();
+
+        let firmware = [0xAB; 4096];
+        let information = valid_information()
+            .length(firmware.len() as u32)
+            .build()
+            .unwrap();
+        let hash = firmware_hash(&information, &firmware);
+        let header = Header {
+            information,
+            signature: SignatureBuilder::new()
+                .sign1(&secp, USER_KEY, &secret_key, &hash)
+                .signature2(0, signature())
+                .build()
+                .unwrap(),
+        };
+
+        let mut verifier = Verifier::new(&header);
+        verifier.update(&header.serialize());
+        verifier.update(&firmware);
+        assert_eq!(verifier.hash(), hash);
+        assert_eq!(verifier.position(), HEADER_LEN as usize + firmware.len());
+        verifier
+            .finalize(
+                &secp,
+                &KeySet::PRODUCTION,
+                Some(&secret_key.public_key(&secp)),
+            )
+            .unwrap();
+    }
+}
@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Calendar dates of the firmware header.
+
+use core::{fmt, str::FromStr};
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// A calendar date in the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Date {
+    /// The year.
+    pub year: u16,
+    /// The month, from 1 to 12.
+    pub month: u8,
+    /// The day of the month, starting from 1.
+    pub day: u8,
+}
+
+impl Date {
+    /// Construct a [`Date`] from the UTC day of a UNIX time stamp.
+    pub fn from_timestamp(timestamp: u32) -> Self {
+        // See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+        let z = i64::from(timestamp / 86400) + 719468;
+        let era = z / 146097;
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+
+    /// Number of days since the UNIX epoch.
+    pub fn days_since_epoch(&self) -> i64 {
+        // See <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5
+            + i64::from(self.day)
+            - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    fn days_in_month(year: u16, month: u8) -> u8 {
+        let is_leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        match month {
+            2 if is_leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+}
+
+/// Parses dates in the format used by the firmware header, for example
+/// `"Jan. 01, 2021"` or `"Feb 20, 2024"`.
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (month, rest) = s.split_once(' ').ok_or(ParseDateError)?;
+        let month = month.strip_suffix('.').unwrap_or(month);
+        let month = MONTHS
+            .iter()
+            .position(|&m| m == month)
+            .ok_or(ParseDateError)?;
+        let month = (month + 1) as u8;
+
+        let (day, year) = rest.split_once(", ").ok_or(ParseDateError)?;
+        let day = parse_digits::<u8>(day, 1..=2)?;
+        let year = parse_digits::<u16>(year, 4..=4)?;
+
+        if day == 0 || day > Self::days_in_month(year, month) {
+            return Err(ParseDateError);
+        }
+
+        Ok(Self { year, month, day })
+    }
+}
+
+fn parse_digits<T: FromStr>(
+    s: &str,
+    len: core::ops::RangeInclusive<usize>,
+) -> Result<T, ParseDateError> {
+    if !len.contains(&s.len()) || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseDateError);
+    }
+
+    s.parse().map_err(|_| ParseDateError)
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The date string is not in the expected format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ParseDateError;
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid date format")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseDateError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let date = |year, month, day| Date { year, month, day };
+
+        assert_eq!("Jan. 01, 2021".parse(), Ok(date(2021, 1, 1)));
+        assert_eq!("Feb 20, 2024".parse(), Ok(date(2024, 2, 20)));
+        assert_eq!("Feb 29, 2024".parse(), Ok(date(2024, 2, 29)));
+        assert_eq!("Dec. 9, 1999".parse(), Ok(date(1999, 12, 9)));
+
+        assert_eq!("Feb 29, 2023".parse::<Date>(), Err(ParseDateError));
+        assert_eq!("Jan. 00, 2021".parse::<Date>(), Err(ParseDateError));
+        assert_eq!("Foo. 01, 2021".parse::<Date>(), Err(ParseDateError));
+        assert_eq!("Jan. 01 2021".parse::<Date>(), Err(ParseDateError));
+        assert_eq!("Jan. +1, 2021".parse::<Date>(), Err(ParseDateError));
+        assert_eq!("".parse::<Date>(), Err(ParseDateError));
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let date = |year, month, day| Date { year, month, day };
+
+        assert_eq!(Date::from_timestamp(1708497781), date(2024, 2, 21));
+        assert_eq!(Date::from_timestamp(0), date(1970, 1, 1));
+        assert_eq!(Date::from_timestamp(951782400), date(2000, 2, 29));
+        assert_eq!(Date::from_timestamp(u32::MAX), date(2106, 2, 7));
+
+        for timestamp in [0, 951782400, 1708497781, u32::MAX] {
+            assert_eq!(
+                Date::from_timestamp(timestamp).days_since_epoch(),
+                i64::from(timestamp / 86400)
+            );
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Header extensions.
+//!
+//! The header bytes after the [`Signature`](crate::Signature) are unused
+//! and must be zero, except for a list of extensions at the start of it.
+//!
+//! Each extension is encoded as:
+//!
+//! | Field   | Size            | Description                  |
+//! |---------|-----------------|------------------------------|
+//! | `tag`   | 2 (LE)          | Extension tag, non-zero.     |
+//! | `len`   | 2 (LE)          | Length of `value`, in bytes. |
+//! | `value` | `len`           | Extension data.              |
+//!
+//! The list ends at the first zero tag, after it all the bytes must be
+//! zero.
+
+use crate::{Information, Signature, VerifyHeaderError, HEADER_LEN};
+
+/// Length of the header padding, where the extensions are, in bytes.
+pub const PADDING_LEN: usize = HEADER_LEN as usize - Information::LEN - Signature::LEN;
+
+/// Length of the tag and length fields of an extension, in bytes.
+const TAG_LEN_LEN: usize = 4;
+
+/// A header extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extension<'a> {
+    /// The extension tag.
+    pub tag: u16,
+    /// The extension data.
+    pub value: &'a [u8],
+}
+
+/// Iterator over the header [`Extension`]s.
+///
+/// Constructed with [`extensions`].
+#[derive(Debug, Clone)]
+pub struct Extensions<'a> {
+    i: &'a [u8],
+}
+
+impl<'a> Iterator for Extensions<'a> {
+    type Item = Extension<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (tag, value, rest) = split_extension(self.i)?;
+        self.i = rest;
+        Some(Extension { tag, value })
+    }
+}
+
+/// Verify the header padding and return the extensions in it.
+///
+/// `padding` is the header input that follows the [`Signature`], for
+/// example the remaining input returned by [`header`](crate::header).
+///
+/// # Errors
+///
+/// - [`VerifyHeaderError::InvalidExtension`] if an extension does not fit
+///   in the padding.
+/// - [`VerifyHeaderError::NonZeroPadding`] if there is a non-zero byte
+///   after the extensions.
+///
+/// The offsets in the errors are relative to the start of `padding`.
+pub fn extensions(padding: &[u8]) -> Result<Extensions<'_>, VerifyHeaderError> {
+    let mut offset = 0;
+    loop {
+        let i = &padding[offset..];
+        if i.len() < TAG_LEN_LEN || tag(i) == 0 {
+            break;
+        }
+
+        match split_extension(i) {
+            Some((_, value, _)) => offset += TAG_LEN_LEN + value.len(),
+            None => return Err(VerifyHeaderError::InvalidExtension(offset)),
+        }
+    }
+
+    if let Some(position) = padding[offset..].iter().position(|&b| b != 0) {
+        return Err(VerifyHeaderError::NonZeroPadding(offset + position));
+    }
+
+    Ok(Extensions {
+        i: &padding[..offset],
+    })
+}
+
+fn tag(i: &[u8]) -> u16 {
+    u16::from_le_bytes([i[0], i[1]])
+}
+
+/// Split the extension at the start of `i`, returns `None` if there are no
+/// more extensions or if it is truncated.
+fn split_extension(i: &[u8]) -> Option<(u16, &[u8], &[u8])> {
+    if i.len() < TAG_LEN_LEN || tag(i) == 0 {
+        return None;
+    }
+
+    let tag = tag(i);
+    let len = usize::from(u16::from_le_bytes([i[2], i[3]]));
+    let i = &i[TAG_LEN_LEN..];
+    if len > i.len() {
+        return None;
+    }
+
+    let (value, rest) = i.split_at(len);
+    Some((tag, value, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extensions() {
+        let mut padding = [0; PADDING_LEN];
+        assert_eq!(extensions(&padding).unwrap().count(), 0);
+
+        padding[..7].copy_from_slice(&[0x01, 0x00, 0x03, 0x00, 0xAA, 0xBB, 0xCC]);
+        padding[7..11].copy_from_slice(&[0x02, 0x01, 0x00, 0x00]);
+        let mut exts = extensions(&padding).unwrap();
+        assert_eq!(
+            exts.next(),
+            Some(Extension {
+                tag: 0x0001,
+                value: &[0xAA, 0xBB, 0xCC]
+            })
+        );
+        assert_eq!(
+            exts.next(),
+            Some(Extension {
+                tag: 0x0102,
+                value: &[]
+            })
+        );
+        assert_eq!(exts.next(), None);
+
+        padding[PADDING_LEN - 1] = 1;
+        assert_eq!(
+            extensions(&padding).unwrap_err(),
+            VerifyHeaderError::NonZeroPadding(PADDING_LEN - 1)
+        );
+
+        // Zero tag with a non-zero length.
+        let mut padding = [0; PADDING_LEN];
+        padding[2] = 1;
+        assert_eq!(
+            extensions(&padding).unwrap_err(),
+            VerifyHeaderError::NonZeroPadding(2)
+        );
+
+        // Truncated extension.
+        let mut padding = [0; PADDING_LEN];
+        padding[4..8].copy_from_slice(&[0x01, 0x00, 0xFF, 0xFF]);
+        padding[..4].copy_from_slice(&[0x01, 0x00, 0x00, 0x00]);
+        assert_eq!(
+            extensions(&padding).unwrap_err(),
+            VerifyHeaderError::InvalidExtension(4)
+        );
+    }
+}
@@ -10,10 +10,22 @@
 #![warn(missing_docs)]
 
 use bitcoin_hashes::sha256d;
+use core::ops::RangeFrom;
 use heapless::{String, Vec};
-use nom::IResult;
+use nom::{IResult, InputIter, InputLength, Slice};
 use secp256k1::{ecdsa, Message, PublicKey, Secp256k1, Verification};
 
+pub mod builder;
+mod date;
+mod extension;
+mod verifier;
+mod version;
+
+pub use self::date::{Date, ParseDateError};
+pub use self::extension::{extensions, Extension, Extensions, PADDING_LEN};
+pub use self::verifier::Verifier;
+pub use self::version::{ParseVersionError, Version};
+
 /// Length of the header, in bytes.
 pub const HEADER_LEN: u32 = 2048;
 
@@ -66,10 +78,132 @@ const FOUNDATION_PUBLIC_KEYS: [[u8; 65]; 4] = [
     ],
 ];
 
-/// Maximum index in the [`Signature::public_key1`] and
+/// Number of valid indexes in the [`Signature::public_key1`] and
 /// [`Signature::public_key2`] fields if it isn't an user key ([`USER_KEY`]).
 pub const MAX_PUBLIC_KEYS: u32 = FOUNDATION_PUBLIC_KEYS.len() as u32;
 
+/// A set of public keys that sign firmware, the indexes of a [`Signature`]
+/// refer to its keys.
+///
+/// Production firmware is verified with [`KeySet::PRODUCTION`], beta and
+/// developer firmware are signed with other keys that form their own
+/// [`KeySet`].
+///
+/// Keys can be revoked so that a firmware signed with a compromised key is
+/// refused even if the signature is valid, either at compile time with
+/// [`KeySet::with_revoked`] or at boot time with [`KeySet::revoke`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeySet<'a> {
+    keys: &'a [[u8; 65]],
+    revoked: u32,
+}
+
+impl KeySet<'static> {
+    /// The keys of the production firmware, see [`foundation_public_keys`].
+    pub const PRODUCTION: Self = Self::new(&FOUNDATION_PUBLIC_KEYS);
+}
+
+impl<'a> KeySet<'a> {
+    /// Maximum number of keys of a set, the revoked keys are stored in a
+    /// bitmap.
+    pub const MAX_LEN: usize = u32::BITS as usize;
+
+    /// Construct a set from uncompressed public keys, none revoked.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there are more than [`KeySet::MAX_LEN`]
+    /// keys.
+    pub const fn new(keys: &'a [[u8; 65]]) -> Self {
+        assert!(keys.len() <= Self::MAX_LEN, "too many keys");
+        Self { keys, revoked: 0 }
+    }
+
+    /// Revoke the keys of the `revoked` bitmap, bit `n` being the key with
+    /// index `n`.
+    pub const fn with_revoked(self, revoked: u32) -> Result<Self, RevokeError> {
+        // The length can't be greater than 32, see `new`.
+        let unknown = if self.keys.len() < Self::MAX_LEN {
+            revoked >> self.keys.len()
+        } else {
+            0
+        };
+        if unknown != 0 {
+            return Err(RevokeError::UnknownKey {
+                index: self.keys.len() as u32 + unknown.trailing_zeros(),
+            });
+        }
+
+        Ok(Self {
+            keys: self.keys,
+            revoked: self.revoked | revoked,
+        })
+    }
+
+    /// Revoke the key with `index`.
+    pub fn revoke(&mut self, index: u32) -> Result<(), RevokeError> {
+        if usize::try_from(index).map_or(true, |i| i >= self.keys.len()) {
+            return Err(RevokeError::UnknownKey { index });
+        }
+
+        self.revoked |= 1 << index;
+        Ok(())
+    }
+
+    /// Returns `true` if the key with `index` is revoked.
+    pub fn is_revoked(&self, index: u32) -> bool {
+        index < u32::BITS && self.revoked & (1 << index) != 0
+    }
+
+    /// Returns the bitmap of the revoked keys.
+    pub fn revoked(&self) -> u32 {
+        self.revoked
+    }
+
+    /// Returns the number of keys of the set.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the set has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the public key with `index`, or `None` if the set has no
+    /// such key or if it isn't valid.
+    ///
+    /// Revoked keys are returned too.
+    pub fn public_key(&self, index: u32) -> Option<PublicKey> {
+        let key = self.keys.get(usize::try_from(index).ok()?)?;
+        PublicKey::from_slice(key).ok()
+    }
+}
+
+/// Errors that can happen when revoking keys of a [`KeySet`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum RevokeError {
+    /// The key index is not in the set.
+    UnknownKey {
+        /// The index of the key.
+        index: u32,
+    },
+}
+
+impl core::fmt::Display for RevokeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RevokeError::UnknownKey { index } => {
+                write!(f, "public key {index} is not in the key set")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RevokeError {}
+
 /// The header of the firmware.
 #[derive(Debug)]
 pub struct Header {
@@ -82,54 +216,65 @@ pub struct Header {
 impl Header {
     /// Verify that the header is well-formed.
     pub fn verify(&self) -> Result<(), VerifyHeaderError> {
-        match self.information.magic {
-            Information::MAGIC_MONO | Information::MAGIC_COLOR => (),
-            _ => return Err(VerifyHeaderError::UnknownMagic(self.information.magic)),
-        };
-
-        if self.information.timestamp == 0 {
-            return Err(VerifyHeaderError::InvalidTimestamp);
-        }
+        self.information.verify()?;
+        self.signature.verify()
+    }
 
-        if self.information.length < HEADER_LEN {
-            return Err(VerifyHeaderError::FirmwareTooSmall(self.information.length));
-        }
+    /// Returns `true` if the firmware was signed by the user and not a
+    /// Foundation approved key.
+    ///
+    /// # See also
+    ///
+    /// - [`foundation_public_keys`].
+    pub fn is_signed_by_user(&self) -> bool {
+        self.signature.is_signed_by_user()
+    }
 
-        if self.information.length > MAX_LEN {
-            return Err(VerifyHeaderError::FirmwareTooBig(self.information.length));
+    /// Check that the firmware is not a downgrade of the `current` one.
+    ///
+    /// The firmware is a downgrade if its version is lower, or if the
+    /// versions are the same and its time stamp is older. Installing the
+    /// same firmware again is allowed.
+    ///
+    /// User signed firmware is not checked, as developers install their own
+    /// builds in any order. The user public key has to be trusted anyway.
+    pub fn is_newer_than(&self, current: &Information) -> Result<(), DowngradeError> {
+        if self.is_signed_by_user() {
+            return Ok(());
         }
 
-        if !self.is_signed_by_user() {
-            if self.signature.public_key1 > MAX_PUBLIC_KEYS {
-                return Err(VerifyHeaderError::InvalidPublicKey1Index(
-                    self.signature.public_key1,
-                ));
-            }
+        let version = self
+            .information
+            .parsed_version()
+            .map_err(|_| DowngradeError::InvalidVersion)?;
+        let current_version = current
+            .parsed_version()
+            .map_err(|_| DowngradeError::InvalidCurrentVersion)?;
 
-            if self.signature.public_key2 > MAX_PUBLIC_KEYS {
-                return Err(VerifyHeaderError::InvalidPublicKey2Index(
-                    self.signature.public_key2,
-                ));
-            }
+        if version < current_version {
+            return Err(DowngradeError::OlderVersion {
+                current: current_version,
+                version,
+            });
+        }
 
-            if self.signature.public_key1 == self.signature.public_key2 {
-                return Err(VerifyHeaderError::SamePublicKeys(
-                    self.signature.public_key1,
-                ));
-            }
+        if version == current_version && self.information.timestamp < current.timestamp {
+            return Err(DowngradeError::OlderTimestamp {
+                current: current.timestamp,
+                timestamp: self.information.timestamp,
+            });
         }
 
         Ok(())
     }
 
-    /// Returns `true` if the firmware was signed by the user and not a
-    /// Foundation approved key.
-    ///
-    /// # See also
-    ///
-    /// - [`foundation_public_keys`].
-    pub fn is_signed_by_user(&self) -> bool {
-        self.signature.public_key1 == USER_KEY
+    /// Serialize the header, the padding is filled with zeroes.
+    pub fn serialize(&self) -> [u8; HEADER_LEN as usize] {
+        let mut buf = [0; HEADER_LEN as usize];
+        buf[..Information::LEN].copy_from_slice(&self.information.serialize());
+        buf[Information::LEN..Information::LEN + Signature::LEN]
+            .copy_from_slice(&self.signature.serialize());
+        buf
     }
 }
 
@@ -156,6 +301,54 @@ impl Information {
     /// The size of this structure when serialized, in bytes.
     pub const LEN: usize = (4 * 2) + DATE_LEN + VERSION_LEN + 4;
 
+    /// Verify that the firmware information is well-formed.
+    pub fn verify(&self) -> Result<(), VerifyHeaderError> {
+        match self.magic {
+            Information::MAGIC_MONO | Information::MAGIC_COLOR => (),
+            _ => return Err(VerifyHeaderError::UnknownMagic(self.magic)),
+        };
+
+        if self.timestamp == 0 {
+            return Err(VerifyHeaderError::InvalidTimestamp);
+        }
+
+        // The date string is written in the local time of the machine that
+        // signed the firmware, so allow it to differ by one day from the
+        // time stamp, which is in UTC.
+        let date = self
+            .calendar_date()
+            .map_err(|_| VerifyHeaderError::InvalidDate)?;
+        let timestamp = self.timestamp_date();
+        if (date.days_since_epoch() - timestamp.days_since_epoch()).abs() > 1 {
+            return Err(VerifyHeaderError::DateMismatch { date, timestamp });
+        }
+
+        if self.length < HEADER_LEN {
+            return Err(VerifyHeaderError::FirmwareTooSmall(self.length));
+        }
+
+        if self.length > MAX_LEN {
+            return Err(VerifyHeaderError::FirmwareTooBig(self.length));
+        }
+
+        Ok(())
+    }
+
+    /// Parse the [`date`](Self::date) string.
+    pub fn calendar_date(&self) -> Result<Date, ParseDateError> {
+        self.date.parse()
+    }
+
+    /// Parse the [`version`](Self::version) string.
+    pub fn parsed_version(&self) -> Result<Version, ParseVersionError> {
+        self.version.parse()
+    }
+
+    /// The UTC day of the [`timestamp`](Self::timestamp).
+    pub fn timestamp_date(&self) -> Date {
+        Date::from_timestamp(self.timestamp)
+    }
+
     /// Serialize the structure.
     pub fn serialize(&self) -> [u8; Self::LEN] {
         let mut off = 0;
@@ -189,8 +382,8 @@ impl Information {
 
 /// Firmware signature information.
 ///
-/// The public key indexes are indexes of the [`foundation_public_keys`]
-/// array.
+/// The public key indexes are indexes of the keys of a [`KeySet`],
+/// [`KeySet::PRODUCTION`] for production firmware.
 #[derive(Debug)]
 pub struct Signature {
     /// The first public key index.
@@ -204,36 +397,92 @@ pub struct Signature {
 }
 
 impl Signature {
-    /// Return the first public key.
-    ///
-    /// # Panics
-    ///
-    /// This function can panic if `public_key1` is out of range.  The header
-    /// should have been verified before with [`Header::verify`].
-    pub fn public_key1(&self) -> PublicKey {
-        let public_keys = foundation_public_keys();
-        public_keys[usize::try_from(self.public_key1).unwrap()]
+    /// The size of this structure when serialized, in bytes.
+    pub const LEN: usize = (4 + 64) * 2;
+
+    /// Verify that the public key indexes are well-formed for the production
+    /// keys, see [`Signature::verify_key_set`].
+    pub fn verify(&self) -> Result<(), VerifyHeaderError> {
+        self.verify_key_set(&KeySet::PRODUCTION)
     }
 
-    /// Return the second public key.
-    ///
-    /// # Panics
-    ///
-    /// This function can panic if `public_key2` is out of range.  The header
-    /// should have been verified before with [`Header::verify`].
-    pub fn public_key2(&self) -> PublicKey {
-        let public_keys = foundation_public_keys();
-        public_keys[usize::try_from(self.public_key2).unwrap()]
+    /// Verify that the public key indexes are well-formed and are indexes of
+    /// keys of `key_set`.
+    pub fn verify_key_set(&self, key_set: &KeySet<'_>) -> Result<(), VerifyHeaderError> {
+        if !self.is_signed_by_user() {
+            let is_in_set = |index| usize::try_from(index).map_or(false, |i| i < key_set.len());
+
+            if !is_in_set(self.public_key1) {
+                return Err(VerifyHeaderError::InvalidPublicKey1Index(self.public_key1));
+            }
+
+            if !is_in_set(self.public_key2) {
+                return Err(VerifyHeaderError::InvalidPublicKey2Index(self.public_key2));
+            }
+
+            if self.public_key1 == self.public_key2 {
+                return Err(VerifyHeaderError::SamePublicKeys(self.public_key1));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the firmware was signed by the user and not a
+    /// Foundation approved key.
+    pub fn is_signed_by_user(&self) -> bool {
+        self.public_key1 == USER_KEY
+    }
+
+    /// Serialize the structure.
+    pub fn serialize(&self) -> [u8; Self::LEN] {
+        let mut off = 0;
+        let mut buf = [0; Self::LEN];
+
+        buf[off..off + 4].copy_from_slice(&self.public_key1.to_le_bytes());
+        off += 4;
+
+        buf[off..off + 64].copy_from_slice(&self.signature1.serialize_compact());
+        off += 64;
+
+        buf[off..off + 4].copy_from_slice(&self.public_key2.to_le_bytes());
+        off += 4;
+
+        buf[off..off + 64].copy_from_slice(&self.signature2.serialize_compact());
+
+        buf
+    }
+
+    /// Return the first public key from `key_set`, or `None` if the set has
+    /// no such key.
+    pub fn public_key1(&self, key_set: &KeySet<'_>) -> Option<PublicKey> {
+        key_set.public_key(self.public_key1)
+    }
+
+    /// Return the second public key from `key_set`, or `None` if the set has
+    /// no such key.
+    pub fn public_key2(&self, key_set: &KeySet<'_>) -> Option<PublicKey> {
+        key_set.public_key(self.public_key2)
     }
 }
 
 /// Errors that can happen when verifying the firmware header.
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum VerifyHeaderError {
     /// Unknown magic bytes.
     UnknownMagic(u32),
     /// The time stamp is invalid.
     InvalidTimestamp,
+    /// The date string is not valid.
+    InvalidDate,
+    /// The date string and the time stamp are not the same day.
+    DateMismatch {
+        /// The date from the date string.
+        date: Date,
+        /// The date from the time stamp.
+        timestamp: Date,
+    },
     /// The reported firmware image length is too small.
     FirmwareTooSmall(u32),
     /// The reported firmware image length is too big.
@@ -244,6 +493,10 @@ pub enum VerifyHeaderError {
     InvalidPublicKey2Index(u32),
     /// The firmware was signed with the same key for both signatures.
     SamePublicKeys(u32),
+    /// The header padding has a non-zero byte at the given offset.
+    NonZeroPadding(usize),
+    /// The header extension at the given offset does not fit in the padding.
+    InvalidExtension(usize),
 }
 
 impl core::fmt::Display for VerifyHeaderError {
@@ -253,6 +506,11 @@ impl core::fmt::Display for VerifyHeaderError {
                 write!(f, "invalid magic bytes: {magic:#010X}")
             }
             VerifyHeaderError::InvalidTimestamp => write!(f, "invalid timestamp"),
+            VerifyHeaderError::InvalidDate => write!(f, "invalid date"),
+            VerifyHeaderError::DateMismatch { date, timestamp } => write!(
+                f,
+                "date ({date}) does not match the timestamp date ({timestamp})"
+            ),
             VerifyHeaderError::FirmwareTooSmall(size) => {
                 write!(f, "firmware is too small: {size} bytes")
             }
@@ -269,6 +527,12 @@ impl core::fmt::Display for VerifyHeaderError {
                 f,
                 "the same public key ({index}) was used to sign the firmware."
             ),
+            VerifyHeaderError::NonZeroPadding(offset) => {
+                write!(f, "header padding is not zero at offset {offset}")
+            }
+            VerifyHeaderError::InvalidExtension(offset) => {
+                write!(f, "header extension at offset {offset} is truncated")
+            }
         }
     }
 }
@@ -277,7 +541,13 @@ impl core::fmt::Display for VerifyHeaderError {
 impl std::error::Error for VerifyHeaderError {}
 
 /// Parse the firmware's [`Header`].
-pub fn header(i: &[u8]) -> IResult<&[u8], Header> {
+///
+/// The input can be a byte slice or any other `nom` input of bytes, for
+/// example to parse the header directly from the flash storage.
+pub fn header<I>(i: I) -> IResult<I, Header>
+where
+    I: Clone + InputLength + InputIter<Item = u8> + Slice<RangeFrom<usize>>,
+{
     nom::combinator::map(
         nom::sequence::tuple((information, signature)),
         |(information, signature)| Header {
@@ -287,13 +557,16 @@ pub fn header(i: &[u8]) -> IResult<&[u8], Header> {
     )(i)
 }
 
-fn information(i: &[u8]) -> IResult<&[u8], Information> {
+fn information<I>(i: I) -> IResult<I, Information>
+where
+    I: Clone + InputLength + InputIter<Item = u8> + Slice<RangeFrom<usize>>,
+{
     nom::combinator::map(
         nom::sequence::tuple((
             nom::number::complete::le_u32,
             nom::number::complete::le_u32,
-            string::<_, DATE_LEN>,
-            string::<_, VERSION_LEN>,
+            string::<_, _, DATE_LEN>,
+            string::<_, _, VERSION_LEN>,
             nom::number::complete::le_u32,
         )),
         |(magic, timestamp, date, version, length)| Information {
@@ -306,7 +579,10 @@ fn information(i: &[u8]) -> IResult<&[u8], Information> {
     )(i)
 }
 
-fn signature(i: &[u8]) -> IResult<&[u8], Signature> {
+fn signature<I>(i: I) -> IResult<I, Signature>
+where
+    I: Clone + InputLength + InputIter<Item = u8> + Slice<RangeFrom<usize>>,
+{
     nom::combinator::map(
         nom::sequence::tuple((
             nom::number::complete::le_u32,
@@ -323,13 +599,14 @@ fn signature(i: &[u8]) -> IResult<&[u8], Signature> {
     )(i)
 }
 
-fn compact_signature<'a, E>(i: &'a [u8]) -> IResult<&'a [u8], ecdsa::Signature, E>
+fn compact_signature<I, E>(i: I) -> IResult<I, ecdsa::Signature, E>
 where
-    E: nom::error::ParseError<&'a [u8]> + nom::error::FromExternalError<&'a [u8], secp256k1::Error>,
+    I: Clone + InputLength + InputIter<Item = u8> + Slice<RangeFrom<usize>>,
+    E: nom::error::ParseError<I> + nom::error::FromExternalError<I, secp256k1::Error>,
 {
-    let start_input = i;
+    let start_input = i.clone();
     let mut buf = [0; 64];
-    let (i, ()) = nom::multi::fill(nom::number::complete::u8, &mut buf)(i)?;
+    let (i, ()) = fill(&mut buf)(i)?;
     ecdsa::Signature::from_compact(&buf)
         .map(|v| (i, v))
         .map_err(|e| {
@@ -341,15 +618,35 @@ where
         })
 }
 
-fn string<'a, E, const N: usize>(i: &'a [u8]) -> IResult<&'a [u8], String<N>, E>
+/// Fill `buf` with bytes from the input.
+///
+/// Like [`nom::multi::fill`] but doesn't require the input to implement
+/// [`PartialEq`].
+fn fill<I, E>(buf: &mut [u8]) -> impl FnMut(I) -> IResult<I, (), E> + '_
+where
+    I: InputLength + InputIter<Item = u8> + Slice<RangeFrom<usize>>,
+    E: nom::error::ParseError<I>,
+{
+    move |mut i: I| {
+        for b in buf.iter_mut() {
+            let (rest, v) = nom::number::complete::u8(i)?;
+            *b = v;
+            i = rest;
+        }
+
+        Ok((i, ()))
+    }
+}
+
+fn string<I, E, const N: usize>(i: I) -> IResult<I, String<N>, E>
 where
-    E: nom::error::ParseError<&'a [u8]>
-        + nom::error::FromExternalError<&'a [u8], core::str::Utf8Error>,
+    I: Clone + InputLength + InputIter<Item = u8> + Slice<RangeFrom<usize>>,
+    E: nom::error::ParseError<I> + nom::error::FromExternalError<I, core::str::Utf8Error>,
 {
-    let start_input = i;
+    let start_input = i.clone();
     let mut buf: Vec<u8, N> = Vec::new();
     buf.resize(N, 0).unwrap();
-    let (i, ()) = nom::multi::fill(nom::number::complete::u8, &mut buf)(i)?;
+    let (i, ()) = fill(&mut buf)(i)?;
 
     // Basically strlen.
     let len = buf
@@ -371,7 +668,7 @@ where
     String::from_utf8(buf)
         .map_err(|e| {
             nom::Err::Failure(E::from_external_error(
-                start_input,
+                start_input.clone(),
                 nom::error::ErrorKind::Fail,
                 e,
             ))
@@ -404,13 +701,24 @@ pub fn foundation_public_keys() -> [PublicKey; 4] {
 }
 
 /// Verifies the signature of the firmware.
+///
+/// The signatures that are not from the user must be from keys of
+/// `key_set` that are not revoked.  The header is verified first, with the
+/// public key indexes checked against `key_set`.
+///
+/// To hash and verify the firmware in chunks see [`Verifier`].
 pub fn verify_signature<C: Verification>(
     secp: &Secp256k1<C>,
     header: &Header,
     firmware_hash: &sha256d::Hash,
+    key_set: &KeySet<'_>,
     user_public_key: Option<&PublicKey>,
 ) -> Result<(), VerifySignatureError> {
-    assert!(header.verify().is_ok());
+    header
+        .information
+        .verify()
+        .and_then(|()| header.signature.verify_key_set(key_set))
+        .map_err(VerifySignatureError::InvalidHeader)?;
 
     let message = Message::from_digest(firmware_hash.to_byte_array());
 
@@ -443,18 +751,24 @@ pub fn verify_signature<C: Verification>(
             signature1.normalize_s();
             signature2.normalize_s();
 
-            header
-                .signature
-                .public_key1()
+            let public_key = |index| {
+                if key_set.is_revoked(index) {
+                    return Err(VerifySignatureError::RevokedKey { index });
+                }
+
+                key_set
+                    .public_key(index)
+                    .ok_or(VerifySignatureError::InvalidKey { index })
+            };
+
+            public_key(header.signature.public_key1)?
                 .verify(secp, &message, &signature1)
                 .map_err(|error| VerifySignatureError::FailedSignature1 {
                     index: header.signature.public_key1,
                     error,
                 })?;
 
-            header
-                .signature
-                .public_key2()
+            public_key(header.signature.public_key2)?
                 .verify(secp, &message, &signature2)
                 .map_err(|error| VerifySignatureError::FailedSignature2 {
                     index: header.signature.public_key2,
@@ -466,9 +780,56 @@ pub fn verify_signature<C: Verification>(
     }
 }
 
+/// The firmware is a downgrade of the installed one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum DowngradeError {
+    /// The version of the firmware can't be parsed.
+    InvalidVersion,
+    /// The version of the installed firmware can't be parsed.
+    InvalidCurrentVersion,
+    /// The version of the firmware is lower.
+    OlderVersion {
+        /// The version of the installed firmware.
+        current: Version,
+        /// The version of the firmware.
+        version: Version,
+    },
+    /// The versions are the same but the firmware is older.
+    OlderTimestamp {
+        /// The time stamp of the installed firmware.
+        current: u32,
+        /// The time stamp of the firmware.
+        timestamp: u32,
+    },
+}
+
+impl core::fmt::Display for DowngradeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DowngradeError::InvalidVersion => write!(f, "invalid firmware version"),
+            DowngradeError::InvalidCurrentVersion => {
+                write!(f, "invalid installed firmware version")
+            }
+            DowngradeError::OlderVersion { current, version } => write!(
+                f,
+                "firmware version {version} is older than the installed {current}"
+            ),
+            DowngradeError::OlderTimestamp { .. } => {
+                write!(f, "firmware is older than the installed one")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DowngradeError {}
+
 /// Errors that can happen when verifying the firmware signatures.
 #[derive(Debug)]
 pub enum VerifySignatureError {
+    /// The header is not valid.
+    InvalidHeader(VerifyHeaderError),
     /// The user signed firmware is not valid.
     InvalidUserSignature {
         /// The public key of the user.
@@ -492,11 +853,29 @@ pub enum VerifySignatureError {
     },
     /// The firmware was signed by the user but no user public key was found.
     MissingUserPublicKey,
+    /// The public key of the key set is not valid.
+    InvalidKey {
+        /// The index of the public key.
+        index: u32,
+    },
+    /// The public key is revoked.
+    RevokedKey {
+        /// The index of the public key.
+        index: u32,
+    },
+    /// The length of the firmware does not match the one in the header.
+    LengthMismatch {
+        /// The length of the firmware in the header.
+        expected: u32,
+        /// The length of the firmware, excluding the header.
+        length: usize,
+    },
 }
 
 impl core::fmt::Display for VerifySignatureError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
+            VerifySignatureError::InvalidHeader(error) => write!(f, "invalid header: {error}"),
             VerifySignatureError::InvalidUserSignature { .. } => {
                 write!(f, "invalid user signature")
             }
@@ -505,6 +884,16 @@ impl core::fmt::Display for VerifySignatureError {
             VerifySignatureError::MissingUserPublicKey => {
                 write!(f, "firmware is user signed but user public key is missing")
             }
+            VerifySignatureError::InvalidKey { index } => {
+                write!(f, "public key {index} of the key set is invalid")
+            }
+            VerifySignatureError::RevokedKey { index } => {
+                write!(f, "public key {index} is revoked")
+            }
+            VerifySignatureError::LengthMismatch { expected, length } => write!(
+                f,
+                "firmware length is {length} bytes but the header specifies {expected} bytes"
+            ),
         }
     }
 }
@@ -513,6 +902,7 @@ impl core::fmt::Display for VerifySignatureError {
 impl std::error::Error for VerifySignatureError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
+            VerifySignatureError::InvalidHeader(error) => Some(error),
             VerifySignatureError::InvalidUserSignature { error, .. } => Some(error),
             VerifySignatureError::FailedSignature1 { error, .. } => Some(error),
             VerifySignatureError::FailedSignature2 { error, .. } => Some(error),
@@ -535,6 +925,7 @@ mod tests {
         assert_eq!(FOUNDATION_PUBLIC_KEYS.len(), 4);
         assert_eq!(MAX_PUBLIC_KEYS, 4);
         assert_eq!(Information::LEN, 34); // Should be equal to sizeof(fw_info_t)
+        assert_eq!(Signature::LEN, 136); // Should be equal to sizeof(fw_signature_t)
     }
 
     // Just check that we don't panic.
@@ -542,4 +933,178 @@ mod tests {
     fn foundation_keys_to_typed_secp256k1_public_key() {
         foundation_public_keys();
     }
+
+    #[test]
+    fn test_key_set() {
+        assert_eq!(KeySet::PRODUCTION.len(), MAX_PUBLIC_KEYS as usize);
+        for (index, public_key) in foundation_public_keys().iter().enumerate() {
+            let index = index as u32;
+            assert_eq!(
+                KeySet::PRODUCTION.public_key(index).as_ref(),
+                Some(public_key)
+            );
+            assert!(!KeySet::PRODUCTION.is_revoked(index));
+        }
+        assert_eq!(KeySet::PRODUCTION.public_key(MAX_PUBLIC_KEYS), None);
+
+        let mut key_set = KeySet::PRODUCTION.with_revoked(0b1001).unwrap();
+        assert!(key_set.is_revoked(0) && key_set.is_revoked(3));
+        assert!(!key_set.is_revoked(1) && !key_set.is_revoked(USER_KEY));
+        key_set.revoke(1).unwrap();
+        assert_eq!(key_set.revoked(), 0b1011);
+        assert_eq!(
+            key_set.revoke(MAX_PUBLIC_KEYS),
+            Err(RevokeError::UnknownKey {
+                index: MAX_PUBLIC_KEYS
+            })
+        );
+        assert_eq!(
+            KeySet::PRODUCTION.with_revoked(0b110001),
+            Err(RevokeError::UnknownKey { index: 4 })
+        );
+
+        let keys = [[0; 65]; KeySet::MAX_LEN];
+        let mut key_set = KeySet::new(&keys).with_revoked(1 << 31).unwrap();
+        assert!(key_set.is_revoked(31));
+        assert_eq!(
+            key_set.revoke(32),
+            Err(RevokeError::UnknownKey { index: 32 })
+        );
+        assert!(!key_set.is_revoked(32));
+    }
+
+    #[test]
+    #[should_panic = "too many keys"]
+    fn test_key_set_too_many_keys() {
+        KeySet::new(&[[0; 65]; KeySet::MAX_LEN + 1]);
+    }
+
+    #[test]
+    fn test_is_newer_than() {
+        use crate::builder::{InformationBuilder, SignatureBuilder};
+
+        let information = |version, timestamp| {
+            InformationBuilder::new()
+                .magic(Information::MAGIC_COLOR)
+                .timestamp(timestamp)
+                .date("Feb 21, 2024")
+                .version(version)
+                .length(HEADER_LEN)
+                .build()
+                .unwrap()
+        };
+        let header = |version, public_key1| {
+            let signature = ecdsa::Signature::from_compact(&[1; 64]).unwrap();
+            Header {
+                information: information(version, 1708473600),
+                signature: SignatureBuilder::new()
+                    .signature1(public_key1, signature)
+                    .signature2(1, signature)
+                    .build()
+                    .unwrap(),
+            }
+        };
+
+        let current = information("2.3.0", 1708473600);
+        assert_eq!(header("2.3.1", 0).is_newer_than(&current), Ok(()));
+        assert_eq!(header("2.3.0", 0).is_newer_than(&current), Ok(()));
+        assert_eq!(
+            header("2.2.9", 0).is_newer_than(&current),
+            Err(DowngradeError::OlderVersion {
+                current: "2.3.0".parse().unwrap(),
+                version: "2.2.9".parse().unwrap(),
+            })
+        );
+        assert_eq!(header("2.2.9", USER_KEY).is_newer_than(&current), Ok(()));
+        assert_eq!(
+            header("2.3", 0).is_newer_than(&current),
+            Err(DowngradeError::InvalidVersion)
+        );
+
+        let current = information("2.3.0", 1708473601);
+        assert_eq!(
+            header("2.3.0", 0).is_newer_than(&current),
+            Err(DowngradeError::OlderTimestamp {
+                current: 1708473601,
+                timestamp: 1708473600,
+            })
+        );
+        assert_eq!(header("2.4.0", 0).is_newer_than(&current), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "signing")]
+    fn test_verify_signature_key_set() {
+        use crate::builder::{firmware_hash, InformationBuilder};
+        use secp256k1::SecretKey;
+
+        let secp = Secp256k1::new();
+        let secret_keys =
+            [0xC1, 0xC2, 0xC3, 0xC4, 0xC5, 0xC6].map(|b| SecretKey::from_slice(&[b; 32]).unwrap());
+        let keys = secret_keys.map(|k| k.public_key(&secp).serialize_uncompressed());
+        let key_set = KeySet::new(&keys);
+
+        let information = InformationBuilder::new()
+            .magic(Information::MAGIC_COLOR)
+            .timestamp(1708473600)
+            .date("Feb 21, 2024")
+            .version("2.3.0")
+            .length(HEADER_LEN + 128)
+            .build()
+            .unwrap();
+        let hash = firmware_hash(&information, &[0xAB; 128]);
+        let message = Message::from_digest(hash.to_byte_array());
+        // The indexes are out of the production range, build the signature
+        // by hand.
+        let header = Header {
+            information,
+            signature: Signature {
+                public_key1: 4,
+                signature1: secp.sign_ecdsa(&message, &secret_keys[4]),
+                public_key2: 5,
+                signature2: secp.sign_ecdsa(&message, &secret_keys[5]),
+            },
+        };
+
+        assert_eq!(
+            header.signature.public_key1(&key_set),
+            PublicKey::from_slice(&keys[4]).ok()
+        );
+        assert_eq!(
+            header.signature.public_key2(&key_set),
+            PublicKey::from_slice(&keys[5]).ok()
+        );
+        assert_eq!(header.signature.public_key1(&KeySet::PRODUCTION), None);
+
+        verify_signature(&secp, &header, &hash, &key_set, None).unwrap();
+        assert!(matches!(
+            verify_signature(
+                &secp,
+                &header,
+                &hash,
+                &key_set.with_revoked(0b100000).unwrap(),
+                None
+            ),
+            Err(VerifySignatureError::RevokedKey { index: 5 })
+        ));
+        assert!(matches!(
+            verify_signature(&secp, &header, &hash, &KeySet::new(&keys[..5]), None),
+            Err(VerifySignatureError::InvalidHeader(
+                VerifyHeaderError::InvalidPublicKey2Index(5)
+            ))
+        ));
+        assert!(matches!(
+            verify_signature(&secp, &header, &hash, &KeySet::PRODUCTION, None),
+            Err(VerifySignatureError::InvalidHeader(
+                VerifyHeaderError::InvalidPublicKey1Index(4)
+            ))
+        ));
+
+        let mut keys = keys;
+        keys.swap(4, 5);
+        assert!(matches!(
+            verify_signature(&secp, &header, &hash, &KeySet::new(&keys), None),
+            Err(VerifySignatureError::FailedSignature1 { index: 4, .. })
+        ));
+    }
 }
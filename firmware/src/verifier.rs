@@ -0,0 +1,199 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Incremental verification of firmware images.
+
+use core::fmt;
+
+use bitcoin_hashes::{sha256d, HashEngine};
+use secp256k1::{PublicKey, Secp256k1, Verification};
+
+use crate::{verify_signature, Header, KeySet, VerifySignatureError, HEADER_LEN};
+
+/// Verifies the signature of a firmware image fed in chunks.
+///
+/// The image is fed from the start, including the header. The header bytes
+/// are not part of the signed data, only the serialized
+/// [`Information`](crate::Information) and the firmware after the header
+/// are, so the first [`HEADER_LEN`] bytes are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use foundation_firmware::{header, KeySet, Verifier, HEADER_LEN};
+/// # use nom::Finish;
+/// # let image: &[u8] = &[];
+/// let (_, header) = header(&image[..HEADER_LEN as usize]).finish().unwrap();
+/// header.verify().unwrap();
+///
+/// let mut verifier = Verifier::new(&header);
+/// for chunk in image.chunks(4096) {
+///     verifier.update(chunk);
+/// }
+///
+/// let secp = secp256k1::Secp256k1::verification_only();
+/// verifier.finalize(&secp, &KeySet::PRODUCTION, None).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct Verifier<'a> {
+    header: &'a Header,
+    engine: sha256d::HashEngine,
+    position: usize,
+}
+
+impl<'a> Verifier<'a> {
+    /// Construct a new [`Verifier`] for the firmware described by `header`.
+    ///
+    /// The header should have been verified before with
+    /// [`Header::verify`].
+    pub fn new(header: &'a Header) -> Self {
+        let mut engine = sha256d::Hash::engine();
+        engine.input(&header.information.serialize());
+
+        Self {
+            header,
+            engine,
+            position: 0,
+        }
+    }
+
+    /// Feed the next bytes of the firmware image.
+    pub fn update(&mut self, mut data: &[u8]) {
+        let header_len = usize::try_from(HEADER_LEN).unwrap();
+        if self.position < header_len {
+            let skip = (header_len - self.position).min(data.len());
+            self.position += skip;
+            data = &data[skip..];
+        }
+
+        self.engine.input(data);
+        self.position += data.len();
+    }
+
+    /// Number of bytes of the image fed so far, including the header.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The hash of the firmware fed so far, as signed.
+    pub fn hash(&self) -> sha256d::Hash {
+        sha256d::Hash::from_engine(self.engine.clone())
+    }
+
+    /// Verify the firmware length and signatures.
+    ///
+    /// See [`verify_signature`] for the meaning of `key_set` and
+    /// `user_public_key`.
+    pub fn finalize<C: Verification>(
+        self,
+        secp: &Secp256k1<C>,
+        key_set: &KeySet<'_>,
+        user_public_key: Option<&PublicKey>,
+    ) -> Result<(), VerifySignatureError> {
+        let header_len = usize::try_from(HEADER_LEN).unwrap();
+        let length = self.position.saturating_sub(header_len);
+        let expected = self.header.information.length;
+        if usize::try_from(expected).map_or(true, |expected| expected != length) {
+            return Err(VerifySignatureError::LengthMismatch { expected, length });
+        }
+
+        verify_signature(secp, self.header, &self.hash(), key_set, user_public_key)
+    }
+}
+
+impl fmt::Debug for Verifier<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Verifier")
+            .field("header", self.header)
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{firmware_hash, InformationBuilder, SignatureBuilder};
+    use crate::{Information, USER_KEY};
+    use secp256k1::{Message, SecretKey};
+
+    const LENGTH: usize = 4096;
+
+    fn image() -> ([u8; HEADER_LEN as usize + LENGTH], Header, PublicKey) {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+
+        let information = InformationBuilder::new()
+            .magic(Information::MAGIC_COLOR)
+            .timestamp(1708473600)
+            .date("Feb 21, 2024")
+            .version("2.3.0")
+            .length(LENGTH as u32)
+            .build()
+            .unwrap();
+
+        let mut image = [0; HEADER_LEN as usize + LENGTH];
+        for (i, b) in image[HEADER_LEN as usize..].iter_mut().enumerate() {
+            *b = i as u8;
+        }
+
+        let hash = firmware_hash(&information, &image[HEADER_LEN as usize..]);
+        let message = Message::from_digest(hash.to_byte_array());
+        let signature = secp.sign_ecdsa(&message, &secret_key);
+
+        let header = Header {
+            information,
+            signature: SignatureBuilder::new()
+                .signature1(USER_KEY, signature)
+                .signature2(0, signature)
+                .build()
+                .unwrap(),
+        };
+        image[..HEADER_LEN as usize].copy_from_slice(&header.serialize());
+
+        (image, header, secret_key.public_key(&secp))
+    }
+
+    #[test]
+    fn test_verifier() {
+        let secp = Secp256k1::verification_only();
+        let (mut image, header, public_key) = image();
+
+        for chunk_len in [1, 100, 2048, 4096, image.len()] {
+            let mut verifier = Verifier::new(&header);
+            for chunk in image.chunks(chunk_len) {
+                verifier.update(chunk);
+            }
+            assert_eq!(verifier.position(), image.len());
+            verifier
+                .finalize(&secp, &KeySet::PRODUCTION, Some(&public_key))
+                .unwrap();
+        }
+
+        // The header bytes are not signed.
+        image[HEADER_LEN as usize - 1] = 0xFF;
+        let mut verifier = Verifier::new(&header);
+        verifier.update(&image);
+        verifier
+            .finalize(&secp, &KeySet::PRODUCTION, Some(&public_key))
+            .unwrap();
+
+        let mut verifier = Verifier::new(&header);
+        verifier.update(&image[..image.len() - 1]);
+        assert!(matches!(
+            verifier.finalize(&secp, &KeySet::PRODUCTION, Some(&public_key)),
+            Err(VerifySignatureError::LengthMismatch {
+                expected: 4096,
+                length: 4095
+            })
+        ));
+
+        image[HEADER_LEN as usize] ^= 1;
+        let mut verifier = Verifier::new(&header);
+        verifier.update(&image);
+        assert!(matches!(
+            verifier.finalize(&secp, &KeySet::PRODUCTION, Some(&public_key)),
+            Err(VerifySignatureError::InvalidUserSignature { .. })
+        ));
+    }
+}
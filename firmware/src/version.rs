@@ -0,0 +1,93 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Versions of the firmware header.
+
+use core::{fmt, str::FromStr};
+
+/// A firmware version, compared component by component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Version {
+    /// The major version.
+    pub major: u16,
+    /// The minor version.
+    pub minor: u16,
+    /// The patch version.
+    pub patch: u16,
+}
+
+/// Parses versions in the format used by the firmware header, for example
+/// `"2.3.0"`.
+impl FromStr for Version {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut components = s.split('.').map(|c| {
+            if c.is_empty() || !c.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(ParseVersionError);
+            }
+
+            c.parse::<u16>().map_err(|_| ParseVersionError)
+        });
+
+        let major = components.next().ok_or(ParseVersionError)??;
+        let minor = components.next().ok_or(ParseVersionError)??;
+        let patch = components.next().ok_or(ParseVersionError)??;
+        if components.next().is_some() {
+            return Err(ParseVersionError);
+        }
+
+        Ok(Self {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The version string is not in the expected format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ParseVersionError;
+
+impl fmt::Display for ParseVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid version format")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseVersionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let version = |major, minor, patch| Version {
+            major,
+            minor,
+            patch,
+        };
+
+        assert_eq!("2.3.0".parse(), Ok(version(2, 3, 0)));
+        assert_eq!("10.0.12".parse(), Ok(version(10, 0, 12)));
+
+        assert_eq!("2.3".parse::<Version>(), Err(ParseVersionError));
+        assert_eq!("2.3.0.1".parse::<Version>(), Err(ParseVersionError));
+        assert_eq!("2..0".parse::<Version>(), Err(ParseVersionError));
+        assert_eq!("v2.3.0".parse::<Version>(), Err(ParseVersionError));
+        assert_eq!("2.+3.0".parse::<Version>(), Err(ParseVersionError));
+        assert_eq!("".parse::<Version>(), Err(ParseVersionError));
+
+        assert!(version(2, 10, 0) > version(2, 9, 1));
+    }
+}
@@ -1,17 +1,84 @@
 // SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundationdevices.com>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use foundation_firmware::{header, VerifyHeaderError};
+use embedded_storage_nom::{
+    rc::{Rc, RcInner},
+    Bytes,
+};
+use foundation_firmware::{
+    extensions, header, Date, Information, Signature, VerifyHeaderError, HEADER_LEN, PADDING_LEN,
+};
 use foundation_test_vectors::firmware::{
     INVALID_MAGIC, INVALID_MAX_LENGTH, INVALID_MIN_LENGTH, INVALID_PUBLIC_KEY1,
     INVALID_PUBLIC_KEY2, INVALID_TIMESTAMP, VALID_HEADER,
 };
 use nom::Finish;
+use std::{cell::RefCell, ptr::NonNull};
 
 #[test]
 pub fn valid_header() {
-    let (_, header) = header(VALID_HEADER).finish().unwrap();
+    let (padding, header) = header(VALID_HEADER).finish().unwrap();
     header.verify().unwrap();
+    assert_eq!(padding.len(), PADDING_LEN);
+    assert_eq!(extensions(padding).unwrap().count(), 0);
+}
+
+#[test]
+pub fn valid_header_from_storage() {
+    #[derive(Debug)]
+    struct Flash(&'static [u8]);
+
+    impl embedded_storage::nor_flash::ErrorType for Flash {
+        type Error = core::convert::Infallible;
+    }
+
+    impl embedded_storage::nor_flash::ReadNorFlash for Flash {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = usize::try_from(offset).unwrap();
+            bytes.copy_from_slice(&self.0[offset..offset + bytes.len()]);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    let inner = Box::leak(Box::new(RcInner::new(RefCell::new(Flash(VALID_HEADER)))));
+    // SAFETY: the inner value is leaked so it is never de-allocated.
+    let storage = unsafe { Rc::from_inner(NonNull::from(inner)) };
+    let bytes = Bytes::<_, 64>::new(0, VALID_HEADER.len(), storage).unwrap();
+
+    let (padding, header) = header(bytes).finish().unwrap();
+    header.verify().unwrap();
+    assert_eq!(padding.len(), PADDING_LEN);
+    assert_eq!(header.serialize()[..], VALID_HEADER[..]);
+}
+
+#[test]
+pub fn non_zero_padding() {
+    let mut buf = VALID_HEADER.to_vec();
+    buf[HEADER_LEN as usize - 1] = 1;
+    let (padding, _) = header(buf.as_slice()).finish().unwrap();
+    assert_eq!(
+        extensions(padding).unwrap_err(),
+        VerifyHeaderError::NonZeroPadding(PADDING_LEN - 1)
+    );
+}
+
+#[test]
+pub fn serialize_roundtrip() {
+    let (_, header) = header(VALID_HEADER).finish().unwrap();
+    let information = header.information.serialize();
+    let signature = header.signature.serialize();
+    assert_eq!(information[..], VALID_HEADER[..Information::LEN]);
+    assert_eq!(
+        signature[..],
+        VALID_HEADER[Information::LEN..Information::LEN + Signature::LEN]
+    );
+    assert_eq!(header.serialize()[..], VALID_HEADER[..]);
 }
 
 #[test]
@@ -61,3 +128,36 @@ pub fn invalid_timestamp() {
     let (_, header) = header(INVALID_TIMESTAMP).finish().unwrap();
     assert_eq!(header.verify(), Err(VerifyHeaderError::InvalidTimestamp));
 }
+
+#[test]
+pub fn date_mismatch() {
+    let (_, mut header) = header(VALID_HEADER).finish().unwrap();
+    assert_eq!(
+        header.information.calendar_date(),
+        Ok(Date {
+            year: 2024,
+            month: 2,
+            day: 20
+        })
+    );
+
+    header.information.date = "Feb 23, 2024".try_into().unwrap();
+    assert_eq!(
+        header.verify(),
+        Err(VerifyHeaderError::DateMismatch {
+            date: Date {
+                year: 2024,
+                month: 2,
+                day: 23
+            },
+            timestamp: Date {
+                year: 2024,
+                month: 2,
+                day: 21
+            },
+        })
+    );
+
+    header.information.date = "20/02/2024".try_into().unwrap();
+    assert_eq!(header.verify(), Err(VerifyHeaderError::InvalidDate));
+}
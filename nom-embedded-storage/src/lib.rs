@@ -8,15 +8,19 @@
 //!
 //! Ideally this should be implemented for `embedded-io` traits but for the
 //! sake of simplicity for Passport we just use [`embedded_storage`].
+//!
+//! Any [`ReadNorFlash`](embedded_storage::nor_flash::ReadNorFlash) can be
+//! used directly, devices with a different addressing scheme can implement
+//! the [`Storage`] trait, see the [`storage`] module.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use core::{
     cell::RefCell,
+    fmt,
     iter::Enumerate,
-    ops::{Range, RangeFrom, RangeFull, RangeTo},
+    ops::{Bound, Range, RangeBounds, RangeFrom, RangeFull, RangeTo},
 };
-use embedded_storage::nor_flash::ReadNorFlash;
 use heapless::Vec;
 use nom::{
     Compare, CompareResult, FindSubstring, FindToken, InputIter, InputLength, InputTake, Needed,
@@ -24,8 +28,10 @@ use nom::{
 };
 
 pub mod rc;
+pub mod storage;
 
 use crate::rc::Rc;
+pub use crate::storage::Storage;
 
 /// A byte slice in the NOR flash storage.
 #[derive(Debug)]
@@ -38,27 +44,38 @@ pub struct Bytes<S, const N: usize> {
 
 impl<S, const N: usize> Bytes<S, N>
 where
-    S: ReadNorFlash,
+    S: Storage,
 {
     /// Create a byte slice from `storage` of `len` bytes at `offset`.
     ///
     /// # Return value
     ///
-    /// If `offset` combined with `len` is past the capacity of `storage`
-    /// an error is returned.
+    /// If `offset` combined with `len` can't be addressed by `storage` or
+    /// is past its capacity an error is returned.
     pub fn new(offset: usize, len: usize, storage: Rc<RefCell<S>>) -> Result<Self, Error> {
         // We expect to read at least one byte from the flash.
         if S::READ_SIZE > 1 {
             return Err(Error::UnsupportedReadSize);
         }
 
+        let end = match offset.checked_add(len) {
+            Some(end) if S::is_addressable(end) => end,
+            _ => {
+                return Err(Error::AddressOverflow {
+                    offset,
+                    len,
+                    address_bits: S::ADDRESS_BITS,
+                })
+            }
+        };
+
         let capacity = if let Ok(s) = storage.try_borrow() {
             s.capacity()
         } else {
             return Err(Error::AlreadyBorrowed);
         };
 
-        if offset + len > capacity {
+        if end > capacity {
             return Err(Error::OutOfBounds {
                 offset,
                 len,
@@ -83,15 +100,10 @@ where
             let mut buffer = self.buffer.borrow_mut();
             buffer.clear();
             buffer
-                .resize(self.len().min(N), 0)
+                .resize((self.len() - pos).min(N), 0)
                 .expect("size should be less than or equal to N");
 
-            let offset = match u32::try_from(self.offset + pos) {
-                Ok(v) => v,
-                Err(_) => return None,
-            };
-
-            if let Err(_) = self.storage.borrow_mut().read(offset, &mut buffer) {
+            if self.read(pos, &mut buffer).is_err() {
                 return None;
             }
 
@@ -101,11 +113,22 @@ where
                 return Some(pos + byte_position);
             }
 
-            pos += self.len().min(N);
+            pos += buffer.len();
         }
 
         None
     }
+
+    /// Read `buffer.len()` bytes at `pos` relative to the start of the
+    /// slice.
+    ///
+    /// The address is always valid for the storage as it is checked when
+    /// the [`Bytes`] is created.
+    fn read(&self, pos: usize, buffer: &mut [u8]) -> Result<(), S::Error> {
+        debug_assert!(pos + buffer.len() <= self.len());
+
+        self.storage.borrow_mut().read(self.offset + pos, buffer)
+    }
 }
 
 impl<S, const N: usize> Bytes<S, N> {
@@ -117,6 +140,87 @@ impl<S, const N: usize> Bytes<S, N> {
         self.len() == 0
     }
 
+    /// Returns the first `count` bytes.
+    ///
+    /// Like [`InputTake::take`] but returns an error instead of panicking
+    /// if `count` is past the length.
+    pub fn try_take(&self, count: usize) -> Result<Self, Error> {
+        self.try_slice(..count)
+    }
+
+    /// Split the bytes at `count`, returning the suffix and the prefix.
+    ///
+    /// Like [`InputTake::take_split`] but returns an error instead of
+    /// panicking if `count` is past the length.
+    pub fn try_take_split(&self, count: usize) -> Result<(Self, Self), Error> {
+        let prefix = self.try_slice(..count)?;
+        let suffix = self.slice_unchecked(count, self.len());
+        Ok((suffix, prefix))
+    }
+
+    /// Returns the bytes in `range`.
+    ///
+    /// Like [`Slice::slice`] but returns an error instead of panicking if
+    /// the range is past the length.
+    pub fn try_slice<R>(&self, range: R) -> Result<Self, Error>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len(),
+        };
+
+        if start > end || end > self.len() {
+            return Err(Error::OutOfRange {
+                start,
+                end,
+                len: self.len(),
+            });
+        }
+
+        Ok(self.slice_unchecked(start, end))
+    }
+
+    /// Slice `start..end`, the range must be valid.
+    fn slice_unchecked(&self, start: usize, end: usize) -> Self {
+        debug_assert!(start <= end && end <= self.len());
+
+        Self {
+            offset: self.offset + start,
+            len: end - start,
+            storage: Rc::clone(&self.storage),
+            buffer: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Handle an out of range slice in the [`nom`] traits, which can't
+    /// return errors.
+    ///
+    /// Panics unless the `saturating` feature is enabled, in that case the
+    /// range is clamped to the length so that the parser fails instead with
+    /// an error as the input is too short.
+    #[cfg_attr(not(feature = "saturating"), allow(unused_variables))]
+    fn out_of_range(&self, error: Error, start: usize, end: usize) -> Self {
+        #[cfg(not(feature = "saturating"))]
+        panic!("{error}");
+
+        #[cfg(feature = "saturating")]
+        {
+            #[cfg(feature = "log")]
+            log::warn!("{error}, clamping");
+
+            let start = start.min(self.len());
+            self.slice_unchecked(start, end.clamp(start, self.len()))
+        }
+    }
+
     /// Return an iterator over [`Bytes`].
     pub fn iter(&self) -> BytesIter<S, N> {
         BytesIter {
@@ -127,6 +231,7 @@ impl<S, const N: usize> Bytes<S, N> {
                 buffer: RefCell::new(Vec::new()),
             },
             pos: 0,
+            cached: 0,
         }
     }
 }
@@ -143,38 +248,52 @@ impl<S, const N: usize> Clone for Bytes<S, N> {
 }
 
 /// An iterator over [`Bytes`].
+///
+/// Up to `N` bytes are read ahead from the storage at once, the following
+/// calls to [`next`](Iterator::next) are served from the buffer.
 #[derive(Debug)]
 pub struct BytesIter<S, const N: usize> {
     inner: Bytes<S, N>,
     pos: usize,
+    /// Position of the first byte of the buffer.
+    cached: usize,
 }
 
 impl<S, const N: usize> Iterator for BytesIter<S, N>
 where
-    S: ReadNorFlash,
+    S: Storage,
 {
     type Item = u8;
 
-    // TODO: Optimize this by pre-fetching N bytes when needed.
     fn next(&mut self) -> Option<Self::Item> {
         if self.pos >= self.inner.len() {
             return None;
         }
 
-        let mut buf = [0; 1];
-        let mut storage = self.inner.storage.borrow_mut();
-        let offset = match u32::try_from(self.inner.offset + self.pos) {
-            Ok(v) => v,
-            Err(_) => return None,
-        };
+        if N == 0 {
+            let mut buf = [0; 1];
+            self.inner.read(self.pos, &mut buf).ok()?;
+            self.pos += 1;
+            return Some(buf[0]);
+        }
+
+        let mut buffer = self.inner.buffer.borrow_mut();
+        if self.pos - self.cached >= buffer.len() {
+            buffer.clear();
+            buffer
+                .resize((self.inner.len() - self.pos).min(N), 0)
+                .expect("size should be less than or equal to N");
 
-        match storage.read(offset, &mut buf) {
-            Ok(_) => {
-                self.pos += 1;
-                Some(buf[0])
+            if self.inner.read(self.pos, &mut buffer).is_err() {
+                buffer.clear();
+                return None;
             }
-            Err(_) => None,
+            self.cached = self.pos;
         }
+
+        let byte = buffer[self.pos - self.cached];
+        self.pos += 1;
+        Some(byte)
     }
 }
 
@@ -182,14 +301,57 @@ where
 #[derive(Debug)]
 pub enum Error {
     AlreadyBorrowed,
+    /// The range can't be addressed by the storage device.
+    AddressOverflow {
+        offset: usize,
+        len: usize,
+        address_bits: u32,
+    },
     OutOfBounds {
         offset: usize,
         len: usize,
         capacity: usize,
     },
     UnsupportedReadSize,
+    /// The range is past the length of the [`Bytes`].
+    OutOfRange {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::AlreadyBorrowed => write!(f, "storage is already borrowed"),
+            Error::AddressOverflow {
+                offset,
+                len,
+                address_bits,
+            } => write!(
+                f,
+                "{len} bytes at offset {offset} can't be addressed with {address_bits} bits"
+            ),
+            Error::OutOfBounds {
+                offset,
+                len,
+                capacity,
+            } => write!(
+                f,
+                "{len} bytes at offset {offset} are past the capacity ({capacity} bytes)"
+            ),
+            Error::UnsupportedReadSize => write!(f, "unsupported read size"),
+            Error::OutOfRange { start, end, len } => {
+                write!(f, "tried to slice {start}..{end}, but the length is {len}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 impl<S, const N: usize> InputLength for Bytes<S, N> {
     fn input_len(&self) -> usize {
         self.len()
@@ -198,44 +360,22 @@ impl<S, const N: usize> InputLength for Bytes<S, N> {
 
 impl<S, const N: usize> InputTake for Bytes<S, N> {
     fn take(&self, count: usize) -> Self {
-        if count > self.len() {
-            panic!("tried to take {count}, but the length is {}", self.len());
-        }
-
-        Self {
-            offset: self.offset,
-            len: count,
-            storage: Rc::clone(&self.storage),
-            buffer: RefCell::new(Vec::new()),
-        }
+        self.try_take(count)
+            .unwrap_or_else(|e| self.out_of_range(e, 0, count))
     }
 
     fn take_split(&self, count: usize) -> (Self, Self) {
-        if count > self.len() {
-            panic!("tried to take {count}, but the length is {}", self.len());
-        }
-
-        let prefix = Self {
-            offset: self.offset,
-            len: count,
-            storage: Rc::clone(&self.storage),
-            buffer: RefCell::new(Vec::new()),
-        };
-
-        let suffix = Self {
-            offset: self.offset + count,
-            len: self.len - count,
-            storage: Rc::clone(&self.storage),
-            buffer: RefCell::new(Vec::new()),
-        };
-
-        (prefix, suffix)
+        self.try_take_split(count).unwrap_or_else(|e| {
+            let prefix = self.out_of_range(e, 0, count);
+            let suffix = self.slice_unchecked(prefix.len(), self.len());
+            (suffix, prefix)
+        })
     }
 }
 
 impl<S, const N: usize> InputIter for Bytes<S, N>
 where
-    S: ReadNorFlash,
+    S: Storage,
 {
     type Item = u8;
     type Iter = Enumerate<BytesIter<S, N>>;
@@ -267,95 +407,34 @@ where
 
 impl<S, const N: usize> Slice<Range<usize>> for Bytes<S, N> {
     fn slice(&self, range: Range<usize>) -> Self {
-        if range.is_empty() {
-            return Self {
-                offset: self.offset,
-                len: 0,
-                storage: Rc::clone(&self.storage),
-                buffer: RefCell::new(Vec::new()),
-            };
-        }
-
-        let new_len = range.end - range.start;
-        if new_len > self.len() {
-            panic!(
-                "tried to slice past the length, start {}, end {}, length {}",
-                range.start,
-                range.end,
-                self.len(),
-            );
-        }
-
-        Self {
-            offset: self.offset + range.start,
-            len: new_len,
-            storage: Rc::clone(&self.storage),
-            buffer: RefCell::new(Vec::new()),
-        }
+        self.try_slice(range.clone())
+            .unwrap_or_else(|e| self.out_of_range(e, range.start, range.end))
     }
 }
 
 impl<S, const N: usize> Slice<RangeTo<usize>> for Bytes<S, N> {
     fn slice(&self, range: RangeTo<usize>) -> Self {
-        if range.end > self.len() {
-            panic!(
-                "tried to take {}, but the length is {}",
-                range.end,
-                self.len()
-            );
-        }
-
-        Self {
-            offset: self.offset,
-            len: range.end,
-            storage: Rc::clone(&self.storage),
-            buffer: RefCell::new(Vec::new()),
-        }
+        self.try_slice(range)
+            .unwrap_or_else(|e| self.out_of_range(e, 0, range.end))
     }
 }
 
-impl<S, const N: usize> Slice<RangeFrom<usize>> for Bytes<S, N>
-where
-    S: ReadNorFlash,
-{
+impl<S, const N: usize> Slice<RangeFrom<usize>> for Bytes<S, N> {
     fn slice(&self, range: RangeFrom<usize>) -> Self {
-        let new_offset = self.offset + range.start;
-        if new_offset >= self.storage.borrow().capacity() {
-            panic!(
-                "tried to slice past the capacity, starting point is {}, capacity is {}",
-                new_offset,
-                self.storage.borrow().capacity(),
-            );
-        }
-
-        let new_len = self.len - range.start;
-        if new_len > self.len {
-            panic!("tried to take {new_len}, but the length is {}", self.len());
-        }
-
-        Self {
-            offset: new_offset,
-            len: new_len,
-            storage: Rc::clone(&self.storage),
-            buffer: RefCell::new(Vec::new()),
-        }
+        self.try_slice(range.clone())
+            .unwrap_or_else(|e| self.out_of_range(e, range.start, self.len()))
     }
 }
 
 impl<S, const N: usize> Slice<RangeFull> for Bytes<S, N> {
     fn slice(&self, _: RangeFull) -> Self {
-        Self {
-            offset: self.offset,
-            len: self.len,
-            storage: Rc::clone(&self.storage),
-            buffer: RefCell::new(Vec::new()),
-        }
+        self.slice_unchecked(0, self.len())
     }
 }
 
 impl<'a, S, const N: usize> Compare<&'a [u8]> for Bytes<S, N>
 where
-    S: ReadNorFlash,
+    S: Storage,
 {
     fn compare(&self, t: &'a [u8]) -> CompareResult {
         if t.len() > self.len() {
@@ -374,12 +453,7 @@ where
                 .resize(chunk.len(), 0)
                 .expect("chunk size should be less than or equal to N");
 
-            let offset = match u32::try_from(self.offset + pos) {
-                Ok(v) => v,
-                Err(_) => return CompareResult::Error,
-            };
-
-            if let Err(_) = self.storage.borrow_mut().read(offset, &mut buffer) {
+            if self.read(pos, &mut buffer).is_err() {
                 return CompareResult::Error;
             }
             pos += chunk.len();
@@ -409,12 +483,7 @@ where
                 .resize(chunk.len(), 0)
                 .expect("chunk size should be less than or equal to N");
 
-            let offset = match u32::try_from(self.offset + pos) {
-                Ok(v) => v,
-                Err(_) => return CompareResult::Error,
-            };
-
-            if let Err(_) = self.storage.borrow_mut().read(offset, &mut buffer) {
+            if self.read(pos, &mut buffer).is_err() {
                 return CompareResult::Error;
             }
             pos += chunk.len();
@@ -451,7 +520,7 @@ fn lowercase_byte(c: u8) -> u8 {
 // Adapted for [`embedded-storage`].
 impl<'a, S, const N: usize> FindSubstring<&'a [u8]> for Bytes<S, N>
 where
-    S: ReadNorFlash,
+    S: Storage,
 {
     fn find_substring(&self, substr: &'a [u8]) -> Option<usize> {
         if substr.len() > self.len() {
@@ -490,7 +559,7 @@ where
 
 impl<S, const N: usize> FindToken<u8> for Bytes<S, N>
 where
-    S: ReadNorFlash,
+    S: Storage,
 {
     fn find_token(&self, token: u8) -> bool {
         self.memchr(token).is_some()
@@ -571,6 +640,44 @@ mod tests {
         assert_eq_iterators!(s.iter_indices(), original.iter().copied().enumerate());
     }
 
+    #[test]
+    fn test_iter_read_ahead() {
+        #[derive(Debug)]
+        struct Counting<'a>(&'a [u8], usize);
+
+        impl<'a> crate::Storage for Counting<'a> {
+            type Error = Error;
+
+            const ADDRESS_BITS: u32 = 32;
+            const READ_SIZE: usize = 1;
+
+            fn read(&mut self, offset: usize, bytes: &mut [u8]) -> Result<(), Self::Error> {
+                self.1 += 1;
+                bytes.copy_from_slice(&self.0[offset..offset + bytes.len()]);
+                Ok(())
+            }
+
+            fn capacity(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        let original = b"abcd1234efgh";
+        let storage = NonNull::from(Box::leak(Box::new(RcInner::new(RefCell::new(Counting(
+            original, 0,
+        ))))));
+        let storage = unsafe { Rc::from_inner(storage) };
+        let s = Bytes::<_, 5>::new(1, 10, Rc::clone(&storage)).unwrap();
+
+        // Two reads of 5 bytes instead of one read per byte.
+        assert!(s.iter().eq(original[1..11].iter().copied()));
+        assert_eq!(storage.borrow().1, 2);
+
+        let s = s.slice(3..);
+        assert!(s.iter().eq(original[4..11].iter().copied()));
+        assert_eq!(storage.borrow().1, 4);
+    }
+
     #[test]
     fn test_slice() {
         let original = b"abcd123";
@@ -607,6 +714,67 @@ mod tests {
         assert_eq_iterators!(s.iter_elements(), original.iter().copied());
     }
 
+    #[test]
+    fn test_try_slice() {
+        let original = b"abcd123";
+        let storage = NonNull::from(Box::leak(Box::new(RcInner::new(RefCell::new(Storage(
+            original,
+        ))))));
+        let storage = unsafe { Rc::from_inner(storage) };
+        let s = Bytes::<_, 16>::new(0, original.len(), storage).unwrap();
+
+        assert!(s.try_slice(2..5).unwrap().iter().eq(*b"cd1"));
+        assert!(s.try_slice(7..).unwrap().is_empty());
+        assert!(s.try_take(7).unwrap().iter().eq(*original));
+        assert!(matches!(
+            s.try_slice(5..8),
+            Err(super::Error::OutOfRange {
+                start: 5,
+                end: 8,
+                len: 7
+            })
+        ));
+        assert!(s.try_slice(8..).is_err());
+        assert!(s.try_take(8).is_err());
+
+        let (suffix, prefix) = s.try_take_split(4).unwrap();
+        assert!(prefix.iter().eq(*b"abcd"));
+        assert!(suffix.iter().eq(*b"123"));
+
+        let (rest, taken) = nom::bytes::complete::take::<_, _, ()>(3usize)(s.clone()).unwrap();
+        assert!(taken.iter().eq(*b"abc"));
+        assert!(rest.iter().eq(*b"d123"));
+    }
+
+    #[test]
+    #[cfg(feature = "saturating")]
+    fn test_saturating() {
+        let original = b"abcd123";
+        let storage = NonNull::from(Box::leak(Box::new(RcInner::new(RefCell::new(Storage(
+            original,
+        ))))));
+        let storage = unsafe { Rc::from_inner(storage) };
+        let s = Bytes::<_, 16>::new(0, original.len(), storage).unwrap();
+
+        assert_eq!(s.take(10).len(), 7);
+        assert_eq!(s.slice(5..10).len(), 2);
+        assert!(s.slice(10..).is_empty());
+    }
+
+    #[test]
+    #[cfg(not(feature = "saturating"))]
+    #[should_panic(expected = "tried to slice 0..10, but the length is 7")]
+    fn test_take_out_of_range() {
+        let original = b"abcd123";
+        let storage = NonNull::from(Box::leak(Box::new(RcInner::new(RefCell::new(Storage(
+            original,
+        ))))));
+        let storage = unsafe { Rc::from_inner(storage) };
+        let s = Bytes::<_, 16>::new(0, original.len(), storage).unwrap();
+
+        s.take(10);
+    }
+
     #[test]
     fn test_find_substring() {
         let original = b"abcd123";
@@ -670,4 +838,93 @@ mod tests {
             "case-insensitive comparison should succeed"
         );
     }
+
+    #[test]
+    fn test_address_overflow() {
+        #[derive(Debug)]
+        struct Storage16<'a>(&'a [u8]);
+
+        impl<'a> crate::Storage for Storage16<'a> {
+            type Error = Error;
+
+            const ADDRESS_BITS: u32 = 16;
+            const READ_SIZE: usize = 1;
+
+            fn read(&mut self, offset: usize, bytes: &mut [u8]) -> Result<(), Self::Error> {
+                bytes.copy_from_slice(&self.0[offset..offset + bytes.len()]);
+                Ok(())
+            }
+
+            fn capacity(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        let original = Box::leak(vec![0u8; 0x10001].into_boxed_slice());
+        let storage = NonNull::from(Box::leak(Box::new(RcInner::new(RefCell::new(Storage16(
+            original,
+        ))))));
+        let storage = unsafe { Rc::from_inner(storage) };
+
+        assert!(Bytes::<_, 16>::new(0, 0x10000, Rc::clone(&storage)).is_ok());
+        assert!(matches!(
+            Bytes::<_, 16>::new(1, 0x10000, Rc::clone(&storage)),
+            Err(super::Error::AddressOverflow {
+                offset: 1,
+                len: 0x10000,
+                address_bits: 16,
+            })
+        ));
+        assert!(matches!(
+            Bytes::<_, 16>::new(usize::MAX, 2, storage),
+            Err(super::Error::AddressOverflow { .. })
+        ));
+    }
+
+    #[test]
+    fn test_blocks() {
+        use crate::storage::{Blocks, ReadBlocks};
+
+        #[derive(Debug)]
+        struct Device<'a>(&'a [u8]);
+
+        impl<'a> ReadBlocks for Device<'a> {
+            type Error = Error;
+
+            const BLOCK_SIZE: usize = 4;
+
+            fn read_block(
+                &mut self,
+                block: u32,
+                offset: usize,
+                bytes: &mut [u8],
+            ) -> Result<(), Self::Error> {
+                assert!(offset + bytes.len() <= Self::BLOCK_SIZE);
+
+                let start = usize::try_from(block).unwrap() * Self::BLOCK_SIZE + offset;
+                let block = self.0.get(start..start + bytes.len()).ok_or(Error)?;
+                bytes.copy_from_slice(block);
+                Ok(())
+            }
+
+            fn block_count(&self) -> u32 {
+                u32::try_from(self.0.len() / Self::BLOCK_SIZE).unwrap()
+            }
+        }
+
+        assert_eq!(<Blocks<Device> as crate::Storage>::ADDRESS_BITS, 34);
+
+        let original = b"abcd1234efgh";
+        let storage = NonNull::from(Box::leak(Box::new(RcInner::new(RefCell::new(Blocks(
+            Device(original),
+        ))))));
+        let storage = unsafe { Rc::from_inner(storage) };
+        let s = Bytes::<_, 3>::new(2, 8, storage).unwrap();
+
+        assert_eq_iterators!(s.iter_elements(), original[2..10].iter().copied());
+        assert_eq!(s.compare(b"cd1234ef"), CompareResult::Ok);
+        assert_eq!(s.find_substring(b"4e"), Some(5));
+        assert_eq!(s.memchr(b'f'), Some(7));
+        assert_eq!(s.memchr(b'g'), None);
+    }
 }
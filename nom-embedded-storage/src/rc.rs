@@ -54,6 +54,11 @@ impl<T> RcInner<T> {
 
 impl<T> Rc<T> {
     /// Construct a [`Rc`] from the inner value.
+    ///
+    /// # Safety
+    ///
+    /// `inner` must point to a valid [`RcInner`] that outlives every [`Rc`]
+    /// constructed from it, as it is never de-allocated.
     pub unsafe fn from_inner(inner: NonNull<RcInner<T>>) -> Self {
         Self {
             ptr: inner,
@@ -77,7 +82,7 @@ impl<T> Deref for Rc<T> {
 
 impl<T> AsRef<T> for Rc<T> {
     fn as_ref(&self) -> &T {
-        &**self
+        self
     }
 }
 
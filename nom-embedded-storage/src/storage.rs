@@ -0,0 +1,131 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundationdevices.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! # Storage devices.
+//!
+//! [`Bytes`](crate::Bytes) reads through the [`Storage`] trait, which makes
+//! the width of the device addresses explicit so that offsets that do not
+//! fit are rejected up front instead of being silently truncated.
+//!
+//! Every [`ReadNorFlash`] implements [`Storage`] with 32-bit addresses.
+//! Devices that address blocks instead of bytes, such as NAND flash, can
+//! implement [`ReadBlocks`] and be wrapped in [`Blocks`].
+
+use core::fmt;
+
+use embedded_storage::nor_flash::ReadNorFlash;
+
+/// A readable storage device.
+pub trait Storage {
+    /// The error returned by the device.
+    type Error: fmt::Debug;
+
+    /// Width in bits of the byte addresses accepted by the device.
+    const ADDRESS_BITS: u32;
+
+    /// Size in bytes of the smallest read supported by the device.
+    const READ_SIZE: usize;
+
+    /// Read `bytes.len()` bytes at `offset`.
+    ///
+    /// The caller guarantees that `offset + bytes.len()` fits in
+    /// [`Storage::ADDRESS_BITS`] and is within [`Storage::capacity`].
+    fn read(&mut self, offset: usize, bytes: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// The capacity of the device in bytes.
+    fn capacity(&self) -> usize;
+
+    /// Returns `true` if the byte range ending at `end` (exclusive) can be
+    /// addressed by the device.
+    fn is_addressable(end: usize) -> bool {
+        match 1u64.checked_shl(Self::ADDRESS_BITS) {
+            Some(limit) => u64::try_from(end).map_or(false, |end| end <= limit),
+            None => true,
+        }
+    }
+}
+
+impl<T> Storage for T
+where
+    T: ReadNorFlash,
+{
+    type Error = T::Error;
+
+    const ADDRESS_BITS: u32 = u32::BITS;
+    const READ_SIZE: usize = T::READ_SIZE;
+
+    fn read(&mut self, offset: usize, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        let offset = u32::try_from(offset).expect("offset should be addressable");
+        ReadNorFlash::read(self, offset, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        ReadNorFlash::capacity(self)
+    }
+}
+
+/// A device whose reads take a 32-bit block address and a byte offset
+/// inside of that block.
+pub trait ReadBlocks {
+    /// The error returned by the device.
+    type Error: fmt::Debug;
+
+    /// Size of a block in bytes, must be a power of two.
+    const BLOCK_SIZE: usize;
+
+    /// Read `bytes.len()` bytes from `block` starting at `offset`.
+    ///
+    /// The read never crosses the end of `block`.
+    fn read_block(
+        &mut self,
+        block: u32,
+        offset: usize,
+        bytes: &mut [u8],
+    ) -> Result<(), Self::Error>;
+
+    /// The number of blocks in the device.
+    fn block_count(&self) -> u32;
+}
+
+/// Adapter to use a [`ReadBlocks`] device as [`Storage`].
+///
+/// Reads that span multiple blocks are split at block boundaries.
+#[derive(Debug)]
+pub struct Blocks<D>(pub D);
+
+impl<D> Storage for Blocks<D>
+where
+    D: ReadBlocks,
+{
+    type Error = D::Error;
+
+    const ADDRESS_BITS: u32 = u32::BITS + D::BLOCK_SIZE.trailing_zeros();
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, mut offset: usize, mut bytes: &mut [u8]) -> Result<(), Self::Error> {
+        debug_assert!(D::BLOCK_SIZE.is_power_of_two());
+
+        while !bytes.is_empty() {
+            let block = u32::try_from(offset / D::BLOCK_SIZE).expect("block should be addressable");
+            let block_offset = offset % D::BLOCK_SIZE;
+            let len = bytes.len().min(D::BLOCK_SIZE - block_offset);
+
+            let (chunk, rest) = bytes.split_at_mut(len);
+            self.0.read_block(block, block_offset, chunk)?;
+
+            offset += len;
+            bytes = rest;
+        }
+
+        Ok(())
+    }
+
+    /// The capacity of the device, saturated to `usize::MAX` when the
+    /// device is larger than the address space of the target.
+    fn capacity(&self) -> usize {
+        u64::from(self.0.block_count())
+            .checked_mul(D::BLOCK_SIZE as u64)
+            .and_then(|capacity| usize::try_from(capacity).ok())
+            .unwrap_or(usize::MAX)
+    }
+}
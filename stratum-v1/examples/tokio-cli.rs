@@ -72,10 +72,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     Some(Message::Authorized) => {
                         authorized_tx.send(true).unwrap();
                     }
-                    Some(Message::Share {
-                        accepted: _,
-                        rejected: _,
-                    }) => {
+                    Some(Message::Share { .. }) => {
                         // TODO update the display if any
                     }
                     Some(Message::VersionMask(_mask)) => {
@@ -87,6 +84,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     Some(Message::CleanJobs) => {
                         // TODO clean the job queue and immediately start hashing a new job
                     }
+                    Some(Message::Reconnect { host, port, wait }) => {
+                        // TODO reconnect to the given pool
+                        error!(
+                            "Pool asks to reconnect to {:?}:{:?} in {:?}s",
+                            host, port, wait
+                        );
+                    }
+                    Some(Message::Disconnected { error }) => {
+                        // TODO reconnect and reset the client
+                        error!("Disconnected: {:?}", error);
+                        break;
+                    }
+                    Some(Message::ProtocolError { method, error }) => {
+                        error!("Notification {} dropped: {:?}", method, error);
+                    }
                     None => {}
                 },
                 Err(e) => {
@@ -0,0 +1,140 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use heapless::Deque;
+
+/// Number of accepted shares kept to estimate the hashrate.
+pub const HASHRATE_HISTORY_LEN: usize = 64;
+
+/// History of the accepted shares, used to estimate the hashrate.
+#[derive(Debug)]
+pub(crate) struct ShareHistory {
+    /// Time at which the estimation starts, in seconds.
+    start: u64,
+    /// Time and difficulty of the accepted shares, oldest first.
+    shares: Deque<(u64, f64), HASHRATE_HISTORY_LEN>,
+}
+
+impl ShareHistory {
+    pub(crate) const fn new() -> Self {
+        Self {
+            start: 0,
+            shares: Deque::new(),
+        }
+    }
+
+    /// Restart the estimation at `now`, in seconds.
+    pub(crate) fn reset(&mut self, now: u64) {
+        self.start = now;
+        self.shares.clear();
+    }
+
+    /// Record a share of `difficulty` accepted at `time`, in seconds.
+    pub(crate) fn push(&mut self, time: u64, difficulty: f64) {
+        if self.shares.is_full() {
+            // The oldest share is dropped, start the estimation from it.
+            let (oldest, _) = self.shares.pop_front().unwrap();
+            self.start = oldest;
+        }
+        self.shares.push_back((time, difficulty)).ok();
+    }
+
+    /// Estimate the hashrate at `now`, in hashes per second.
+    ///
+    /// The accepted difficulty is decayed exponentially over `window`
+    /// seconds, in the same way as pools usually do.
+    pub(crate) fn estimate(&self, now: u64, window: u64) -> f64 {
+        let window = window.max(1) as f64;
+        let mut rate = 0.0;
+        let mut last = self.start;
+        let mut pending = 0.0;
+        for &(time, difficulty) in self.shares.iter() {
+            pending += difficulty;
+            // Shares accepted in the same second are accounted together.
+            if time > last {
+                decay_time(&mut rate, pending, (time - last) as f64, window);
+                last = time;
+                pending = 0.0;
+            }
+        }
+        if now > last || pending > 0.0 {
+            let elapsed = now.saturating_sub(last).max(1) as f64;
+            decay_time(&mut rate, pending, elapsed, window);
+        }
+
+        // A share of difficulty 1 takes 2^32 hashes on average.
+        rate * 4_294_967_296.0
+    }
+}
+
+/// Add `difficulty` accepted over `secs` to the difficulty per second
+/// `rate`, decayed over `window` seconds.
+fn decay_time(rate: &mut f64, difficulty: f64, secs: f64, window: f64) {
+    let prop = 1.0 - exp_neg(secs / window);
+    *rate += difficulty / secs * prop;
+    *rate /= 1.0 + prop;
+}
+
+/// `e^-x` for a positive `x`, `core` doesn't provide `exp`.
+fn exp_neg(mut x: f64) -> f64 {
+    if x > 700.0 {
+        return 0.0;
+    }
+
+    // Reduce the argument so that the series converges fast, then square
+    // the result back.
+    let mut squares = 0;
+    while x > 0.5 {
+        x /= 2.0;
+        squares += 1;
+    }
+
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..=10 {
+        term *= -x / f64::from(n);
+        sum += term;
+    }
+
+    for _ in 0..squares {
+        sum *= sum;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exp_neg() {
+        assert_eq!(exp_neg(0.0), 1.0);
+        for (x, e) in [
+            (0.1, 0.904_837_418_035_959_6),
+            (1.0, 0.367_879_441_171_442_33),
+            (10.0, 4.539_992_976_248_485e-5),
+        ] {
+            assert!((exp_neg(x) - e).abs() / e < 1e-9, "exp(-{x})");
+        }
+        assert_eq!(exp_neg(1000.0), 0.0);
+    }
+
+    #[test]
+    fn test_estimate() {
+        let mut history = ShareHistory::new();
+        history.reset(1000);
+        assert_eq!(history.estimate(1000, 60), 0.0);
+
+        // One share of difficulty 2 every 10 seconds.
+        for i in 1..=HASHRATE_HISTORY_LEN as u64 * 2 {
+            history.push(1000 + i * 10, 2.0);
+        }
+        let now = 1000 + HASHRATE_HISTORY_LEN as u64 * 2 * 10;
+        let expected = 2.0 * 4_294_967_296.0 / 10.0;
+        let hashrate = history.estimate(now, 60);
+        assert!((hashrate - expected).abs() / expected < 0.01);
+
+        // Without new shares the estimation decays.
+        assert!(history.estimate(now + 600, 60) < expected * 0.6);
+    }
+}
@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use super::notification::Work;
+use super::{notification::Work, request::Share, target::Target};
 use crate::{Error, Result};
 
 use bitcoin_hashes::sha256d::Hash as DHash;
@@ -42,6 +42,108 @@ impl defmt::Format for Job {
     }
 }
 
+/// Version of the [`Job`] serialization format.
+pub const JOB_FORMAT_VERSION: u8 = 1;
+
+/// Length of a serialized [`Job`].
+pub const JOB_SERIALIZED_LEN: usize = 1 + 1 + 32 + 1 + 8 + 80 + 4;
+
+const CRC32: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+impl Job {
+    /// Serialize the job, for example to send it to another processor.
+    ///
+    /// The layout is fixed, with the integers in little-endian order:
+    ///
+    /// | Offset | Length | Field                                    |
+    /// |--------|--------|------------------------------------------|
+    /// | 0      | 1      | [`JOB_FORMAT_VERSION`]                   |
+    /// | 1      | 1      | Length of the job ID                     |
+    /// | 2      | 32     | Job ID, padded with zeros                |
+    /// | 34     | 1      | Length of the extranonce2                |
+    /// | 35     | 8      | Extranonce2, padded with zeros           |
+    /// | 43     | 80     | Header, in the order of the fields       |
+    /// | 123    | 4      | CRC-32 (ISO HDLC) of the previous bytes  |
+    pub fn serialize(&self) -> [u8; JOB_SERIALIZED_LEN] {
+        let mut buf = [0; JOB_SERIALIZED_LEN];
+        buf[0] = JOB_FORMAT_VERSION;
+        buf[1] = self.job_id.len() as u8;
+        buf[2..2 + self.job_id.len()].copy_from_slice(self.job_id.as_bytes());
+        buf[34] = self.extranonce2.len() as u8;
+        buf[35..35 + self.extranonce2.len()].copy_from_slice(&self.extranonce2);
+        buf[43..47].copy_from_slice(&self.header.version.to_le_bytes());
+        buf[47..79].copy_from_slice(&self.header.prev_blockhash);
+        buf[79..111].copy_from_slice(&self.header.merkle_root);
+        buf[111..115].copy_from_slice(&self.header.ntime.to_le_bytes());
+        buf[115..119].copy_from_slice(&self.header.nbits.to_le_bytes());
+        buf[119..123].copy_from_slice(&self.header.nonce.to_le_bytes());
+        let crc = CRC32.checksum(&buf[..123]);
+        buf[123..].copy_from_slice(&crc.to_le_bytes());
+        buf
+    }
+
+    /// Deserialize a job serialized with [`Job::serialize`].
+    ///
+    /// Returns [`Error::JobVersion`] if the format version is not
+    /// [`JOB_FORMAT_VERSION`], and [`Error::JobCorrupted`] if the job is
+    /// not valid.
+    pub fn deserialize(buf: &[u8]) -> Result<Job> {
+        if buf.len() != JOB_SERIALIZED_LEN {
+            return Err(Error::JobCorrupted);
+        }
+        let crc = u32::from_le_bytes(buf[123..].try_into().unwrap());
+        if CRC32.checksum(&buf[..123]) != crc {
+            return Err(Error::JobCorrupted);
+        }
+        if buf[0] != JOB_FORMAT_VERSION {
+            return Err(Error::JobVersion(buf[0]));
+        }
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap());
+        let job_id = buf[2..34]
+            .get(..buf[1] as usize)
+            .and_then(|job_id| core::str::from_utf8(job_id).ok())
+            .and_then(|job_id| String::try_from(job_id).ok())
+            .ok_or(Error::JobCorrupted)?;
+        let extranonce2 = buf[35..43]
+            .get(..buf[34] as usize)
+            .and_then(|extranonce2| Vec::from_slice(extranonce2).ok())
+            .ok_or(Error::JobCorrupted)?;
+        Ok(Job {
+            job_id,
+            extranonce2,
+            header: Header {
+                version: u32_at(43) as i32,
+                prev_blockhash: buf[47..79].try_into().unwrap(),
+                merkle_root: buf[79..111].try_into().unwrap(),
+                ntime: u32_at(111),
+                nbits: u32_at(115),
+                nonce: u32_at(119),
+            },
+        })
+    }
+
+    /// Target of the network for a block, from the `nbits` of the header.
+    pub fn network_target(&self) -> Target {
+        Target::from_nbits(self.header.nbits)
+    }
+}
+
+/// Fields of the block header that were rolled to find a share.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Rolled {
+    /// The share has version bits.
+    pub version: bool,
+    /// The share extranonce2 is not zero.
+    pub extranonce2: bool,
+    /// The share ntime is not the one of the notified job.
+    ///
+    /// Only known while the job is the last one notified by the Pool,
+    /// `false` afterwards.
+    pub ntime: bool,
+}
+
 #[derive(Debug, Default)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub(crate) struct JobCreator {
@@ -58,6 +160,16 @@ pub(crate) struct JobCreator {
 }
 
 impl JobCreator {
+    /// Forget the work and the extranonces, keeping the rolling settings.
+    pub(crate) fn reset(&mut self) {
+        *self = JobCreator {
+            version_rolling: self.version_rolling,
+            extranonce2_rolling: self.extranonce2_rolling,
+            ntime_rolling: self.ntime_rolling,
+            ..Default::default()
+        };
+    }
+
     pub(crate) fn set_version_mask(&mut self, mask: u32) {
         self.version_mask = mask as i32;
     }
@@ -110,6 +222,21 @@ impl JobCreator {
         Ok(merkle_root)
     }
 
+    /// Fields rolled to find `share`, compared with the notified job.
+    pub(crate) fn rolled(&self, share: &Share) -> Rolled {
+        let ntime = match self.last_work {
+            Some(ref work) if work.job_id.as_str() == share.job_id.as_str() => {
+                share.ntime != work.ntime
+            }
+            _ => false,
+        };
+        Rolled {
+            version: share.version_bits.map_or(false, |bits| bits != 0),
+            extranonce2: share.extranonce2.iter().any(|&b| b != 0),
+            ntime,
+        }
+    }
+
     pub(crate) fn roll(&mut self) -> Result<Job> {
         let work = self.last_work.as_ref().ok_or(Error::NoWork)?;
         let rolled_version = if self.version_rolling {
@@ -291,6 +418,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rolled() {
+        let mut job_creator = JobCreator::default();
+        job_creator
+            .set_work(Work {
+                job_id: hstring!(32, "1234"),
+                prev_hash: [0; 32],
+                coinb1: Vec::new(),
+                coinb2: Vec::new(),
+                merkle_branch: Vec::new(),
+                version: 0x2000_0000,
+                nbits: 0x1234_5678,
+                ntime: 100,
+                clean_jobs: false,
+            })
+            .unwrap();
+        let mut share = Share {
+            job_id: hstring!(64, "1234"),
+            extranonce2: hvec!(u8, 8, &[0, 0]),
+            ntime: 100,
+            nonce: 0,
+            version_bits: None,
+        };
+        assert_eq!(job_creator.rolled(&share), Rolled::default());
+
+        share.ntime = 101;
+        share.version_bits = Some(0x2000);
+        share.extranonce2 = hvec!(u8, 8, &[0, 1]);
+        assert_eq!(
+            job_creator.rolled(&share),
+            Rolled {
+                version: true,
+                extranonce2: true,
+                ntime: true,
+            }
+        );
+
+        // The ntime of a previous job is not known.
+        share.job_id = hstring!(64, "1233");
+        assert!(!job_creator.rolled(&share).ntime);
+    }
+
     #[test]
     fn test_merkle_root() {
         // example from https://github.com/stratum-mining/stratum/pull/305/files
@@ -411,4 +580,41 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn test_serialize() {
+        let job = Job {
+            job_id: hstring!(32, "662ede"),
+            extranonce2: hvec!(u8, 8, &[0, 0, 0, 1]),
+            header: Header {
+                version: 0x2000_e000,
+                prev_blockhash: [0x11; 32],
+                merkle_root: [0x22; 32],
+                ntime: 0x66aa_d286,
+                nbits: 0x1703_1abe,
+                nonce: 0x1234_5678,
+            },
+        };
+        let buf = job.serialize();
+        assert_eq!(&buf[..8], &[1, 6, b'6', b'6', b'2', b'e', b'd', b'e']);
+        assert_eq!(&buf[43..47], &[0x00, 0xe0, 0x00, 0x20]);
+        assert_eq!(Job::deserialize(&buf), Ok(job));
+
+        assert_eq!(Job::deserialize(&buf[1..]), Err(Error::JobCorrupted));
+        let mut corrupted = buf;
+        corrupted[100] ^= 1;
+        assert_eq!(Job::deserialize(&corrupted), Err(Error::JobCorrupted));
+
+        let mut version = buf;
+        version[0] = 2;
+        let crc = CRC32.checksum(&version[..123]);
+        version[123..].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(Job::deserialize(&version), Err(Error::JobVersion(2)));
+
+        let mut bad_len = buf;
+        bad_len[34] = 9;
+        let crc = CRC32.checksum(&bad_len[..123]);
+        bad_len[123..].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(Job::deserialize(&bad_len), Err(Error::JobCorrupted));
+    }
 }
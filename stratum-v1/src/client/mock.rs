@@ -0,0 +1,680 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Local mock pool for offline end-to-end testing.
+//!
+//! [`MockPool`] implements the transport traits expected by [`Client`](crate::Client)
+//! and answers the Stratum v1 handshake like a real pool would. It always
+//! hands out the same work with a very low share difficulty, so a complete
+//! miner stack can be exercised during device bring-up or manufacturing
+//! without any internet connection.
+//!
+//! [`GOLDEN_SHARES`] lists known solutions of this work for the fixed
+//! [`EXTRANONCE1`], they can be used to validate the hashing of a firmware.
+
+use super::notification::{parse_notify, Work};
+use super::request::Share;
+
+use bitcoin_hashes::sha256d::Hash as DHash;
+use core::convert::Infallible;
+use embedded_io_async::{ErrorType, Read, ReadReady, Write};
+use serde_json::{json, Value};
+use std::{collections::VecDeque, format, vec::Vec};
+
+/// Extranonce1 given by the mock pool to every client.
+pub const EXTRANONCE1: [u8; 4] = [0x08, 0x00, 0x00, 0x02];
+
+/// Size of the extranonce2 expected by the mock pool.
+pub const EXTRANONCE2_SIZE: usize = 4;
+
+/// Version rolling mask accepted by the mock pool.
+pub const VERSION_MASK: u32 = 0x1fff_e000;
+
+/// Number of leading zero bits a share hash needs to be accepted.
+///
+/// The difficulty sent to the client is the equivalent stratum difficulty,
+/// `2^(SHARE_ZERO_BITS - 32)`.
+pub const SHARE_ZERO_BITS: u32 = 16;
+
+/// Job ID of the work sent by the mock pool.
+pub const JOB_ID: &str = "1";
+
+/// nTime of the work sent by the mock pool.
+pub const NTIME: u32 = 0x66aa_d286;
+
+const NOTIFY: &str = r#"{"params":["1","9c16805af67958e9c183d0fa47e4b8245fea76e26cfe874b0000000e00000000","01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff20020862062f503253482f04b8864e5008","072f736c7573682f000000000100f2052a010000001976a914d23fcdf86f7e756a64a7a9688ef9903327048ed988ac00000000",["7a61407ca49ea40e5777e2a922c4fb331f836dfa0d365e06b11b9c9a651e7b9f"],"20000000","17031abe","66aad286",true],"id":null,"method":"mining.notify"}"#;
+
+/// A known valid share for the mock pool work.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoldenShare {
+    pub extranonce2: [u8; EXTRANONCE2_SIZE],
+    pub ntime: u32,
+    pub nonce: u32,
+    pub version_bits: Option<u32>,
+    /// Expected block header hash, in internal byte order.
+    pub hash: [u8; 32],
+}
+
+/// Known valid shares for the mock pool work, see [`GoldenShare`].
+pub const GOLDEN_SHARES: [GoldenShare; 3] = [
+    GoldenShare {
+        extranonce2: [0x00, 0x00, 0x00, 0x00],
+        ntime: NTIME,
+        nonce: 0x0002_4db4,
+        version_bits: None,
+        hash: [
+            0x18, 0xd4, 0xb6, 0xfc, 0xe1, 0x84, 0x15, 0x33, 0x03, 0xdf, 0xc1, 0x15, 0x11, 0x3b,
+            0xf9, 0xf2, 0x81, 0x3e, 0x34, 0xce, 0x13, 0x80, 0x59, 0x4a, 0xa7, 0x2a, 0xdd, 0x91,
+            0x3d, 0x31, 0x00, 0x00,
+        ],
+    },
+    GoldenShare {
+        extranonce2: [0x00, 0x00, 0x00, 0x01],
+        ntime: NTIME,
+        nonce: 0x0000_4728,
+        version_bits: None,
+        hash: [
+            0x23, 0x6a, 0xd8, 0x5a, 0xda, 0x2d, 0xf0, 0xa3, 0x9c, 0x5e, 0x0c, 0xa0, 0xe3, 0x8f,
+            0x12, 0x71, 0x7d, 0xc6, 0x73, 0x63, 0xf6, 0x66, 0x7a, 0x23, 0x90, 0x72, 0xcc, 0x6a,
+            0x6d, 0xf7, 0x00, 0x00,
+        ],
+    },
+    GoldenShare {
+        extranonce2: [0x00, 0x00, 0x00, 0x02],
+        ntime: NTIME,
+        nonce: 0x0000_300c,
+        version_bits: None,
+        hash: [
+            0xe6, 0xbd, 0x3c, 0x79, 0x21, 0x97, 0x91, 0x71, 0x3d, 0x7c, 0x7c, 0xdc, 0x76, 0xbc,
+            0x37, 0xff, 0xfb, 0xaf, 0x31, 0x85, 0xa1, 0xbb, 0x2a, 0x4d, 0x66, 0x33, 0x48, 0xe6,
+            0x89, 0xbd, 0x00, 0x00,
+        ],
+    },
+];
+
+/// A share submitted to the mock pool.
+#[derive(Debug, PartialEq)]
+pub struct Submitted {
+    pub share: Share,
+    pub accepted: bool,
+}
+
+/// Mock Stratum v1 pool, see the [module documentation](self).
+#[derive(Debug)]
+pub struct MockPool {
+    work: Work,
+    rx: VecDeque<Vec<u8>>,
+    tx: Vec<u8>,
+    submits: Vec<Submitted>,
+    pongs: Vec<Option<u64>>,
+    closed: bool,
+}
+
+impl Default for MockPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockPool {
+    pub fn new() -> Self {
+        Self {
+            work: work(),
+            rx: VecDeque::new(),
+            tx: Vec::new(),
+            submits: Vec::new(),
+            pongs: Vec::new(),
+            closed: false,
+        }
+    }
+
+    /// Shares submitted so far, in order.
+    pub fn submits(&self) -> &[Submitted] {
+        &self.submits
+    }
+
+    /// Ids of the `mining.ping` requests answered by the client.
+    pub fn pongs(&self) -> &[Option<u64>] {
+        &self.pongs
+    }
+
+    /// Close the connection once the queued lines are received.
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    /// Queue a raw line to be received by the client, for example to inject
+    /// malformed notifications.
+    pub fn push_line(&mut self, line: &[u8]) {
+        let mut line = line.to_vec();
+        line.push(b'\n');
+        self.rx.push_back(line);
+    }
+
+    fn send(&mut self, value: Value) {
+        let mut line = serde_json::to_vec(&value).unwrap();
+        line.push(b'\n');
+        self.rx.push_back(line);
+    }
+
+    fn respond(&mut self, id: Option<u64>, result: Value) {
+        self.send(json!({"id": id, "result": result, "error": null}));
+    }
+
+    fn respond_error(&mut self, id: Option<u64>, code: isize, message: &str) {
+        self.send(json!({"id": id, "result": null, "error": [code, message, null]}));
+    }
+
+    fn handle(&mut self, line: &[u8]) {
+        let Ok(req) = serde_json::from_slice::<Value>(line) else {
+            self.respond_error(None, 20, "Other/Unknown");
+            return;
+        };
+        let id = req["id"].as_u64();
+        if req["method"].is_null() && req["result"] == "pong" {
+            self.pongs.push(id);
+            return;
+        }
+        match req["method"].as_str() {
+            Some("mining.configure") => self.respond(
+                id,
+                json!({
+                    "version-rolling": true,
+                    "version-rolling.mask": format!("{VERSION_MASK:08x}"),
+                }),
+            ),
+            Some("mining.subscribe") => {
+                self.respond(
+                    id,
+                    json!([
+                        [["mining.set_difficulty", "1"], ["mining.notify", "1"]],
+                        format!("{:08x}", u32::from_be_bytes(EXTRANONCE1)),
+                        EXTRANONCE2_SIZE,
+                    ]),
+                );
+                self.send(json!({
+                    "id": null,
+                    "method": "mining.set_version_mask",
+                    "params": [format!("{VERSION_MASK:08x}")],
+                }));
+                self.send(json!({
+                    "id": null,
+                    "method": "mining.set_difficulty",
+                    "params": [2f64.powi(SHARE_ZERO_BITS as i32 - 32)],
+                }));
+                self.rx.push_back(format!("{NOTIFY}\n").into_bytes());
+            }
+            Some("mining.authorize") => self.respond(id, json!(true)),
+            Some("mining.submit") => match self.submit(&req["params"]) {
+                Some(submitted) => {
+                    if submitted.share.job_id != JOB_ID {
+                        self.respond_error(id, 21, "Job not found");
+                    } else if submitted.accepted {
+                        self.respond(id, json!(true));
+                    } else {
+                        self.respond_error(id, 23, "Difficulty too low");
+                    }
+                    self.submits.push(submitted);
+                }
+                None => self.respond_error(id, 20, "Other/Unknown"),
+            },
+            _ => self.respond_error(id, 20, "Other/Unknown"),
+        }
+    }
+
+    fn submit(&self, params: &Value) -> Option<Submitted> {
+        let params = params.as_array()?;
+        let param = |i: usize| params.get(i).and_then(Value::as_str);
+        let extranonce2 = unhex(param(2)?)?;
+        let ntime = u32::from_str_radix(param(3)?, 16).ok()?;
+        let nonce = u32::from_str_radix(param(4)?, 16).ok()?;
+        let version_bits = match param(5) {
+            Some(v) => Some(u32::from_str_radix(v, 16).ok()?),
+            None => None,
+        };
+        let hash = hash_share(&self.work, &extranonce2, ntime, nonce, version_bits);
+        Some(Submitted {
+            share: Share {
+                job_id: param(1)?.try_into().ok()?,
+                extranonce2: heapless::Vec::from_slice(&extranonce2).ok()?,
+                ntime,
+                nonce,
+                version_bits,
+            },
+            accepted: extranonce2.len() == EXTRANONCE2_SIZE
+                && leading_zero_bits(&hash) >= SHARE_ZERO_BITS,
+        })
+    }
+}
+
+impl ErrorType for MockPool {
+    type Error = Infallible;
+}
+
+impl Read for MockPool {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let Some(mut line) = self.rx.pop_front() else {
+            return Ok(0);
+        };
+        let n = line.len().min(buf.len());
+        buf[..n].copy_from_slice(&line[..n]);
+        if n < line.len() {
+            self.rx.push_front(line.split_off(n));
+        }
+        Ok(n)
+    }
+}
+
+impl ReadReady for MockPool {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.rx.is_empty() || self.closed)
+    }
+}
+
+impl Write for MockPool {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.tx.extend_from_slice(buf);
+        while let Some(pos) = self.tx.iter().position(|&c| c == b'\n') {
+            let line: Vec<u8> = self.tx.drain(..=pos).collect();
+            self.handle(&line[..pos]);
+        }
+        Ok(buf.len())
+    }
+}
+
+/// Block header hash of a share for the mock pool work, in internal byte
+/// order.
+pub fn share_hash(
+    extranonce2: &[u8],
+    ntime: u32,
+    nonce: u32,
+    version_bits: Option<u32>,
+) -> [u8; 32] {
+    hash_share(&work(), extranonce2, ntime, nonce, version_bits)
+}
+
+fn work() -> Work {
+    parse_notify(NOTIFY.as_bytes()).expect("mock pool work is valid")
+}
+
+fn hash_share(
+    work: &Work,
+    extranonce2: &[u8],
+    ntime: u32,
+    nonce: u32,
+    version_bits: Option<u32>,
+) -> [u8; 32] {
+    let mut coinbase = Vec::new();
+    coinbase.extend_from_slice(&work.coinb1);
+    coinbase.extend_from_slice(&EXTRANONCE1);
+    coinbase.extend_from_slice(extranonce2);
+    coinbase.extend_from_slice(&work.coinb2);
+    let mut merkle_root = DHash::hash(&coinbase).to_byte_array();
+    for node in &work.merkle_branch {
+        let mut to_hash = [0; 64];
+        to_hash[..32].copy_from_slice(&merkle_root);
+        to_hash[32..].copy_from_slice(node);
+        merkle_root = DHash::hash(&to_hash).to_byte_array();
+    }
+
+    let version = match version_bits {
+        Some(bits) => (work.version as u32 & !VERSION_MASK) | (bits & VERSION_MASK),
+        None => work.version as u32,
+    };
+    // The notified previous block hash is in display order.
+    let mut prev_blockhash = work.prev_hash;
+    prev_blockhash.reverse();
+
+    let mut header = [0; 80];
+    header[..4].copy_from_slice(&version.to_le_bytes());
+    header[4..36].copy_from_slice(&prev_blockhash);
+    header[36..68].copy_from_slice(&merkle_root);
+    header[68..72].copy_from_slice(&ntime.to_le_bytes());
+    header[72..76].copy_from_slice(&work.nbits.to_le_bytes());
+    header[76..].copy_from_slice(&nonce.to_le_bytes());
+    DHash::hash(&header).to_byte_array()
+}
+
+fn leading_zero_bits(hash: &[u8; 32]) -> u32 {
+    let mut bits = 0;
+    for &b in hash.iter().rev() {
+        bits += b.leading_zeros();
+        if b != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+fn unhex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Client, ClientState, Error, Extensions, Message, NetworkOp, Rolled, ShareResult,
+        VersionRolling,
+    };
+    use core::str::FromStr;
+    use embedded_io_async::ErrorKind;
+
+    #[test]
+    fn test_golden_shares() {
+        for golden in GOLDEN_SHARES {
+            let hash = share_hash(
+                &golden.extranonce2,
+                golden.ntime,
+                golden.nonce,
+                golden.version_bits,
+            );
+            assert_eq!(hash, golden.hash);
+            assert!(leading_zero_bits(&hash) >= SHARE_ZERO_BITS);
+        }
+    }
+
+    async fn poll(client: &mut Client<MockPool, 1480, 512>) -> Message {
+        loop {
+            if let Some(msg) = client.poll_message().await.unwrap() {
+                return msg;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_end_to_end() {
+        let mut client = Client::<_, 1480, 512>::new(MockPool::new());
+        client.enable_software_rolling(false, true, false);
+
+        client
+            .send_configure(Extensions {
+                version_rolling: Some(VersionRolling {
+                    mask: Some(VERSION_MASK),
+                    min_bit_count: None,
+                }),
+                minimum_difficulty: None,
+                subscribe_extranonce: None,
+                info: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(poll(&mut client).await, Message::Configured);
+        client.send_connect(None).await.unwrap();
+        assert_eq!(poll(&mut client).await, Message::Connected);
+        assert_eq!(poll(&mut client).await, Message::VersionMask(VERSION_MASK));
+        assert_eq!(
+            poll(&mut client).await,
+            Message::Difficulty(2f64.powi(SHARE_ZERO_BITS as i32 - 32))
+        );
+        assert_eq!(poll(&mut client).await, Message::CleanJobs);
+        client
+            .send_authorize(hstring!(64, "miner1"), hstring!(64, "x"))
+            .await
+            .unwrap();
+        assert_eq!(poll(&mut client).await, Message::Authorized);
+
+        for (i, golden) in GOLDEN_SHARES.iter().skip(1).enumerate() {
+            let job = client.roll_job().await.unwrap();
+            assert_eq!(job.extranonce2, golden.extranonce2);
+
+            let mut prev_blockhash = job.header.prev_blockhash;
+            prev_blockhash.reverse();
+            let mut header = [0; 80];
+            header[..4].copy_from_slice(&job.header.version.to_le_bytes());
+            header[4..36].copy_from_slice(&prev_blockhash);
+            header[36..68].copy_from_slice(&job.header.merkle_root);
+            header[68..72].copy_from_slice(&job.header.ntime.to_le_bytes());
+            header[72..76].copy_from_slice(&job.header.nbits.to_le_bytes());
+            header[76..].copy_from_slice(&golden.nonce.to_le_bytes());
+            assert_eq!(DHash::hash(&header).to_byte_array(), golden.hash);
+            assert!(client.share_target().is_met_by(&golden.hash));
+
+            client
+                .send_submit(Share {
+                    job_id: job.job_id.as_str().try_into().unwrap(),
+                    extranonce2: job.extranonce2.clone(),
+                    ntime: job.header.ntime,
+                    nonce: golden.nonce,
+                    version_bits: None,
+                })
+                .await
+                .unwrap();
+            assert_eq!(
+                poll(&mut client).await,
+                Message::Share {
+                    accepted: i as u64 + 1,
+                    rejected: 0,
+                    result: ShareResult {
+                        id: i as u64 + 4,
+                        job_id: hstring!(64, JOB_ID),
+                        rolled: Rolled {
+                            version: false,
+                            extranonce2: true,
+                            ntime: false,
+                        },
+                        reject_code: None,
+                    },
+                }
+            );
+        }
+
+        client
+            .send_submit(Share {
+                job_id: hstring!(64, JOB_ID),
+                extranonce2: hvec!(u8, 8, &GOLDEN_SHARES[0].extranonce2),
+                ntime: NTIME,
+                nonce: GOLDEN_SHARES[0].nonce + 1,
+                version_bits: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            poll(&mut client).await,
+            Message::Share {
+                accepted: 2,
+                rejected: 1,
+                result: ShareResult {
+                    id: 6,
+                    job_id: hstring!(64, JOB_ID),
+                    rolled: Rolled::default(),
+                    reject_code: Some(23),
+                },
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_insufficient_version_bits() {
+        let mut client = Client::<_, 1480, 512>::new(MockPool::new());
+        client
+            .send_configure(Extensions {
+                version_rolling: Some(VersionRolling {
+                    mask: Some(0x1fff_ffff),
+                    min_bit_count: Some(20),
+                }),
+                minimum_difficulty: None,
+                subscribe_extranonce: None,
+                info: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(poll(&mut client).await, Message::Configured);
+        assert_eq!(
+            client.poll_message().await,
+            Err(Error::InsufficientVersionBits {
+                requested: 20,
+                available: 16,
+            })
+        );
+        assert_eq!(
+            client
+                .configuration()
+                .map(Extensions::effective_version_space),
+            Some(VERSION_MASK.count_ones())
+        );
+        client.send_connect(None).await.unwrap();
+        assert_eq!(poll(&mut client).await, Message::Connected);
+    }
+
+    #[tokio::test]
+    async fn test_protocol_error() {
+        let mut pool = MockPool::new();
+        pool.push_line(br#"{"id":null,"method":"mining.set_difficulty","params":["x"]}"#);
+        pool.push_line(br#"{"id":null,"method":"mining.foo","params":[]}"#);
+        pool.push_line(br#"{"id":null,"params":[]}"#);
+        pool.push_line(br#"{"id":null,"method":"mining.set_difficulty","params":[2]}"#);
+        let mut client = Client::<_, 1480, 512>::new(pool);
+
+        assert!(matches!(
+            poll(&mut client).await,
+            Message::ProtocolError {
+                method,
+                error: Error::JsonError(_),
+            } if method == "mining.set_difficulty"
+        ));
+        assert_eq!(
+            poll(&mut client).await,
+            Message::ProtocolError {
+                method: hstring!(32, "mining.foo"),
+                error: Error::UnknownNotification,
+            }
+        );
+        assert!(matches!(
+            poll(&mut client).await,
+            Message::ProtocolError { method, error: Error::JsonError(_) } if method.is_empty()
+        ));
+        assert_eq!(poll(&mut client).await, Message::Difficulty(2.0));
+    }
+
+    #[tokio::test]
+    async fn test_response_error() {
+        let mut pool = MockPool::new();
+        pool.push_line(br#"{"id":7,"result":true,"error":null}"#);
+        pool.push_line(br#"{"id":8,"result":"#);
+        pool.push_line(br#"{"id":null,"method":"mining.set_difficulty","params":[2]}"#);
+        let mut client = Client::<_, 1480, 512>::new(pool);
+
+        let mut errors = Vec::new();
+        let msg = loop {
+            match client.poll_message().await {
+                Ok(None) => {}
+                Ok(Some(msg)) => break msg,
+                Err(error) if errors.len() < 2 => errors.push(error),
+                Err(error) => panic!("unexpected error {error:?}"),
+            }
+        };
+        assert_eq!(errors[0], Error::IdNotFound(7));
+        assert!(matches!(errors[1], Error::JsonError(_)));
+        assert_eq!(msg, Message::Difficulty(2.0));
+    }
+
+    #[tokio::test]
+    async fn test_line_too_long() {
+        let mut pool = MockPool::new();
+        let mut line = br#"{"id":null,"method":"mining.notify","params":[""#.to_vec();
+        line.resize(200, b'0');
+        line.extend_from_slice(br#""]}"#);
+        pool.push_line(&line);
+        pool.push_line(br#"{"id":null,"method":"mining.set_difficulty","params":[2]}"#);
+        let mut client = Client::<_, 64, 512>::new(pool);
+
+        let error = loop {
+            match client.poll_message().await {
+                Ok(None) => {}
+                Ok(Some(msg)) => panic!("unexpected message {msg:?}"),
+                Err(error) => break error,
+            }
+        };
+        assert_eq!(
+            error,
+            Error::LineTooLong {
+                needed: line.len() + 1
+            }
+        );
+        assert_eq!(client.lines_too_long(), 1);
+        let msg = loop {
+            if let Some(msg) = client.poll_message().await.unwrap() {
+                break msg;
+            }
+        };
+        assert_eq!(msg, Message::Difficulty(2.0));
+    }
+
+    #[tokio::test]
+    async fn test_ping_and_reconnect() {
+        let mut pool = MockPool::new();
+        pool.push_line(br#"{"id":42,"method":"mining.ping","params":[]}"#);
+        pool.push_line(
+            br#"{"id":null,"method":"client.reconnect","params":["pool.example.com",3333,10]}"#,
+        );
+        let mut client = Client::<_, 512, 512>::new(pool);
+
+        let msg = loop {
+            if let Some(msg) = client.poll_message().await.unwrap() {
+                break msg;
+            }
+        };
+        assert_eq!(
+            msg,
+            Message::Reconnect {
+                host: Some(hstring!(64, "pool.example.com")),
+                port: Some(3333),
+                wait: Some(10),
+            }
+        );
+        assert_eq!(client.network_conn.pongs(), &[Some(42)]);
+    }
+
+    #[tokio::test]
+    async fn test_disconnected() {
+        let extensions = || Extensions {
+            version_rolling: None,
+            minimum_difficulty: None,
+            subscribe_extranonce: None,
+            info: None,
+        };
+        let mut client = Client::<_, 1480, 512>::new(MockPool::new());
+        assert_eq!(client.state(), ClientState::Idle);
+        client.send_configure(extensions()).await.unwrap();
+        assert_eq!(poll(&mut client).await, Message::Configured);
+        client.send_connect(None).await.unwrap();
+        assert_eq!(poll(&mut client).await, Message::Connected);
+        assert_eq!(client.state(), ClientState::Connected);
+
+        client.network_conn.close();
+        let msg = loop {
+            if let Message::Disconnected { error } = poll(&mut client).await {
+                break error;
+            }
+        };
+        assert_eq!(
+            msg,
+            Error::Network {
+                op: NetworkOp::Read,
+                kind: ErrorKind::ConnectionAborted,
+            }
+        );
+        assert_eq!(client.state(), ClientState::Disconnected);
+        assert_eq!(client.poll_message().await, Err(Error::NotConnected));
+        assert_eq!(
+            client
+                .send_authorize(hstring!(64, "miner1"), hstring!(64, "x"))
+                .await,
+            Err(Error::NotConnected)
+        );
+
+        client.replace_transport(MockPool::new());
+        assert_eq!(client.state(), ClientState::Idle);
+        client.send_configure(extensions()).await.unwrap();
+        assert_eq!(poll(&mut client).await, Message::Configured);
+        client.send_connect(None).await.unwrap();
+        assert_eq!(poll(&mut client).await, Message::Connected);
+    }
+}
@@ -1,20 +1,27 @@
 // SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+mod hashrate;
 mod job;
+#[cfg(feature = "std")]
+pub mod mock;
 mod notification;
 mod request;
 mod response;
+mod target;
 
-use crate::{Error, Result};
-pub use job::Job;
+use crate::{error::NetworkOp, Error, Result};
+use hashrate::ShareHistory;
+pub use hashrate::HASHRATE_HISTORY_LEN;
 use job::JobCreator;
+pub use job::{Job, Rolled, JOB_FORMAT_VERSION, JOB_SERIALIZED_LEN};
 use notification::Notification;
 use request::ReqKind;
 pub use request::{Extensions, Info, Share, VersionRolling};
 use response::Subscription;
+pub use target::Target;
 
-use embedded_io_async::{Read, ReadReady, Write};
+use embedded_io_async::{ErrorKind, Read, ReadReady, Write};
 use heapless::{FnvIndexMap, String, Vec};
 
 #[derive(Debug)]
@@ -23,6 +30,9 @@ pub struct Client<C: Read + ReadReady + Write, const RX_BUF_SIZE: usize, const T
     network_conn: C,
     rx_buf: [u8; RX_BUF_SIZE],
     rx_free_pos: usize,
+    /// Length of the line being discarded, if it didn't fit in `rx_buf`.
+    rx_discarded: Option<usize>,
+    lines_too_long: u64,
     tx_buf: [u8; TX_BUF_SIZE],
     reqs: FnvIndexMap<u64, ReqKind, 16>,
     job_creator: JobCreator,
@@ -30,22 +40,90 @@ pub struct Client<C: Read + ReadReady + Write, const RX_BUF_SIZE: usize, const T
     subscriptions: Vec<Subscription, 2>,
     shares_accepted: u64,
     shares_rejected: u64,
+    difficulty: f64,
+    time_source: Option<fn() -> u64>,
+    share_history: ShareHistory,
     req_id: u64,
-    connected: bool,
-    authorized: bool,
+    state: ClientState,
+    /// Transport error not reported yet, see [`Message::Disconnected`].
+    disconnect_error: Option<Error>,
+    /// Error returned by the next poll, after the message it relates to.
+    pending_error: Option<Error>,
     user: String<64>,
 }
 
+/// State of the [`Client`] session with the Pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ClientState {
+    /// Nothing was negotiated with the Pool yet.
+    Idle,
+    /// The Pool answered [`Client::send_configure`].
+    Configured,
+    /// The Pool answered [`Client::send_connect`].
+    Connected,
+    /// The Pool answered [`Client::send_authorize`], shares can be submitted.
+    Authorized,
+    /// The transport failed, the Client must be [reset](Client::reset).
+    Disconnected,
+}
+
 #[derive(Debug, PartialEq)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum Message {
     Configured,
     Connected,
     Authorized,
-    Share { accepted: u64, rejected: u64 },
+    Share {
+        accepted: u64,
+        rejected: u64,
+        /// The share this response is for.
+        result: ShareResult,
+    },
     VersionMask(u32),
     Difficulty(f64),
     CleanJobs,
+    /// The Pool asks to reconnect, see [`Client::poll_message`].
+    Reconnect {
+        /// Host to connect to, the current one if `None`.
+        host: Option<String<64>>,
+        /// Port to connect to, the current one if `None`.
+        port: Option<u16>,
+        /// Seconds to wait before connecting, none if `None`.
+        wait: Option<u32>,
+    },
+    /// The transport failed, see [`Client::poll_message`].
+    Disconnected {
+        /// The transport error.
+        error: Error,
+    },
+    /// A notification from the Pool could not be handled and was dropped.
+    ProtocolError {
+        /// The notification method, empty if it could not be parsed.
+        method: String<32>,
+        error: Error,
+    },
+}
+
+/// Result of a submitted share, see [`Message::Share`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ShareResult {
+    /// Id of the submit request.
+    pub id: u64,
+    /// Job ID of the share.
+    pub job_id: String<64>,
+    /// Fields rolled to find the share.
+    pub rolled: Rolled,
+    /// Error code given by the Pool, `None` if the share was accepted.
+    pub reject_code: Option<isize>,
+}
+
+impl ShareResult {
+    /// Returns `true` if the Pool accepted the share.
+    pub fn is_accepted(&self) -> bool {
+        self.reject_code.is_none()
+    }
 }
 
 impl<C: Read + ReadReady + Write, const RX_BUF_SIZE: usize, const TX_BUF_SIZE: usize>
@@ -56,6 +134,8 @@ impl<C: Read + ReadReady + Write, const RX_BUF_SIZE: usize, const TX_BUF_SIZE: u
             network_conn,
             rx_buf: [0; RX_BUF_SIZE],
             rx_free_pos: 0,
+            rx_discarded: None,
+            lines_too_long: 0,
             tx_buf: [0; TX_BUF_SIZE],
             reqs: FnvIndexMap::new(),
             job_creator: JobCreator::default(),
@@ -63,9 +143,13 @@ impl<C: Read + ReadReady + Write, const RX_BUF_SIZE: usize, const TX_BUF_SIZE: u
             subscriptions: Vec::new(),
             shares_accepted: 0,
             shares_rejected: 0,
+            difficulty: 1.0,
+            time_source: None,
+            share_history: ShareHistory::new(),
             req_id: 0,
-            connected: false,
-            authorized: false,
+            state: ClientState::Idle,
+            disconnect_error: None,
+            pending_error: None,
             user: String::new(),
         }
     }
@@ -84,125 +168,367 @@ impl<C: Read + ReadReady + Write, const RX_BUF_SIZE: usize, const TX_BUF_SIZE: u
         );
     }
 
+    /// Set the time source used to estimate the hashrate.
+    ///
+    /// `now` returns the current time in seconds, the estimation starts
+    /// when this is called. See [`Client::estimated_hashrate`].
+    pub fn set_time_source(&mut self, now: fn() -> u64) {
+        self.time_source = Some(now);
+        self.share_history.reset(now());
+    }
+
+    /// Estimate the hashrate from the accepted shares, in hashes per second.
+    ///
+    /// The difficulty of the accepted shares is decayed exponentially over
+    /// `window_secs` seconds, as pools do, from the last
+    /// [`HASHRATE_HISTORY_LEN`] accepted shares.
+    ///
+    /// Returns `None` if no time source was set with
+    /// [`Client::set_time_source`].
+    pub fn estimated_hashrate(&self, window_secs: u64) -> Option<f64> {
+        let now = self.time_source?;
+        Some(self.share_history.estimate(now(), window_secs))
+    }
+
     pub async fn roll_job(&mut self) -> Result<Job> {
         self.job_creator.roll()
     }
 
+    /// Number of lines from the Pool discarded because they didn't fit in
+    /// the RX buffer, see [`Error::LineTooLong`].
+    pub fn lines_too_long(&self) -> u64 {
+        self.lines_too_long
+    }
+
+    /// Target of the shares for the current difficulty of the Pool.
+    ///
+    /// The shares with a hash that doesn't meet it are rejected by the Pool,
+    /// they don't need to be submitted.
+    pub fn share_target(&self) -> Target {
+        Target::from_difficulty(self.difficulty)
+    }
+
+    /// State of the session with the Pool.
+    pub fn state(&self) -> ClientState {
+        self.state
+    }
+
+    /// Forget the session with the Pool, to start a new one on the same
+    /// transport once it reconnected.
+    ///
+    /// The pending requests, the subscriptions, the extranonces and the work
+    /// are cleared. The share counters, the hashrate estimation and the
+    /// software rolling settings are kept.
+    pub fn reset(&mut self) {
+        self.rx_free_pos = 0;
+        self.rx_discarded = None;
+        self.reqs.clear();
+        self.job_creator.reset();
+        self.configuration = None;
+        self.subscriptions.clear();
+        self.difficulty = 1.0;
+        self.state = ClientState::Idle;
+        self.disconnect_error = None;
+        self.pending_error = None;
+        self.user.clear();
+        info!("Stratum v1 Client Reset");
+    }
+
+    /// Replace the transport, for example with a new TLS connection, and
+    /// [reset](Client::reset) the Client.
+    ///
+    /// Returns the previous transport.
+    pub fn replace_transport(&mut self, network_conn: C) -> C {
+        self.reset();
+        core::mem::replace(&mut self.network_conn, network_conn)
+    }
+
+    /// Extensions negotiated with the Pool, once configured.
+    pub fn configuration(&self) -> Option<&Extensions> {
+        self.configuration.as_ref()
+    }
+
+    /// # Poll Message
+    ///
+    /// Process the received data and return the next [`Message`], if any.
+    ///
+    /// Returns [`Error::InsufficientVersionBits`] on the poll following
+    /// [`Message::Configured`] when the Pool negotiated fewer version rolling
+    /// bits than requested in [`Client::send_configure`]. The Client is still
+    /// configured and the hasher can fall back to extranonce rolling.
+    ///
+    /// A response that can't be handled is dropped and its error returned,
+    /// the Client keeps working with the following lines.
+    ///
+    /// A line that doesn't fit in the RX buffer is discarded, and
+    /// [`Error::LineTooLong`] is returned once its end is received. The
+    /// Client keeps working with the following lines.
+    ///
+    /// The `mining.ping` requests of the Pool are answered automatically.
+    /// On [`Message::Reconnect`] the caller should close the connection and
+    /// open a new one with a new Client, after the given wait.
+    ///
+    /// When the transport fails, here or when sending a request, or when the
+    /// Pool closes the connection, [`Message::Disconnected`] is returned once
+    /// and then [`Error::NotConnected`] until the Client is
+    /// [reset](Client::reset).
     pub async fn poll_message(&mut self) -> Result<Option<Message>> {
+        if self.state == ClientState::Disconnected {
+            return match self.disconnect_error.take() {
+                Some(error) => Ok(Some(Message::Disconnected { error })),
+                None => Err(Error::NotConnected),
+            };
+        }
+
+        if let Some(error) = self.pending_error.take() {
+            return Err(error);
+        }
+
+        if let Some(discarded) = self.rx_discarded {
+            match self.rx_buf[..self.rx_free_pos]
+                .iter()
+                .position(|&c| c == b'\n')
+            {
+                Some(pos) => {
+                    self.rx_buf.copy_within(pos + 1..self.rx_free_pos, 0);
+                    self.rx_free_pos -= pos + 1;
+                    self.rx_discarded = None;
+                    self.lines_too_long += 1;
+                    let needed = discarded + pos + 1;
+                    error!(
+                        "Line of {} bytes discarded, RX buffer is {} bytes",
+                        needed, RX_BUF_SIZE
+                    );
+                    return Err(Error::LineTooLong { needed });
+                }
+                None => {
+                    self.rx_discarded = Some(discarded + self.rx_free_pos);
+                    self.rx_free_pos = 0;
+                }
+            }
+        }
+
         let mut msg = None;
+        let mut pong = None;
+        let mut error = None;
+        // Like `?`, but the line is consumed before the error is returned,
+        // otherwise every poll would parse it again.
+        macro_rules! try_line {
+            ($result:expr) => {
+                match $result {
+                    Ok(value) => value,
+                    Err(e) => {
+                        error = Some(e);
+                        break;
+                    }
+                }
+            };
+        }
         let mut start = 0;
-        while let Some(stop) = self.rx_buf[start..self.rx_free_pos]
+        while let Some(pos) = self.rx_buf[start..self.rx_free_pos]
             .iter()
             .position(|&c| c == b'\n')
         {
+            let stop = start + pos;
             let line = &self.rx_buf[start..stop];
             debug!(
                 "Received Message [{}..{}], free pos: {}",
                 start, stop, self.rx_free_pos
             );
             trace!("{:?}", line);
-            if let Some(id) = response::parse_id(line)? {
+            start = stop + 1;
+            let id = try_line!(response::parse_id(line));
+            if let Some(id) = id.filter(|_| notification::parse_method_name(line).is_err()) {
                 // it's a Response
                 match self.reqs.get(&id) {
-                    Some(ReqKind::Configure) => {
-                        self.configuration = Some(response::parse_configure(line)?);
+                    Some(&ReqKind::Configure { min_bit_count }) => {
+                        let configuration = try_line!(response::parse_configure(line));
+                        let available = configuration.effective_version_space();
+                        self.configuration = Some(configuration);
+                        self.state = ClientState::Configured;
                         self.reqs.remove(&id);
                         info!("Stratum v1 Client Configured");
                         msg = Some(Message::Configured);
+                        if let Some(requested) = min_bit_count {
+                            if available < requested as u32 {
+                                error!(
+                                    "Version rolling mask has {} bits, {} requested",
+                                    available, requested
+                                );
+                                // The configuration is kept so that the Client
+                                // can go on without version rolling.
+                                self.pending_error = Some(Error::InsufficientVersionBits {
+                                    requested,
+                                    available: available as u8,
+                                });
+                                break;
+                            }
+                        }
                     }
                     Some(ReqKind::Connect) => {
-                        let conn = response::parse_connect(line)?;
+                        let conn = try_line!(response::parse_connect(line));
                         self.subscriptions = conn.subscriptions;
-                        self.job_creator
-                            .set_extranonces(conn.extranonce1, conn.extranonce2_size)?;
-                        self.connected = true;
+                        try_line!(self
+                            .job_creator
+                            .set_extranonces(conn.extranonce1, conn.extranonce2_size));
+                        self.state = ClientState::Connected;
                         self.reqs.remove(&id);
                         info!("Stratum v1 Client Connected");
                         msg = Some(Message::Connected);
                     }
                     Some(ReqKind::Authorize) => {
-                        if response::parse_authorize(line)? {
-                            self.authorized = true;
+                        if try_line!(response::parse_authorize(line)) {
+                            self.state = ClientState::Authorized;
                             self.reqs.remove(&id);
                             info!("Stratum v1 Client Authorized");
                             msg = Some(Message::Authorized);
                         }
                     }
-                    Some(ReqKind::Submit) => {
-                        match response::parse_submit(line) {
-                            Ok(_) => {
-                                self.shares_accepted += 1;
-                                info!(
-                                    "Share #{} Accepted, count: {}/{}",
-                                    id, self.shares_accepted, self.shares_rejected
-                                );
+                    Some(ReqKind::Submit { .. }) => {
+                        let reject_code = match response::parse_submit(line) {
+                            Ok(_) => None,
+                            Err(Error::Pool { code, .. }) => Some(code),
+                            Err(e) => try_line!(Err(e)),
+                        };
+                        let Some(ReqKind::Submit {
+                            difficulty,
+                            job_id,
+                            rolled,
+                        }) = self.reqs.remove(&id)
+                        else {
+                            unreachable!("the request is a submit");
+                        };
+                        if reject_code.is_none() {
+                            self.shares_accepted += 1;
+                            if let Some(now) = self.time_source {
+                                self.share_history.push(now(), difficulty);
                             }
-                            Err(Error::Pool {
-                                code: _c, // TODO: use this code to differentiate why share has been rejected
-                                message: _,
-                                detail: _,
-                            }) => {
-                                self.shares_rejected += 1;
-                                info!(
-                                    "Share #{} Rejected, count: {}/{}",
-                                    id, self.shares_accepted, self.shares_rejected
-                                );
-                            }
-                            Err(e) => return Err(e),
+                            info!(
+                                "Share #{} Accepted, count: {}/{}",
+                                id, self.shares_accepted, self.shares_rejected
+                            );
+                        } else {
+                            self.shares_rejected += 1;
+                            info!(
+                                "Share #{} Rejected, count: {}/{}",
+                                id, self.shares_accepted, self.shares_rejected
+                            );
                         }
-                        self.reqs.remove(&id);
                         msg = Some(Message::Share {
                             accepted: self.shares_accepted,
                             rejected: self.shares_rejected,
+                            result: ShareResult {
+                                id,
+                                job_id,
+                                rolled,
+                                reject_code,
+                            },
                         });
                     }
-                    None => return Err(Error::IdNotFound(id)),
+                    None => try_line!(Err(Error::IdNotFound(id))),
                 }
+            } else if notification::parse_method(line) == Ok(Notification::Ping) {
+                // it's a Request, answered once the RX buffer is released
+                debug!("Ping {:?}", id);
+                pong = Some(id);
+                break;
             } else {
                 // it's a Notification
-                match notification::parse_method(line)? {
-                    Notification::SetVersionMask => {
-                        let mask = notification::parse_set_version_mask(line)?;
-                        self.job_creator.set_version_mask(mask);
-                        msg = Some(Message::VersionMask(mask));
-                        info!("Set Version Mask: 0x{:x}", mask);
-                    }
-                    Notification::SetDifficulty => {
-                        let diff = notification::parse_set_difficulty(line)?;
-                        msg = Some(Message::Difficulty(diff));
-                        info!("Set Difficulty: {}", diff);
-                    }
-                    Notification::Notify => {
-                        let work = notification::parse_notify(line)?;
-                        if work.clean_jobs {
-                            msg = Some(Message::CleanJobs);
+                match Self::handle_notification(&mut self.job_creator, line) {
+                    Ok(Some(m)) => {
+                        if let Message::Difficulty(difficulty) = m {
+                            self.difficulty = difficulty;
                         }
-                        info!("New Work: {:?}", work);
-                        self.job_creator.set_work(work)?;
+                        msg = Some(m);
+                    }
+                    Ok(None) => {}
+                    Err(error) => {
+                        let method = notification::parse_method_name(line).unwrap_or_default();
+                        error!("Notification {} dropped: {:?}", method.as_str(), error);
+                        msg = Some(Message::ProtocolError { method, error });
+                        // Return now so that the error is not hidden by the
+                        // following messages, they will be handled on the
+                        // next poll.
+                        break;
                     }
                 }
             }
-            start = stop + 1;
         }
-        if start > 0 && self.rx_free_pos > start {
+        if start > 0 {
             debug!("copy {} bytes @0", self.rx_free_pos - start);
             self.rx_buf.copy_within(start..self.rx_free_pos, 0);
             self.rx_free_pos -= start;
         }
-        if self.network_conn.read_ready().map_err(|_| Error::Network)? {
+        if let Some(error) = error {
+            // The messages of the previous lines are not lost.
+            if msg.is_none() {
+                return Err(error);
+            }
+            self.pending_error = Some(error);
+        }
+        if let Some(id) = pong {
+            let n = request::pong(id, self.tx_buf.as_mut_slice())?;
+            debug!("Send Pong: {} bytes", n);
+            self.send_req(n).await?;
+        }
+        if let Err(error) = self.read().await {
+            self.transport_failed(error);
+            if msg.is_none() {
+                msg = self
+                    .disconnect_error
+                    .take()
+                    .map(|error| Message::Disconnected { error });
+            }
+            return Ok(msg);
+        }
+        if self.rx_free_pos == RX_BUF_SIZE && !self.rx_buf.contains(&b'\n') {
+            // The line can't fit, drop what we have to make room and
+            // discard the rest of the line as it is received.
+            warn!("RX buffer full without a complete line, discarding it");
+            self.rx_discarded = Some(self.rx_discarded.unwrap_or(0) + RX_BUF_SIZE);
+            self.rx_free_pos = 0;
+        }
+        Ok(msg)
+    }
+
+    async fn read(&mut self) -> Result<()> {
+        let free = RX_BUF_SIZE - self.rx_free_pos;
+        if free > 0
+            && self
+                .network_conn
+                .read_ready()
+                .map_err(|e| NetworkOp::ReadReady.error(e))?
+        {
             let n = self
                 .network_conn
                 .read(self.rx_buf[self.rx_free_pos..].as_mut())
                 .await
-                .map_err(|_| Error::Network)?;
+                .map_err(|e| NetworkOp::Read.error(e))?;
+            if n == 0 {
+                // The Pool closed the connection.
+                return Err(Error::Network {
+                    op: NetworkOp::Read,
+                    kind: ErrorKind::ConnectionAborted,
+                });
+            }
             debug!("read {} bytes @{}", n, self.rx_free_pos);
             trace!("{:?}", &self.rx_buf[self.rx_free_pos..self.rx_free_pos + n]);
             self.rx_free_pos += n;
         }
-        Ok(msg)
+        Ok(())
+    }
+
+    /// Record a transport error, reported by [`Client::poll_message`].
+    fn transport_failed(&mut self, error: Error) {
+        error!("Transport failed: {:?}", error);
+        self.state = ClientState::Disconnected;
+        self.disconnect_error = Some(error);
     }
 
     fn prepare_req(&mut self, req_kind: ReqKind) -> Result<()> {
+        if self.state == ClientState::Disconnected {
+            return Err(Error::NotConnected);
+        }
         self.req_id += 1;
         self.reqs
             .insert(self.req_id, req_kind)
@@ -210,13 +536,56 @@ impl<C: Read + ReadReady + Write, const RX_BUF_SIZE: usize, const TX_BUF_SIZE: u
         Ok(())
     }
 
+    fn handle_notification(job_creator: &mut JobCreator, line: &[u8]) -> Result<Option<Message>> {
+        let mut msg = None;
+        match notification::parse_method(line)? {
+            Notification::SetVersionMask => {
+                let mask = notification::parse_set_version_mask(line)?;
+                job_creator.set_version_mask(mask);
+                msg = Some(Message::VersionMask(mask));
+                info!("Set Version Mask: 0x{:x}", mask);
+            }
+            Notification::SetDifficulty => {
+                let diff = notification::parse_set_difficulty(line)?;
+                msg = Some(Message::Difficulty(diff));
+                info!("Set Difficulty: {}", diff);
+            }
+            Notification::Notify => {
+                let work = notification::parse_notify(line)?;
+                if work.clean_jobs {
+                    msg = Some(Message::CleanJobs);
+                }
+                info!("New Work: {:?}", work);
+                job_creator.set_work(work)?;
+            }
+            Notification::Reconnect => {
+                let reconnect = notification::parse_reconnect(line)?;
+                info!("Reconnect: {:?}", reconnect);
+                msg = Some(Message::Reconnect {
+                    host: reconnect.host,
+                    port: reconnect.port,
+                    wait: reconnect.wait,
+                });
+            }
+            // Answered by `poll_message`.
+            Notification::Ping => {}
+        }
+        Ok(msg)
+    }
+
     async fn send_req(&mut self, req_len: usize) -> Result<()> {
         self.tx_buf[req_len] = 0x0a;
         trace!("{:?}", &self.tx_buf[..req_len + 1]);
-        self.network_conn
+        if let Err(e) = self
+            .network_conn
             .write_all(&self.tx_buf[..req_len + 1])
             .await
-            .map_err(|_| Error::Network)
+        {
+            let error = NetworkOp::Write.error(e);
+            self.transport_failed(error.clone());
+            return Err(error);
+        }
+        Ok(())
     }
 
     /// # Configure Client
@@ -229,7 +598,8 @@ impl<C: Read + ReadReady + Write, const RX_BUF_SIZE: usize, const TX_BUF_SIZE: u
         if self.configuration.is_some() {
             return Err(Error::AlreadyConfigured);
         }
-        self.prepare_req(ReqKind::Configure)?;
+        let min_bit_count = exts.version_rolling.as_ref().and_then(|v| v.min_bit_count);
+        self.prepare_req(ReqKind::Configure { min_bit_count })?;
         let n = request::configure(self.req_id, exts, self.tx_buf.as_mut_slice())?;
         debug!("Send Configure: {} bytes, id = {}", n, self.req_id);
         self.send_req(n).await
@@ -245,7 +615,7 @@ impl<C: Read + ReadReady + Write, const RX_BUF_SIZE: usize, const TX_BUF_SIZE: u
         if self.configuration.is_none() {
             return Err(Error::NotConfigured);
         }
-        if self.connected {
+        if matches!(self.state, ClientState::Connected | ClientState::Authorized) {
             return Err(Error::AlreadyConnected);
         }
         self.prepare_req(ReqKind::Connect)?;
@@ -264,10 +634,10 @@ impl<C: Read + ReadReady + Write, const RX_BUF_SIZE: usize, const TX_BUF_SIZE: u
     /// pass: a string with user password.
     ///
     pub async fn send_authorize(&mut self, user: String<64>, pass: String<64>) -> Result<()> {
-        if !self.connected {
+        if !matches!(self.state, ClientState::Connected | ClientState::Authorized) {
             return Err(Error::NotConnected);
         }
-        if self.authorized {
+        if self.state == ClientState::Authorized {
             return Err(Error::AlreadyAuthorized);
         }
         self.prepare_req(ReqKind::Authorize)?;
@@ -292,10 +662,14 @@ impl<C: Read + ReadReady + Write, const RX_BUF_SIZE: usize, const TX_BUF_SIZE: u
     /// version_bits: an optional 32-bits unsigned integer with the share's version_bits.
     ///
     pub async fn send_submit(&mut self, share: Share) -> Result<()> {
-        if !self.authorized {
+        if self.state != ClientState::Authorized {
             return Err(Error::Unauthorized);
         }
-        self.prepare_req(ReqKind::Submit)?;
+        self.prepare_req(ReqKind::Submit {
+            difficulty: self.difficulty,
+            job_id: share.job_id.clone(),
+            rolled: self.job_creator.rolled(&share),
+        })?;
         let n = request::submit(
             self.req_id,
             self.user.clone(),
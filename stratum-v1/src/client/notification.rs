@@ -4,7 +4,7 @@
 use crate::{Error, Result};
 use faster_hex::hex_decode;
 use heapless::{String, Vec};
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 use super::request::Request;
 
@@ -28,22 +28,38 @@ pub enum Notification {
     SetVersionMask,
     Notify,
     SetDifficulty,
+    Reconnect,
+    Ping,
 }
 
-pub(crate) fn parse_method(resp: &[u8]) -> Result<Notification> {
+/// Parameters of a `client.reconnect` notification.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Reconnect {
+    /// Host to connect to, the current one if `None`.
+    pub host: Option<String<64>>,
+    /// Port to connect to, the current one if `None`.
+    pub port: Option<u16>,
+    /// Seconds to wait before connecting, none if `None`.
+    pub wait: Option<u32>,
+}
+
+pub(crate) fn parse_method_name(resp: &[u8]) -> Result<String<32>> {
     #[derive(Debug, Deserialize)]
     #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
     struct MethodOnly {
         method: String<32>,
     }
-    match serde_json_core::from_slice::<MethodOnly>(resp)?
-        .0
-        .method
-        .as_str()
-    {
+    Ok(serde_json_core::from_slice::<MethodOnly>(resp)?.0.method)
+}
+
+pub(crate) fn parse_method(resp: &[u8]) -> Result<Notification> {
+    match parse_method_name(resp)?.as_str() {
         "mining.set_version_mask" => Ok(Notification::SetVersionMask),
         "mining.notify" => Ok(Notification::Notify),
         "mining.set_difficulty" => Ok(Notification::SetDifficulty),
+        "client.reconnect" => Ok(Notification::Reconnect),
+        "mining.ping" => Ok(Notification::Ping),
         _ => Err(Error::UnknownNotification),
     }
 }
@@ -160,6 +176,62 @@ pub(crate) fn parse_set_difficulty(resp: &[u8]) -> Result<f64> {
         .ok_or(Error::VecEmpty)
 }
 
+pub(crate) fn parse_reconnect(resp: &[u8]) -> Result<Reconnect> {
+    // Every parameter is optional, and the port is a number or a string
+    // depending on the Pool.
+    struct ReconnectRaw<P>(Option<String<64>>, Option<P>, Option<u32>);
+
+    impl<'de, P: Deserialize<'de>> Deserialize<'de> for ReconnectRaw<P> {
+        fn deserialize<D: Deserializer<'de>>(der: D) -> core::result::Result<Self, D::Error> {
+            use core::marker::PhantomData;
+            use serde::de::{SeqAccess, Visitor};
+
+            struct SeqVisit<P>(PhantomData<P>);
+
+            impl<'de, P: Deserialize<'de>> Visitor<'de> for SeqVisit<P> {
+                type Value = ReconnectRaw<P>;
+
+                fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                    formatter.write_str("[host, port, wait]")
+                }
+
+                fn visit_seq<A: SeqAccess<'de>>(
+                    self,
+                    mut seq: A,
+                ) -> core::result::Result<Self::Value, A::Error> {
+                    Ok(ReconnectRaw(
+                        seq.next_element()?,
+                        seq.next_element()?,
+                        seq.next_element()?,
+                    ))
+                }
+            }
+
+            der.deserialize_seq(SeqVisit(PhantomData))
+        }
+    }
+
+    let raw = match serde_json_core::from_slice::<Request<ReconnectRaw<u16>>>(resp) {
+        Ok((req, _)) => req.params.ok_or(Error::RpcBadRequest)?,
+        Err(_) => {
+            let raw = serde_json_core::from_slice::<Request<ReconnectRaw<String<5>>>>(resp)?
+                .0
+                .params
+                .ok_or(Error::RpcBadRequest)?;
+            let port = match raw.1 {
+                Some(port) => Some(port.parse().map_err(|_| Error::RpcBadRequest)?),
+                None => None,
+            };
+            ReconnectRaw(raw.0, port, raw.2)
+        }
+    };
+    Ok(Reconnect {
+        host: raw.0.filter(|host| !host.is_empty()),
+        port: raw.1,
+        wait: raw.2,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use core::str::FromStr;
@@ -413,6 +485,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_reconnect() {
+        assert_eq!(
+            parse_reconnect(
+                br#"{"params": ["pool.example.com", 3333, 5], "id": null, "method": "client.reconnect"}"#
+            ),
+            Ok(Reconnect {
+                host: Some(hstring!(64, "pool.example.com")),
+                port: Some(3333),
+                wait: Some(5),
+            })
+        );
+
+        assert_eq!(
+            parse_reconnect(
+                br#"{"params": ["pool.example.com", "3334"], "id": null, "method": "client.reconnect"}"#
+            ),
+            Ok(Reconnect {
+                host: Some(hstring!(64, "pool.example.com")),
+                port: Some(3334),
+                wait: None,
+            })
+        );
+
+        assert_eq!(
+            parse_reconnect(br#"{"params": [], "id": null, "method": "client.reconnect"}"#),
+            Ok(Reconnect {
+                host: None,
+                port: None,
+                wait: None,
+            })
+        );
+
+        assert_eq!(
+            parse_reconnect(
+                br#"{"params": ["pool.example.com", "port"], "id": null, "method": "client.reconnect"}"#
+            ),
+            Err(Error::RpcBadRequest)
+        );
+    }
+
     #[test]
     fn test_parse_method() {
         assert_eq!(
@@ -434,6 +547,11 @@ mod tests {
             Ok(Notification::SetDifficulty)
         );
 
+        assert_eq!(
+            parse_method(br#"{"params": [], "id": 5, "method": "mining.ping"}"#),
+            Ok(Notification::Ping)
+        );
+
         assert_eq!(
             parse_method(br#"{"params": [], "id": null, "method": "mining.broken"}"#),
             Err(Error::UnknownNotification)
@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use super::job::Rolled;
 use crate::{Error, Result};
 use faster_hex::hex_string;
 use heapless::{String, Vec};
@@ -9,10 +10,16 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub(crate) enum ReqKind {
-    Configure,
+    Configure {
+        min_bit_count: Option<u8>,
+    },
     Connect,
     Authorize,
-    Submit,
+    Submit {
+        difficulty: f64,
+        job_id: String<64>,
+        rolled: Rolled,
+    },
 }
 
 ///Request representation.
@@ -85,6 +92,19 @@ pub struct Extensions {
     pub info: Option<Info>,
 }
 
+impl Extensions {
+    /// Number of version bits that can be rolled, as given by the
+    /// negotiated version rolling mask.
+    ///
+    /// Returns 0 when version rolling is not enabled or no mask was given.
+    pub fn effective_version_space(&self) -> u32 {
+        self.version_rolling
+            .as_ref()
+            .and_then(|v| v.mask)
+            .map_or(0, u32::count_ones)
+    }
+}
+
 pub(crate) fn configure(id: u64, exts: Extensions, buf: &mut [u8]) -> Result<usize> {
     let method = "mining.configure".try_into().unwrap();
 
@@ -252,6 +272,24 @@ pub(crate) fn submit(id: u64, user: String<64>, share: Share, buf: &mut [u8]) ->
     serde_json_core::to_slice(&req, buf).map_err(|_| Error::JsonBufferFull)
 }
 
+/// Response to a `mining.ping` request of the Pool.
+pub(crate) fn pong(id: Option<u64>, buf: &mut [u8]) -> Result<usize> {
+    #[derive(Debug, Serialize)]
+    #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+    struct Pong {
+        id: Option<u64>,
+        result: &'static str,
+        error: Option<()>,
+    }
+
+    let resp = Pong {
+        id,
+        result: "pong",
+        error: None,
+    };
+    serde_json_core::to_slice(&resp, buf).map_err(|_| Error::JsonBufferFull)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -366,4 +404,11 @@ mod tests {
         assert_eq!(len.unwrap(), 97);
         assert_eq!(&buf[..97], br#"{"id":1,"method":"mining.submit","params":["slush.miner1","bf","00000001","504e86ed","b2957c02"]}"#);
     }
+
+    #[test]
+    fn test_pong() {
+        let mut buf = [0u8; 64];
+        assert_eq!(pong(Some(7), &mut buf), Ok(37));
+        assert_eq!(&buf[..37], br#"{"id":7,"result":"pong","error":null}"#);
+    }
 }
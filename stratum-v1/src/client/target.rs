@@ -0,0 +1,142 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// A 256-bit target, a hash meets it if it is not bigger.
+///
+/// The bytes are in little-endian order, as the block header hashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Target([u8; 32]);
+
+impl Target {
+    /// The biggest target, met by every hash.
+    pub const MAX: Target = Target([0xff; 32]);
+
+    /// Target of a share of `difficulty`, as given by `mining.set_difficulty`.
+    ///
+    /// The target of the difficulty 1 is `0xffff * 2^208`. A difficulty that
+    /// is not positive gives [`Target::MAX`].
+    pub fn from_difficulty(difficulty: f64) -> Target {
+        if difficulty.is_nan() || difficulty <= 0.0 {
+            return Target::MAX;
+        }
+        let value = 65535.0 / difficulty;
+        if !value.is_finite() {
+            return Target::MAX;
+        }
+        // value = mantissa * 2^(exponent - 1075)
+        let bits = value.to_bits();
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exponent) = match ((bits >> 52) & 0x7ff) as i32 {
+            0 => (fraction, 1),
+            exponent => (fraction | (1 << 52), exponent),
+        };
+        Target::from_shifted(mantissa, exponent - 1075 + 208)
+    }
+
+    /// Target encoded in the compact `nbits` field of a block header.
+    ///
+    /// A negative target gives a zero target, met by no hash.
+    pub fn from_nbits(nbits: u32) -> Target {
+        if nbits & 0x0080_0000 != 0 {
+            return Target([0; 32]);
+        }
+        let exponent = (nbits >> 24) as i32;
+        let mantissa = u64::from(nbits & 0x007f_ffff);
+        Target::from_shifted(mantissa, 8 * (exponent - 3))
+    }
+
+    /// Target of `mantissa * 2^shift`, saturated to [`Target::MAX`].
+    fn from_shifted(mantissa: u64, shift: i32) -> Target {
+        if shift < 0 {
+            let mantissa = mantissa.checked_shr(shift.unsigned_abs()).unwrap_or(0);
+            return Target::from_shifted(mantissa, 0);
+        }
+        if mantissa == 0 {
+            return Target([0; 32]);
+        }
+        if shift as u32 + (64 - mantissa.leading_zeros()) > 256 {
+            return Target::MAX;
+        }
+        let mut target = [0; 32];
+        let wide = u128::from(mantissa) << (shift % 8);
+        for (byte, &b) in target[shift as usize / 8..]
+            .iter_mut()
+            .zip(wide.to_le_bytes().iter())
+        {
+            *byte = b;
+        }
+        Target(target)
+    }
+
+    /// Returns `true` if `hash`, in little-endian order, is not bigger than
+    /// the target.
+    pub fn is_met_by(&self, hash: &[u8; 32]) -> bool {
+        hash.iter().rev().le(self.0.iter().rev())
+    }
+
+    /// Returns the target in little-endian order.
+    pub fn to_le_bytes(self) -> [u8; 32] {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(shift: usize, value: &[u8]) -> Target {
+        let mut target = [0; 32];
+        target[shift / 8..shift / 8 + value.len()].copy_from_slice(value);
+        Target(target)
+    }
+
+    #[test]
+    fn test_from_difficulty() {
+        assert_eq!(Target::from_difficulty(1.0), target(208, &[0xff, 0xff]));
+        assert_eq!(
+            Target::from_difficulty(1.0),
+            Target::from_nbits(0x1d00_ffff)
+        );
+        assert_eq!(
+            Target::from_difficulty(2.0),
+            target(200, &[0x80, 0xff, 0x7f])
+        );
+        assert_eq!(
+            Target::from_difficulty(65536.0),
+            target(184, &[0x00, 0xff, 0xff])
+        );
+        assert_eq!(Target::from_difficulty(0.0), Target::MAX);
+        assert_eq!(Target::from_difficulty(f64::NAN), Target::MAX);
+        assert_eq!(Target::from_difficulty(1e-70), Target::MAX);
+        assert_eq!(Target::from_difficulty(f64::INFINITY), Target([0; 32]));
+    }
+
+    #[test]
+    fn test_from_nbits() {
+        assert_eq!(
+            Target::from_nbits(0x1703_1abe),
+            target(160, &[0xbe, 0x1a, 0x03])
+        );
+        assert_eq!(
+            Target::from_nbits(0x0312_3456),
+            target(0, &[0x56, 0x34, 0x12])
+        );
+        assert_eq!(Target::from_nbits(0x0212_3456), target(0, &[0x34, 0x12]));
+        assert_eq!(Target::from_nbits(0x0492_3456), Target([0; 32]));
+        assert_eq!(Target::from_nbits(0xff12_3456), Target::MAX);
+    }
+
+    #[test]
+    fn test_is_met_by() {
+        let target = Target::from_difficulty(1.0);
+        let mut hash = [0; 32];
+        assert!(target.is_met_by(&hash));
+        hash[..27].fill(0xff);
+        assert!(target.is_met_by(&hash));
+        hash[0] = 0;
+        hash[28] = 1;
+        assert!(!target.is_met_by(&hash));
+        assert!(Target::MAX.is_met_by(&[0xff; 32]));
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use derive_more::From;
+use embedded_io_async::ErrorKind;
 use heapless::String;
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -45,6 +46,14 @@ pub enum Error {
 
     NoWork,
 
+    /// Pool version rolling mask has fewer bits than the requested minimum
+    InsufficientVersionBits {
+        /// Minimum number of bits requested in the configure request
+        requested: u8,
+        /// Number of bits in the negotiated mask
+        available: u8,
+    },
+
     /// Pool reported an error
     Pool {
         code: isize,
@@ -53,12 +62,26 @@ pub enum Error {
     },
 
     /// Network error
-    // #[from]
-    // Network(embedded_io::ErrorKind),
-    Network,
+    Network {
+        /// The transport operation that failed
+        op: NetworkOp,
+        /// The kind of the transport error
+        kind: ErrorKind,
+    },
 
     IdNotFound(u64),
 
+    /// A line from the Pool didn't fit in the RX buffer and was discarded
+    LineTooLong {
+        /// Length of the line, including the new line character
+        needed: usize,
+    },
+
+    /// A serialized Job has an unsupported format version
+    JobVersion(u8),
+    /// A serialized Job has a bad length, CRC or field
+    JobCorrupted,
+
     /// correspond to serde_json_core::ser:Error::BufferFull
     JsonBufferFull,
     /// correspond to all serde_json_core::de:Error
@@ -73,6 +96,28 @@ pub enum Error {
     HexError(faster_hex::Error),
 }
 
+/// Transport operation reported in [`Error::Network`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum NetworkOp {
+    /// Checking if the transport has data to read
+    ReadReady,
+    /// Reading from the transport
+    Read,
+    /// Writing to the transport
+    Write,
+}
+
+impl NetworkOp {
+    /// Build an [`Error::Network`] for this operation from a transport error
+    pub(crate) fn error<E: embedded_io_async::Error>(self, e: E) -> Error {
+        Error::Network {
+            op: self,
+            kind: e.kind(),
+        }
+    }
+}
+
 #[rustversion::since(1.81)]
 impl core::error::Error for Error {}
 
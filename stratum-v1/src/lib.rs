@@ -12,8 +12,16 @@
 #![macro_use]
 pub(crate) mod fmt;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 mod client;
 mod error;
 
-pub use client::{Client, Extensions, Info, Job, Message, Share, VersionRolling};
-pub use error::{Error, Result};
+#[cfg(feature = "std")]
+pub use client::mock;
+pub use client::{
+    Client, ClientState, Extensions, Info, Job, Message, Rolled, Share, ShareResult, Target,
+    VersionRolling, HASHRATE_HISTORY_LEN, JOB_FORMAT_VERSION, JOB_SERIALIZED_LEN,
+};
+pub use error::{Error, NetworkOp, Result};
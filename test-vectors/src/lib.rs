@@ -152,6 +152,23 @@ mod blockchain_commons {
             vectors
         }
     }
+
+    /// BCR-2020-010 output descriptor vectors.
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct OutputDescriptorVector {
+        pub name: String,
+        pub descriptor: String,
+        #[serde(with = "faster_hex::nopfx_ignorecase")]
+        pub as_cbor: Vec<u8>,
+    }
+
+    impl OutputDescriptorVector {
+        pub fn new() -> Vec<Self> {
+            serde_json::from_slice(include_bytes!("../data/bcr-2020-010.json"))
+                .expect("file should be valid JSON")
+        }
+    }
 }
 
 #[cfg(feature = "blockchain-commons")]
@@ -14,6 +14,12 @@ impl TestVectors {
     }
 }
 
+impl Default for TestVectors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct TestVector {
     pub description: String,
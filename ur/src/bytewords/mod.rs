@@ -23,6 +23,7 @@ use itertools::Either;
 /// The three different `bytewords` encoding styles.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum Style {
     /// Four-letter words, separated by spaces.
     Standard,
@@ -44,11 +45,26 @@ impl Style {
 
 /// The different errors that can be returned when decoding.
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum DecodeError {
     /// Usually indicates a wrong encoding [`Style`] was passed.
     InvalidWord {
-        /// Position where the invalid word was encountered.
+        /// Position where the invalid word was encountered, counted in
+        /// words, or `None` if it is part of the checksum.
         position: Option<usize>,
+        /// Byte offset of the invalid word in the bytewords string.
+        offset: usize,
+    },
+    /// An invalid two-character token was found in a [`Style::Minimal`]
+    /// string.
+    InvalidMinimalWord {
+        /// Position where the invalid token was encountered, counted in
+        /// words, or `None` if it is part of the checksum.
+        position: Option<usize>,
+        /// Byte offset of the invalid token in the bytewords string.
+        offset: usize,
+        /// The offending token.
+        token: [u8; 2],
     },
     /// The CRC32 checksum doesn't validate.
     InvalidChecksum {
@@ -72,13 +88,65 @@ pub enum DecodeError {
     },
 }
 
+impl DecodeError {
+    /// Stable numeric code of the error.
+    ///
+    /// Useful to report errors on targets where formatting the error with
+    /// [`Display`](fmt::Display) is too expensive.
+    pub const fn code(&self) -> u8 {
+        match self {
+            DecodeError::InvalidWord { .. } => 1,
+            DecodeError::InvalidMinimalWord { .. } => 2,
+            DecodeError::InvalidChecksum { .. } => 3,
+            DecodeError::ChecksumNotPresent => 4,
+            DecodeError::InvalidLength => 5,
+            DecodeError::NonAscii => 6,
+            DecodeError::NotEnoughSpace { .. } => 7,
+        }
+    }
+
+    /// Byte offset in the bytewords string where the error was found, if
+    /// applicable.
+    pub const fn offset(&self) -> Option<usize> {
+        match self {
+            DecodeError::InvalidWord { offset, .. }
+            | DecodeError::InvalidMinimalWord { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for DecodeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DecodeError::InvalidWord {
                 position: Some(position),
-            } => write!(f, "Invalid word found at position {position}"),
-            DecodeError::InvalidWord { position: None } => write!(f, "Invalid word found"),
+                offset,
+            } => write!(
+                f,
+                "Invalid word found at position {position} (offset {offset})"
+            ),
+            DecodeError::InvalidWord {
+                position: None,
+                offset,
+            } => write!(f, "Invalid checksum word found at offset {offset}"),
+            DecodeError::InvalidMinimalWord {
+                position,
+                offset,
+                token,
+            } => {
+                let token = core::str::from_utf8(token).unwrap_or("??");
+                match position {
+                    Some(position) => write!(
+                        f,
+                        "Invalid minimal word '{token}' found at position {position} (offset {offset})"
+                    ),
+                    None => write!(
+                        f,
+                        "Invalid minimal checksum word '{token}' found at offset {offset}"
+                    ),
+                }
+            }
             DecodeError::InvalidChecksum {
                 expected,
                 calculated,
@@ -100,8 +168,12 @@ impl fmt::Display for DecodeError {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
 /// The errors that can be returned when encoding.
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum EncodeError {
     /// Not enough space to decode the bytewords into.
     NotEnoughSpace,
@@ -149,7 +221,7 @@ pub fn decode(encoded: &str, style: Style) -> Result<alloc::vec::Vec<u8>, Decode
     let (bytes, expected_checksum) = decoder(encoded, style)?;
     let bytes = bytes
         .enumerate()
-        .map(|(i, b)| b.ok_or(DecodeError::InvalidWord { position: Some(i) }))
+        .map(|(i, b)| b.map_err(|e| e.at(i)))
         .collect::<Result<alloc::vec::Vec<u8>, _>>()?;
 
     let calculated_checksum = CRC32.checksum(&bytes).to_be_bytes();
@@ -166,12 +238,28 @@ pub fn decode(encoded: &str, style: Style) -> Result<alloc::vec::Vec<u8>, Decode
 /// Try to validate a bytewords encoded string and calculate its length in
 /// bytes.
 pub fn validate(encoded: &str, style: Style) -> Result<usize, DecodeError> {
+    validate_with_head(encoded, style, &mut [])
+}
+
+/// Same as [`validate`] but also copies the first decoded bytes to `head`.
+///
+/// Only `head.len()` bytes at most are copied, the rest of the payload is
+/// only used to calculate the checksum.
+pub(crate) fn validate_with_head(
+    encoded: &str,
+    style: Style,
+    head: &mut [u8],
+) -> Result<usize, DecodeError> {
     let (bytes, expected_checksum) = decoder(encoded, style)?;
     let mut digest = CRC32.digest();
 
     let mut n = 0;
     for maybe_byte in bytes {
-        digest.update(&[maybe_byte.ok_or(DecodeError::InvalidWord { position: Some(n) })?]);
+        let byte = maybe_byte.map_err(|e| e.at(n))?;
+        if let Some(b) = head.get_mut(n) {
+            *b = byte;
+        }
+        digest.update(&[byte]);
         n += 1;
     }
 
@@ -209,7 +297,7 @@ pub fn decode_to_slice(
     while let Some(maybe_byte) = bytes.next() {
         if n >= result.len() {
             while let Some(maybe_byte) = bytes.next() {
-                maybe_byte.ok_or(DecodeError::InvalidWord { position: Some(n) })?;
+                maybe_byte.map_err(|e| e.at(n))?;
 
                 n += 1;
             }
@@ -220,7 +308,7 @@ pub fn decode_to_slice(
             });
         }
 
-        result[n] = maybe_byte.ok_or(DecodeError::InvalidWord { position: Some(n) })?;
+        result[n] = maybe_byte.map_err(|e| e.at(n))?;
         n += 1;
     }
 
@@ -235,10 +323,134 @@ pub fn decode_to_slice(
     Ok(n)
 }
 
+/// Decodes a `bytewords`-encoded string lazily, byte by byte. The encoding
+/// must contain a four-byte checksum.
+///
+/// No buffer is needed to hold the payload, the bytes are returned as they
+/// are decoded and the checksum is verified after the last byte, so an
+/// [`DecodeError::InvalidChecksum`] may be returned as the last item. The
+/// iterator stops after the first error.
+///
+/// # Examples
+///
+/// ```
+/// # use foundation_ur::bytewords::{decode_iter, Style};
+/// let mut bytes = decode_iter("aeadaolazmjendeoti", Style::Minimal);
+/// assert_eq!(bytes.next(), Some(Ok(0)));
+/// assert_eq!(bytes.next(), Some(Ok(1)));
+/// ```
+pub fn decode_iter(
+    encoded: &str,
+    style: Style,
+) -> impl Iterator<Item = Result<u8, DecodeError>> + '_ {
+    let (mut state, mut error) = match decoder(encoded, style) {
+        Ok((bytes, expected_checksum)) => {
+            (Some((bytes, expected_checksum, CRC32.digest(), 0)), None)
+        }
+        Err(e) => (None, Some(e)),
+    };
+
+    core::iter::from_fn(move || {
+        if let Some(e) = error.take() {
+            return Some(Err(e));
+        }
+
+        let (bytes, _, digest, n) = state.as_mut()?;
+        match bytes.next() {
+            Some(Ok(byte)) => {
+                digest.update(&[byte]);
+                *n += 1;
+                Some(Ok(byte))
+            }
+            Some(Err(e)) => {
+                let e = e.at(*n);
+                state = None;
+                Some(Err(e))
+            }
+            None => {
+                let (_, expected_checksum, digest, _) = state.take().unwrap();
+                let calculated_checksum = digest.finalize().to_be_bytes();
+                if calculated_checksum != expected_checksum {
+                    Some(Err(DecodeError::InvalidChecksum {
+                        expected: expected_checksum,
+                        calculated: calculated_checksum,
+                    }))
+                } else {
+                    None
+                }
+            }
+        }
+    })
+}
+
+/// Returns the position of the word containing the byte at `offset` of a
+/// bytewords string encoded with `style`.
+///
+/// The position is also the index of the decoded byte, which allows to map
+/// an error reported by a scanner back to the byte of the fragment that
+/// was damaged in the QR frame.
+///
+/// # Examples
+///
+/// ```
+/// # use foundation_ur::bytewords::{position_from_offset, Style};
+/// assert_eq!(position_from_offset(6, Style::Minimal), 3);
+/// assert_eq!(position_from_offset(6, Style::Standard), 1);
+/// ```
+pub const fn position_from_offset(offset: usize, style: Style) -> usize {
+    match style {
+        Style::Standard | Style::Uri => offset / 5,
+        Style::Minimal => offset / 2,
+    }
+}
+
+/// A word that failed to decode, without the word position.
+struct InvalidKey {
+    offset: usize,
+    minimal_token: Option<[u8; 2]>,
+}
+
+impl InvalidKey {
+    fn new(encoded: &str, key: &str, style: Style) -> Self {
+        let offset = key.as_ptr() as usize - encoded.as_ptr() as usize;
+        let minimal_token = match style {
+            Style::Minimal => {
+                let mut token = [0; 2];
+                token.copy_from_slice(key.as_bytes());
+                Some(token)
+            }
+            _ => None,
+        };
+
+        Self {
+            offset,
+            minimal_token,
+        }
+    }
+
+    fn into_error(self, position: Option<usize>) -> DecodeError {
+        match self.minimal_token {
+            Some(token) => DecodeError::InvalidMinimalWord {
+                position,
+                offset: self.offset,
+                token,
+            },
+            None => DecodeError::InvalidWord {
+                position,
+                offset: self.offset,
+            },
+        }
+    }
+
+    fn at(self, position: usize) -> DecodeError {
+        self.into_error(Some(position))
+    }
+}
+
 fn decoder(
     encoded: &str,
     style: Style,
-) -> Result<(impl Iterator<Item = Option<u8>> + '_, [u8; 4]), DecodeError> {
+) -> Result<(impl Iterator<Item = Result<u8, InvalidKey>> + '_, [u8; 4]), DecodeError> {
     if !encoded.is_ascii() {
         return Err(DecodeError::NonAscii);
     }
@@ -265,14 +477,19 @@ fn decoder(
         }
     };
 
-    let mut bytes = keys.map(|k| indexes.get(k).copied());
+    let mut bytes = keys.map(move |k| {
+        indexes
+            .get(k)
+            .copied()
+            .ok_or_else(|| InvalidKey::new(encoded, k, style))
+    });
 
     // Consume checksum bytes before anything else.
     let mut checksum = [0u8; 4];
     for b in checksum.iter_mut().rev() {
         match bytes.next_back() {
-            Some(Some(byte)) => *b = byte,
-            Some(None) => return Err(DecodeError::InvalidWord { position: None }),
+            Some(Ok(byte)) => *b = byte,
+            Some(Err(e)) => return Err(e.into_error(None)),
             None => return Err(DecodeError::ChecksumNotPresent),
         }
     }
@@ -501,6 +718,80 @@ mod tests {
             decode("₿", Style::Minimal).unwrap_err(),
             DecodeError::NonAscii
         );
+
+        // invalid words
+        assert_eq!(
+            decode(
+                "able acid xxxx lava zoom jade need echo taxi",
+                Style::Standard
+            )
+            .unwrap_err(),
+            DecodeError::InvalidWord {
+                position: Some(2),
+                offset: 10,
+            }
+        );
+        assert_eq!(
+            decode("aeadaolazmjendeoxx", Style::Minimal).unwrap_err(),
+            DecodeError::InvalidMinimalWord {
+                position: None,
+                offset: 16,
+                token: *b"xx",
+            }
+        );
+        let e = decode("aeadxxlazmjendeoti", Style::Minimal).unwrap_err();
+        assert_eq!(
+            e,
+            DecodeError::InvalidMinimalWord {
+                position: Some(2),
+                offset: 4,
+                token: *b"xx",
+            }
+        );
+        assert_eq!(e.code(), 2);
+        assert_eq!(position_from_offset(e.offset().unwrap(), Style::Minimal), 2);
+    }
+
+    #[test]
+    fn test_decode_iter() {
+        let input = vec![0, 1, 2, 128, 255];
+        let decoded = decode_iter("aeadaolazmjendeoti", Style::Minimal)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, input);
+        assert_eq!(
+            decode_iter("able-acid-also-lava-zoom-jade-need-echo-taxi", Style::Uri)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            input
+        );
+        assert_eq!(
+            decode_iter(&encode(&[], Style::Minimal), Style::Minimal).count(),
+            0
+        );
+
+        // The payload is returned before the checksum is found to be bad.
+        let mut bytes = decode_iter("aeadaolazojendeowf", Style::Minimal);
+        assert_eq!(
+            bytes.by_ref().take(5).collect::<Result<Vec<_>, _>>(),
+            Ok(vec![0, 1, 2, 128, 251])
+        );
+        assert_eq!(
+            bytes.next(),
+            Some(Err(DecodeError::InvalidChecksum {
+                expected: [107, 155, 51, 243],
+                calculated: [108, 246, 247, 201]
+            }))
+        );
+        assert_eq!(bytes.next(), None);
+
+        let mut bytes = decode_iter("aeadxxlazmjendeoti", Style::Minimal);
+        assert_eq!(bytes.nth(2).unwrap().unwrap_err().offset(), Some(4));
+        assert_eq!(bytes.next(), None);
+
+        let mut bytes = decode_iter("₿", Style::Minimal);
+        assert_eq!(bytes.next(), Some(Err(DecodeError::NonAscii)));
+        assert_eq!(bytes.next(), None);
     }
 
     #[test]
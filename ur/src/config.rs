@@ -0,0 +1,270 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: MIT
+
+//! Compile-time checks of the `heapless` configurations.
+//!
+//! The capacities of the [`HeaplessEncoder`](crate::HeaplessEncoder) and
+//! [`HeaplessDecoder`](crate::HeaplessDecoder) are given as const
+//! generics, some combinations of them can't work and would only fail at
+//! runtime, for example when the decoder queue overflows.
+//!
+//! The `new` constructors of the `heapless` types already refuse to compile
+//! with an invalid configuration. The functions of this module can be used
+//! to check the configurations in `const` context, and the
+//! [`assert_encoder_config!`](crate::assert_encoder_config),
+//! [`assert_decoder_config!`](crate::assert_decoder_config) and
+//! [`assert_compatible_config!`](crate::assert_compatible_config) macros
+//! turn them into compile errors.
+//!
+//! # Examples
+//!
+//! ```
+//! const MAX_FRAGMENT_LEN: usize = 200;
+//! const MAX_SEQUENCE_COUNT: usize = 128;
+//!
+//! foundation_ur::assert_encoder_config!(MAX_FRAGMENT_LEN, MAX_SEQUENCE_COUNT);
+//! foundation_ur::assert_decoder_config!(MAX_FRAGMENT_LEN, MAX_SEQUENCE_COUNT, 8, 8);
+//! foundation_ur::assert_compatible_config!(
+//!     MAX_FRAGMENT_LEN,
+//!     MAX_SEQUENCE_COUNT,
+//!     MAX_FRAGMENT_LEN,
+//!     MAX_SEQUENCE_COUNT,
+//! );
+//! ```
+//!
+//! An invalid configuration doesn't compile:
+//!
+//! ```compile_fail
+//! // The queue can't hold the mixed parts that become simple at once.
+//! foundation_ur::assert_decoder_config!(200, 128, 16, 8);
+//! ```
+
+use core::fmt;
+
+/// An invalid `heapless` configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ConfigError {
+    /// `MAX_FRAGMENT_LEN` is zero.
+    ZeroFragmentLength,
+    /// `MAX_SEQUENCE_COUNT` is not a power of two greater than one, as
+    /// required by the index sets.
+    InvalidSequenceCount,
+    /// `QUEUE_SIZE` is smaller than `MAX_MIXED_PARTS` or zero.
+    QueueTooSmall,
+    /// The encoder `MAX_FRAGMENT_LEN` is bigger than the decoder one.
+    FragmentLengthMismatch,
+    /// The encoder `MAX_SEQUENCE_COUNT` is bigger than the decoder one.
+    SequenceCountMismatch,
+}
+
+impl ConfigError {
+    /// Panic with a description of the error.
+    ///
+    /// Used to turn the error into a compile error in `const` context.
+    pub const fn panic(self) -> ! {
+        match self {
+            ConfigError::ZeroFragmentLength => panic!("MAX_FRAGMENT_LEN must not be zero"),
+            ConfigError::InvalidSequenceCount => {
+                panic!("MAX_SEQUENCE_COUNT must be a power of two greater than one")
+            }
+            ConfigError::QueueTooSmall => {
+                panic!("QUEUE_SIZE must not be zero nor smaller than MAX_MIXED_PARTS")
+            }
+            ConfigError::FragmentLengthMismatch => panic!(
+                "the encoder MAX_FRAGMENT_LEN must not be bigger than the decoder MAX_FRAGMENT_LEN"
+            ),
+            ConfigError::SequenceCountMismatch => panic!(
+                "the encoder MAX_SEQUENCE_COUNT must not be bigger than the decoder MAX_SEQUENCE_COUNT"
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::ZeroFragmentLength => write!(f, "MAX_FRAGMENT_LEN is zero"),
+            ConfigError::InvalidSequenceCount => write!(
+                f,
+                "MAX_SEQUENCE_COUNT is not a power of two greater than one"
+            ),
+            ConfigError::QueueTooSmall => {
+                write!(f, "QUEUE_SIZE is zero or smaller than MAX_MIXED_PARTS")
+            }
+            ConfigError::FragmentLengthMismatch => write!(
+                f,
+                "the encoder MAX_FRAGMENT_LEN is bigger than the decoder MAX_FRAGMENT_LEN"
+            ),
+            ConfigError::SequenceCountMismatch => write!(
+                f,
+                "the encoder MAX_SEQUENCE_COUNT is bigger than the decoder MAX_SEQUENCE_COUNT"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConfigError {}
+
+/// Validate the capacities of a [`HeaplessEncoder`](crate::HeaplessEncoder).
+pub const fn validate_encoder(
+    max_fragment_len: usize,
+    max_sequence_count: usize,
+) -> Result<(), ConfigError> {
+    if max_fragment_len == 0 {
+        return Err(ConfigError::ZeroFragmentLength);
+    }
+
+    if max_sequence_count < 2 || !max_sequence_count.is_power_of_two() {
+        return Err(ConfigError::InvalidSequenceCount);
+    }
+
+    Ok(())
+}
+
+/// Validate the capacities of a [`HeaplessDecoder`](crate::HeaplessDecoder).
+///
+/// Every mixed part stored can become simple once a fragment is received,
+/// so the queue must be able to hold `max_mixed_parts` parts.
+pub const fn validate_decoder(
+    max_fragment_len: usize,
+    max_sequence_count: usize,
+    max_mixed_parts: usize,
+    queue_size: usize,
+) -> Result<(), ConfigError> {
+    if let Err(e) = validate_encoder(max_fragment_len, max_sequence_count) {
+        return Err(e);
+    }
+
+    if queue_size == 0 || queue_size < max_mixed_parts {
+        return Err(ConfigError::QueueTooSmall);
+    }
+
+    Ok(())
+}
+
+/// Validate that the parts of an encoder can be received by a decoder.
+pub const fn validate_compatible(
+    encoder_max_fragment_len: usize,
+    encoder_max_sequence_count: usize,
+    decoder_max_fragment_len: usize,
+    decoder_max_sequence_count: usize,
+) -> Result<(), ConfigError> {
+    if encoder_max_fragment_len > decoder_max_fragment_len {
+        return Err(ConfigError::FragmentLengthMismatch);
+    }
+
+    if encoder_max_sequence_count > decoder_max_sequence_count {
+        return Err(ConfigError::SequenceCountMismatch);
+    }
+
+    Ok(())
+}
+
+/// Panic if the configuration is not valid.
+///
+/// In `const` context this is a compile error.
+pub const fn assert_valid(result: Result<(), ConfigError>) {
+    if let Err(e) = result {
+        e.panic();
+    }
+}
+
+/// Assert at compile time that the capacities of a
+/// [`HeaplessEncoder`](crate::HeaplessEncoder) are valid.
+///
+/// The arguments are `MAX_FRAGMENT_LEN` and `MAX_SEQUENCE_COUNT`. See
+/// [`validate_encoder`](crate::config::validate_encoder).
+#[macro_export]
+macro_rules! assert_encoder_config {
+    ($max_fragment_len:expr, $max_sequence_count:expr $(,)?) => {
+        const _: () = $crate::config::assert_valid($crate::config::validate_encoder(
+            $max_fragment_len,
+            $max_sequence_count,
+        ));
+    };
+}
+
+/// Assert at compile time that the capacities of a
+/// [`HeaplessDecoder`](crate::HeaplessDecoder) are valid.
+///
+/// The arguments are `MAX_FRAGMENT_LEN`, `MAX_SEQUENCE_COUNT`,
+/// `MAX_MIXED_PARTS` and `QUEUE_SIZE`. See
+/// [`validate_decoder`](crate::config::validate_decoder).
+#[macro_export]
+macro_rules! assert_decoder_config {
+    ($max_fragment_len:expr, $max_sequence_count:expr, $max_mixed_parts:expr, $queue_size:expr $(,)?) => {
+        const _: () = $crate::config::assert_valid($crate::config::validate_decoder(
+            $max_fragment_len,
+            $max_sequence_count,
+            $max_mixed_parts,
+            $queue_size,
+        ));
+    };
+}
+
+/// Assert at compile time that the parts of an encoder can be received by
+/// a decoder.
+///
+/// The arguments are the encoder `MAX_FRAGMENT_LEN` and
+/// `MAX_SEQUENCE_COUNT` followed by the decoder ones. See
+/// [`validate_compatible`](crate::config::validate_compatible).
+#[macro_export]
+macro_rules! assert_compatible_config {
+    (
+        $encoder_max_fragment_len:expr,
+        $encoder_max_sequence_count:expr,
+        $decoder_max_fragment_len:expr,
+        $decoder_max_sequence_count:expr $(,)?
+    ) => {
+        const _: () = $crate::config::assert_valid($crate::config::validate_compatible(
+            $encoder_max_fragment_len,
+            $encoder_max_sequence_count,
+            $decoder_max_fragment_len,
+            $decoder_max_sequence_count,
+        ));
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate_encoder(32, 32), Ok(()));
+        assert_eq!(
+            validate_encoder(0, 32),
+            Err(ConfigError::ZeroFragmentLength)
+        );
+        assert_eq!(
+            validate_encoder(32, 1),
+            Err(ConfigError::InvalidSequenceCount)
+        );
+        assert_eq!(
+            validate_encoder(32, 24),
+            Err(ConfigError::InvalidSequenceCount)
+        );
+
+        assert_eq!(validate_decoder(32, 32, 8, 8), Ok(()));
+        assert_eq!(
+            validate_decoder(32, 32, 16, 8),
+            Err(ConfigError::QueueTooSmall)
+        );
+        assert_eq!(
+            validate_decoder(32, 32, 0, 0),
+            Err(ConfigError::QueueTooSmall)
+        );
+
+        assert_eq!(validate_compatible(30, 16, 32, 32), Ok(()));
+        assert_eq!(
+            validate_compatible(64, 16, 32, 32),
+            Err(ConfigError::FragmentLengthMismatch)
+        );
+        assert_eq!(
+            validate_compatible(30, 64, 32, 32),
+            Err(ConfigError::SequenceCountMismatch)
+        );
+    }
+}
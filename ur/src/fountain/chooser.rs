@@ -7,7 +7,7 @@
 use crate::{
     collections::{Set, Vec},
     fountain::{sampler, sampler::BaseWeighted},
-    xoshiro::Xoshiro256,
+    prng::{shuffle, Xoshiro256},
 };
 
 /// A fragment chooser.
@@ -76,7 +76,7 @@ impl<T: Types> BaseFragmentChooser<T> {
         self.indexes.clear();
         self.indexes.reserve(sequence_count.try_into().unwrap());
         self.indexes.extend(0..sequence_count.try_into().unwrap());
-        shuffle_indexes::<T>(&mut prng, &mut self.indexes, &mut self.shuffled, degree);
+        shuffle(&mut prng, &mut self.indexes, &mut self.shuffled, degree);
 
         set.extend(self.shuffled.iter().copied());
         set
@@ -91,27 +91,6 @@ fn choose_degree<T: Types>(
     sampler.set((0..sequence_count).map(|x| 1.0 / f64::from(x + 1)));
     usize::try_from(sampler.next(prng) + 1).unwrap()
 }
-///
-/// # Errors
-///
-/// If serialization fails an error will be returned.
-
-fn shuffle_indexes<T: Types>(
-    prng: &mut Xoshiro256,
-    indexes: &mut T::Indexes,
-    shuffled: &mut T::Shuffled,
-    degree: usize,
-) {
-    debug_assert!(degree <= indexes.len());
-
-    shuffled.reserve(degree);
-    while shuffled.len() < degree {
-        let index = usize::try_from(prng.next_int(0, (indexes.len() - 1) as u64)).unwrap();
-        let item = indexes.remove(index);
-        shuffled.try_push(item).unwrap();
-    }
-}
-
 fn seed(sequence: u32, checksum: u32) -> [u8; 8] {
     let mut seed = [0u8; 8];
     seed[0..4].copy_from_slice(&sequence.to_be_bytes());
@@ -157,7 +136,7 @@ pub mod tests {
     use super::*;
     use crate::fountain::sampler::Weighted;
     use crate::fountain::util::{div_ceil, fragment_length};
-    use crate::xoshiro::test_utils::make_message;
+    use crate::prng::test_utils::make_message;
     use crate::CRC32;
     use alloc::collections::BTreeSet;
 
@@ -245,30 +224,4 @@ pub mod tests {
             assert_eq!(calculated_degree, expected_degree);
         }
     }
-
-    #[test]
-    fn test_shuffle() {
-        const COUNT: usize = 10;
-        const EXPECTED: &[[usize; COUNT]] = &[
-            [6, 4, 9, 3, 10, 5, 7, 8, 1, 2],
-            [10, 8, 6, 5, 1, 2, 3, 9, 7, 4],
-            [6, 4, 5, 8, 9, 3, 2, 1, 7, 10],
-            [7, 3, 5, 1, 10, 9, 4, 8, 2, 6],
-            [8, 5, 7, 10, 2, 1, 4, 3, 9, 6],
-            [4, 3, 5, 6, 10, 2, 7, 8, 9, 1],
-            [5, 1, 3, 9, 4, 6, 2, 10, 7, 8],
-            [2, 1, 10, 8, 9, 4, 7, 6, 3, 5],
-            [6, 7, 10, 4, 8, 9, 2, 3, 1, 5],
-            [10, 2, 1, 7, 9, 5, 6, 3, 4, 8],
-        ];
-
-        let mut prng = Xoshiro256::from("Wolf");
-        for &expected in EXPECTED {
-            let mut indexes: alloc::vec::Vec<usize> = (1..=COUNT).collect();
-            let mut shuffled = alloc::vec::Vec::new();
-            shuffle_indexes::<Alloc>(&mut prng, &mut indexes, &mut shuffled, COUNT);
-
-            assert_eq!(shuffled, expected);
-        }
-    }
 }
@@ -14,6 +14,7 @@ use crate::{
         chooser::BaseFragmentChooser,
         part::{IndexedPart, Part},
     },
+    CRC32,
 };
 
 /// A [`decoder`](BaseDecoder) that uses [`alloc`] collection types.
@@ -46,8 +47,20 @@ impl<
         QUEUE_SIZE,
     >
 {
+    const CONFIG: () = crate::config::assert_valid(crate::config::validate_decoder(
+        MAX_FRAGMENT_LEN,
+        MAX_SEQUENCE_COUNT,
+        MAX_MIXED_PARTS,
+        QUEUE_SIZE,
+    ));
+
     /// Constructs a new [`HeaplessDecoder`].
+    ///
+    /// Doesn't compile if the capacities are not valid, see
+    /// [`validate_decoder`](crate::config::validate_decoder).
     pub const fn new() -> Self {
+        let () = Self::CONFIG;
+
         Self {
             message: heapless::Vec::new(),
             mixed_parts: heapless::Vec::new(),
@@ -55,6 +68,9 @@ impl<
             queue: heapless::Deque::new(),
             fragment_chooser: chooser::HeaplessFragmentChooser::new(),
             message_description: None,
+            sources: heapless::Vec::new(),
+            conflicts: heapless::IndexSet::new(),
+            max_mixed_parts: None,
         }
     }
 }
@@ -72,6 +88,11 @@ pub struct BaseDecoder<T: Types> {
     queue: T::Queue,
     fragment_chooser: BaseFragmentChooser<T::Chooser>,
     message_description: Option<MessageDescription>,
+    /// Sequence number of the part each fragment was recovered from.
+    sources: T::Sources,
+    /// Sequence numbers of parts that disagree on a fragment.
+    conflicts: T::Indexes,
+    max_mixed_parts: Option<usize>,
 }
 
 impl<T: Types> BaseDecoder<T> {
@@ -104,6 +125,15 @@ impl<T: Types> BaseDecoder<T> {
                     capacity: self.message.capacity(),
                 });
             }
+            let sequence_count = usize::try_from(part.sequence_count).unwrap();
+            if self.sources.try_resize(sequence_count, 0).is_err() {
+                // Leave the decoder empty for the next part.
+                self.message.clear();
+                return Err(Error::NotEnoughSpace {
+                    needed: sequence_count,
+                    capacity: self.sources.capacity(),
+                });
+            }
             self.message_description = Some(part.to_message_description());
         } else if !self.is_part_consistent(part) {
             return Err(Error::InconsistentPart {
@@ -126,7 +156,7 @@ impl<T: Types> BaseDecoder<T> {
             });
         }
 
-        let part = IndexedPart::new(data, indexes);
+        let part = IndexedPart::new(part.sequence, data, indexes);
         self.queue.push_back(part);
 
         while !self.is_complete() && !self.queue.is_empty() {
@@ -134,7 +164,7 @@ impl<T: Types> BaseDecoder<T> {
             if part.is_simple() {
                 self.process_simple(&part)?;
             } else {
-                self.process_mixed(part);
+                self.process_mixed(part)?;
             }
         }
         Ok(!self.is_complete())
@@ -160,6 +190,11 @@ impl<T: Types> BaseDecoder<T> {
     ///
     /// If an inconsistent internal state is detected, an error will be returned.
     ///
+    /// If the checksum of the decoded message does not match the one of the
+    /// parts, [`Error::InvalidChecksum`] is returned. Use
+    /// [`suspected_sequences`](Self::suspected_sequences) to find out which
+    /// parts may be corrupted.
+    ///
     /// # Examples
     ///
     /// See the [`crate::fountain`] module documentation for an example.
@@ -167,21 +202,75 @@ impl<T: Types> BaseDecoder<T> {
     /// [`complete`]: BaseDecoder::is_complete
     pub fn message(&self) -> Result<Option<&[u8]>, Error> {
         if self.is_complete() {
-            if self.message[self.message_description.as_ref().unwrap().message_length..]
-                .iter()
-                .any(|&b| b != 0)
-            {
+            let message_description = self.message_description.as_ref().unwrap();
+            let (message, padding) = self.message.split_at(message_description.message_length);
+            if padding.iter().any(|&b| b != 0) {
                 return Err(Error::InvalidPadding);
             }
 
-            Ok(Some(
-                &self.message[..self.message_description.as_ref().unwrap().message_length],
-            ))
+            let calculated = CRC32.checksum(message);
+            if calculated != message_description.checksum {
+                return Err(Error::InvalidChecksum {
+                    expected: message_description.checksum,
+                    calculated,
+                });
+            }
+
+            Ok(Some(message))
         } else {
             Ok(None)
         }
     }
 
+    /// Returns the longest prefix of the message decoded so far.
+    ///
+    /// This is the message up to the first fragment not recovered yet, so
+    /// that parsing of large messages can start before all the parts are
+    /// received. The checksum of the message is only verified once it is
+    /// [`complete`](Self::is_complete), so the returned bytes are not
+    /// validated and may still turn out to be corrupted.
+    ///
+    /// Returns an empty slice if no part was received.
+    pub fn contiguous_message(&self) -> &[u8] {
+        let Some(message_description) = self.message_description.as_ref() else {
+            return &[];
+        };
+
+        // Sequence numbers start at 1, 0 is a fragment not recovered yet.
+        let fragments = self
+            .sources
+            .iter()
+            .take_while(|&&sequence| sequence != 0)
+            .count();
+        let len = (fragments * message_description.fragment_length)
+            .min(message_description.message_length);
+        &self.message[..len]
+    }
+
+    /// Returns the sequence numbers of the parts that may be corrupted.
+    ///
+    /// When parts carrying the same fragment were received and they
+    /// disagree, only the sequence numbers of those parts are returned.
+    /// Otherwise the sequence numbers of the parts each fragment was
+    /// recovered from are returned, ordered by fragment index.
+    ///
+    /// This is useful to ask the user to scan again only some of the parts
+    /// when [`message`](Self::message) returns [`Error::InvalidChecksum`].
+    pub fn suspected_sequences(&self) -> impl Iterator<Item = u32> + '_ {
+        let no_conflicts = self.conflicts.is_empty();
+        self.conflicts
+            .iter()
+            .map(|&sequence| u32::try_from(sequence).unwrap())
+            .chain(
+                self.sources
+                    .iter()
+                    .copied()
+                    // Sequence numbers start at 1, 0 is a fragment not
+                    // recovered yet.
+                    .filter(move |&sequence| no_conflicts && sequence != 0),
+            )
+    }
+
     /// Returns whether the decoder is complete and hence the message available.
     ///
     /// # Examples
@@ -219,6 +308,52 @@ impl<T: Types> BaseDecoder<T> {
         f64::min(0.99, f64::from(received_parts) / estimated_input_parts)
     }
 
+    /// Returns the number of fragments of the message recovered so far.
+    #[must_use]
+    pub fn fragments_received(&self) -> usize {
+        self.received.len()
+    }
+
+    /// Returns the number of mixed parts stored, waiting to be reduced.
+    #[must_use]
+    pub fn mixed_parts_in_use(&self) -> usize {
+        self.mixed_parts.len()
+    }
+
+    /// Returns how much of the decoder storage is in use.
+    ///
+    /// For [`alloc`] types the capacities are the ones currently allocated,
+    /// they grow as needed.
+    #[must_use]
+    pub fn capacity_report(&self) -> CapacityReport {
+        let mixed_parts_capacity = self.mixed_parts.capacity();
+        CapacityReport {
+            message_len: self.message.len(),
+            message_capacity: self.message.capacity(),
+            fragments_received: self.received.len(),
+            sequence_count: self
+                .message_description
+                .as_ref()
+                .map_or(0, |d| usize::try_from(d.sequence_count).unwrap()),
+            mixed_parts: self.mixed_parts.len(),
+            mixed_parts_capacity: self
+                .max_mixed_parts
+                .map_or(mixed_parts_capacity, |max| max.min(mixed_parts_capacity)),
+        }
+    }
+
+    /// Limit the number of mixed parts stored.
+    ///
+    /// When set, [`receive`](Self::receive) returns
+    /// [`Error::TooManyMixedParts`] instead of dropping a mixed part that
+    /// doesn't fit, either because of the limit or because the storage is
+    /// full. When `None`, the default, such parts are dropped.
+    ///
+    /// The limit is kept when the decoder is [cleared](Self::clear).
+    pub fn set_max_mixed_parts(&mut self, max_mixed_parts: Option<usize>) {
+        self.max_mixed_parts = max_mixed_parts;
+    }
+
     /// Returns `true` if the decoder doesn't contain any data.
     ///
     /// Once a part is successfully [received](Self::receive) this method will
@@ -230,6 +365,8 @@ impl<T: Types> BaseDecoder<T> {
             && self.received.is_empty()
             && self.queue.is_empty()
             && self.message_description.is_none()
+            && self.sources.is_empty()
+            && self.conflicts.is_empty()
     }
 
     /// Clear the decoder so that it can be used again.
@@ -239,6 +376,8 @@ impl<T: Types> BaseDecoder<T> {
         self.received.clear();
         self.queue.clear();
         self.message_description = None;
+        self.sources.clear();
+        self.conflicts.clear();
 
         debug_assert!(self.is_empty());
     }
@@ -257,15 +396,26 @@ impl<T: Types> BaseDecoder<T> {
 
     fn process_simple(&mut self, part: &IndexedPart<T::Fragment, T::Indexes>) -> Result<(), Error> {
         let index = *part.indexes.first().unwrap();
+        let offset = index * self.message_description.as_ref().unwrap().fragment_length;
+        let fragment = offset..offset + self.message_description.as_ref().unwrap().fragment_length;
         if self.received.contains(&index) {
+            // Cross-check the redundant part against the fragment we
+            // already have, both parts are suspects if they disagree.
+            if self.message[fragment.clone()] != part.data[..] {
+                self.conflicts
+                    .insert(usize::try_from(self.sources[index]).unwrap())
+                    .ok();
+                self.conflicts
+                    .insert(usize::try_from(part.sequence).unwrap())
+                    .ok();
+            }
             return Ok(());
         }
 
         self.reduce_mixed(part);
 
-        let offset = index * self.message_description.as_ref().unwrap().fragment_length;
-        self.message[offset..offset + self.message_description.as_ref().unwrap().fragment_length]
-            .copy_from_slice(&part.data);
+        self.message[fragment].copy_from_slice(&part.data);
+        self.sources[index] = part.sequence;
         self.received
             .insert(index)
             .map_err(|_| Error::TooManyFragments)?;
@@ -273,10 +423,13 @@ impl<T: Types> BaseDecoder<T> {
         Ok(())
     }
 
-    fn process_mixed(&mut self, mut part: IndexedPart<T::Fragment, T::Indexes>) {
+    fn process_mixed(
+        &mut self,
+        mut part: IndexedPart<T::Fragment, T::Indexes>,
+    ) -> Result<(), Error> {
         for mixed_part in (&self.mixed_parts as &[IndexedPart<T::Fragment, T::Indexes>]).iter() {
             if part.indexes == mixed_part.indexes {
-                return;
+                return Ok(());
             }
         }
 
@@ -307,11 +460,38 @@ impl<T: Types> BaseDecoder<T> {
             self.queue.push_back(part);
         } else {
             self.reduce_mixed(&part);
-            self.mixed_parts.try_push(part).ok();
+            let limit_reached = self
+                .max_mixed_parts
+                .is_some_and(|max| self.mixed_parts.len() >= max);
+            if limit_reached || self.mixed_parts.try_push(part).is_err() {
+                if let Some(limit) = self.max_mixed_parts {
+                    return Err(Error::TooManyMixedParts { limit });
+                }
+            }
         }
+
+        Ok(())
     }
 }
 
+/// Storage usage of a [`BaseDecoder`], see [`BaseDecoder::capacity_report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct CapacityReport {
+    /// Length of the message buffer, in bytes, including the padding.
+    pub message_len: usize,
+    /// Capacity of the message buffer, in bytes.
+    pub message_capacity: usize,
+    /// Number of fragments recovered.
+    pub fragments_received: usize,
+    /// Number of fragments of the message, zero if no part was received.
+    pub sequence_count: usize,
+    /// Number of mixed parts stored.
+    pub mixed_parts: usize,
+    /// Maximum number of mixed parts that can be stored.
+    pub mixed_parts_capacity: usize,
+}
+
 /// Types for [`BaseDecoder`].
 pub trait Types: Default {
     /// Decoded message buffer.
@@ -326,6 +506,9 @@ pub trait Types: Default {
     /// Indexes storage.
     type Indexes: PartialEq + Set<usize>;
 
+    /// Sequence number of the part each fragment was recovered from.
+    type Sources: Vec<u32>;
+
     /// Part queue.
     type Queue: Deque<IndexedPart<Self::Fragment, Self::Indexes>>;
 
@@ -345,6 +528,7 @@ impl Types for Alloc {
         alloc::vec::Vec<IndexedPart<alloc::vec::Vec<u8>, alloc::collections::BTreeSet<usize>>>;
     type Fragment = alloc::vec::Vec<u8>;
     type Indexes = alloc::collections::BTreeSet<usize>;
+    type Sources = alloc::vec::Vec<u32>;
     type Queue = alloc::collections::VecDeque<
         IndexedPart<alloc::vec::Vec<u8>, alloc::collections::BTreeSet<usize>>,
     >;
@@ -384,6 +568,8 @@ impl<
 
     type Indexes = heapless::FnvIndexSet<usize, MAX_SEQUENCE_COUNT>;
 
+    type Sources = heapless::Vec<u32, MAX_SEQUENCE_COUNT>;
+
     type Queue = heapless::Deque<
         IndexedPart<
             heapless::Vec<u8, MAX_FRAGMENT_LEN>,
@@ -397,6 +583,7 @@ impl<
 
 /// Errors that can happen during decoding.
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum Error {
     /// The padding is invalid.
     InvalidPadding,
@@ -418,6 +605,21 @@ pub enum Error {
     },
     /// Too many fragments.
     TooManyFragments,
+    /// Too many mixed parts, see [`BaseDecoder::set_max_mixed_parts`].
+    TooManyMixedParts {
+        /// Maximum number of mixed parts set.
+        limit: usize,
+    },
+    /// The checksum of the decoded message is not valid.
+    ///
+    /// See [`BaseDecoder::suspected_sequences`] to find the parts that may
+    /// be corrupted.
+    InvalidChecksum {
+        /// The checksum of the message given in the parts.
+        expected: u32,
+        /// The checksum of the decoded message.
+        calculated: u32,
+    },
 }
 
 impl fmt::Display for Error {
@@ -464,6 +666,16 @@ impl fmt::Display for Error {
                 write!(f, "Not enough space: needed {needed}, capacity {capacity}")?
             }
             Error::TooManyFragments => write!(f, "Too many fragments for the current message")?,
+            Error::TooManyMixedParts { limit } => {
+                write!(f, "Too many mixed parts, the limit is {limit}")?
+            }
+            Error::InvalidChecksum {
+                expected,
+                calculated,
+            } => write!(
+                f,
+                "Invalid message checksum (expected {expected:X}, calculated {calculated:X})"
+            )?,
         };
         Ok(())
     }
@@ -477,7 +689,7 @@ impl std::error::Error for Error {}
 pub mod tests {
     use super::*;
     use crate::fountain::fragment_length;
-    use crate::{fountain::Encoder, xoshiro::test_utils::make_message};
+    use crate::{fountain::Encoder, prng::test_utils::make_message};
 
     const MESSAGE_SIZE: usize = 32767;
     const MAX_FRAGMENT_LEN: usize = 1000;
@@ -495,7 +707,7 @@ pub mod tests {
         fn test<T: Types>(decoder: &mut BaseDecoder<T>) {
             let message = message();
             let mut encoder = Encoder::new();
-            encoder.start(&message, MAX_FRAGMENT_LEN);
+            encoder.start(&message, MAX_FRAGMENT_LEN).unwrap();
             while !decoder.is_complete() {
                 assert_eq!(decoder.message().unwrap(), None);
                 let part = encoder.next_part();
@@ -521,7 +733,7 @@ pub mod tests {
     fn test_decoder_skip_some_simple_fragments() {
         let message = make_message(SEED, MESSAGE_SIZE);
         let mut encoder = Encoder::new();
-        encoder.start(&message, MAX_FRAGMENT_LEN);
+        encoder.start(&message, MAX_FRAGMENT_LEN).unwrap();
         let mut decoder = Decoder::default();
         let mut skip = false;
         while !decoder.is_complete() {
@@ -538,7 +750,7 @@ pub mod tests {
     fn test_decoder_receive_return_value() {
         let message = make_message(SEED, MESSAGE_SIZE);
         let mut encoder = Encoder::new();
-        encoder.start(&message, MAX_FRAGMENT_LEN);
+        encoder.start(&message, MAX_FRAGMENT_LEN).unwrap();
         let mut decoder = Decoder::default();
         let part = encoder.next_part();
         assert!(decoder.receive(&part).unwrap());
@@ -563,7 +775,7 @@ pub mod tests {
     fn test_decoder_part_validation() {
         fn test<T: Types>(decoder: &mut BaseDecoder<T>) {
             let mut encoder = Encoder::new();
-            encoder.start("foo".as_bytes(), 2);
+            encoder.start("foo".as_bytes(), 2).unwrap();
 
             let mut part = encoder.next_part();
             assert!(decoder.receive(&part).unwrap());
@@ -588,6 +800,75 @@ pub mod tests {
         test(&mut decoder);
     }
 
+    #[test]
+    fn test_decoder_invalid_checksum() {
+        let message = make_message(SEED, 100);
+        let mut encoder = Encoder::new();
+        encoder.start(&message, 10).unwrap();
+        let sequence_count = encoder.sequence_count();
+
+        // Corrupted simple part, without redundant parts to compare against.
+        let mut decoder = Decoder::default();
+        for _ in 0..sequence_count {
+            let part = encoder.next_part();
+            let mut data = part.data.to_vec();
+            if part.sequence == 3 {
+                data[0] ^= 0xFF;
+            }
+            decoder
+                .receive(&Part {
+                    data: &data,
+                    ..part
+                })
+                .unwrap();
+        }
+        assert!(decoder.is_complete());
+        assert!(matches!(
+            decoder.message(),
+            Err(Error::InvalidChecksum { expected, .. }) if expected == CRC32.checksum(&message)
+        ));
+        let suspected: alloc::vec::Vec<u32> = decoder.suspected_sequences().collect();
+        assert_eq!(
+            suspected,
+            (1..=sequence_count).collect::<alloc::vec::Vec<_>>()
+        );
+
+        // Corrupted simple part, a redundant mixed part disagrees with it.
+        let mut encoder = Encoder::new();
+        encoder.start(&message, 10).unwrap();
+        let mut decoder = Decoder::default();
+        let mut data = alloc::vec::Vec::new();
+        while !decoder.is_complete() {
+            let part = encoder.next_part();
+            let indexes: alloc::collections::BTreeSet<usize> = part.indexes::<chooser::Alloc, _>();
+            // Hold back the first fragment until the conflict is found.
+            if indexes.contains(&0) && (part.sequence == 1 || decoder.conflicts.is_empty()) {
+                continue;
+            }
+            data.clear();
+            data.extend_from_slice(part.data);
+            if part.sequence == 3 {
+                data[0] ^= 0xFF;
+            }
+            decoder
+                .receive(&Part {
+                    data: &data,
+                    ..part
+                })
+                .unwrap();
+        }
+        assert!(matches!(
+            decoder.message(),
+            Err(Error::InvalidChecksum { .. })
+        ));
+        let suspected: alloc::vec::Vec<u32> = decoder.suspected_sequences().collect();
+        assert!(suspected.contains(&3));
+        assert!(suspected.len() < usize::try_from(sequence_count).unwrap());
+
+        decoder.clear();
+        assert_eq!(decoder.suspected_sequences().count(), 0);
+    }
+
     #[test]
     fn test_empty_decoder_empty_part() {
         fn test<T: Types>(decoder: &mut BaseDecoder<T>) {
@@ -624,4 +905,85 @@ pub mod tests {
         test(&mut heapless_decoder);
         test(&mut decoder);
     }
+
+    #[test]
+    fn test_decoder_contiguous_message() {
+        let message = make_message(SEED, 1000);
+        let mut encoder = Encoder::new();
+        encoder.start(&message, 100).unwrap();
+
+        let mut decoder = Decoder::default();
+        assert!(decoder.contiguous_message().is_empty());
+
+        // Skip the second fragment, the prefix stops at the first one.
+        decoder.receive(&encoder.next_part()).unwrap();
+        encoder.next_part();
+        decoder.receive(&encoder.next_part()).unwrap();
+        assert_eq!(decoder.contiguous_message(), &message[..100]);
+
+        while !decoder.is_complete() {
+            decoder.receive(&encoder.next_part()).unwrap();
+            assert!(message.starts_with(decoder.contiguous_message()));
+        }
+
+        // The padding is not part of the prefix.
+        assert_eq!(decoder.contiguous_message(), &message[..]);
+    }
+
+    #[test]
+    fn test_decoder_capacity() {
+        let message = make_message(SEED, 1024);
+        let mut encoder = Encoder::new();
+        encoder.start(&message, 100).unwrap();
+
+        let mut decoder = Decoder::default();
+        let report = decoder.capacity_report();
+        assert_eq!(report.sequence_count, 0);
+        assert_eq!(report.fragments_received, 0);
+
+        decoder.receive(&encoder.next_part()).unwrap();
+        assert_eq!(decoder.fragments_received(), 1);
+        assert_eq!(decoder.mixed_parts_in_use(), 0);
+        let report = decoder.capacity_report();
+        assert_eq!(report.sequence_count, 11);
+        assert_eq!(report.message_len, 11 * 94);
+
+        // Skip the remaining simple parts so that the mixed parts pile up.
+        for _ in 1..11 {
+            encoder.next_part();
+        }
+        decoder.set_max_mixed_parts(Some(2));
+        let error = loop {
+            match decoder.receive(&encoder.next_part()) {
+                Ok(_) => assert!(decoder.mixed_parts_in_use() <= 2),
+                Err(e) => break e,
+            }
+        };
+        assert!(matches!(error, Error::TooManyMixedParts { limit: 2 }));
+        assert_eq!(decoder.capacity_report().mixed_parts, 2);
+        assert_eq!(decoder.capacity_report().mixed_parts_capacity, 2);
+    }
+
+    #[test]
+    fn test_decoder_not_enough_space_is_empty() {
+        let mut decoder: HeaplessDecoder<1000, 8, 100, 8, 8> = HeaplessDecoder::new();
+        let part = Part {
+            sequence: 1,
+            sequence_count: 20,
+            message_length: 200,
+            checksum: 0,
+            data: &[0; 10],
+        };
+
+        for _ in 0..2 {
+            assert!(matches!(
+                decoder.receive(&part),
+                Err(Error::NotEnoughSpace {
+                    needed: 20,
+                    capacity: 8
+                })
+            ));
+            assert!(decoder.is_empty());
+        }
+    }
 }
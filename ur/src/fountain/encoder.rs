@@ -4,6 +4,8 @@
 
 //! Encoder.
 
+use core::fmt;
+
 use crate::{
     collections::{Set, Vec},
     fountain::{
@@ -27,6 +29,7 @@ impl<'a> Encoder<'a> {
             fragment_length: 0,
             checksum: 0,
             current_sequence: 0,
+            schedule: EmissionSchedule::Sequential,
             chooser: chooser::FragmentChooser::new(),
             data: alloc::vec::Vec::new(),
             indexes: alloc::collections::BTreeSet::new(),
@@ -41,13 +44,24 @@ pub type HeaplessEncoder<'a, const MAX_FRAGMENT_LEN: usize, const MAX_SEQUENCE_C
 impl<'a, const MAX_FRAGMENT_LEN: usize, const MAX_SEQUENCE_COUNT: usize>
     HeaplessEncoder<'a, MAX_FRAGMENT_LEN, MAX_SEQUENCE_COUNT>
 {
+    const CONFIG: () = crate::config::assert_valid(crate::config::validate_encoder(
+        MAX_FRAGMENT_LEN,
+        MAX_SEQUENCE_COUNT,
+    ));
+
     /// Constructs a new [`HeaplessEncoder`].
+    ///
+    /// Doesn't compile if the capacities are not valid, see
+    /// [`validate_encoder`](crate::config::validate_encoder).
     pub const fn new() -> Self {
+        let () = Self::CONFIG;
+
         Self {
             message: None,
             fragment_length: 0,
             checksum: 0,
             current_sequence: 0,
+            schedule: EmissionSchedule::Sequential,
             chooser: chooser::HeaplessFragmentChooser::new(),
             data: heapless::Vec::new(),
             indexes: heapless::IndexSet::new(),
@@ -65,6 +79,7 @@ pub struct BaseEncoder<'a, T: Types> {
     fragment_length: usize,
     checksum: u32,
     current_sequence: u32,
+    schedule: EmissionSchedule,
     chooser: chooser::BaseFragmentChooser<T::Chooser>,
     data: T::Data,
     indexes: T::Indexes,
@@ -73,30 +88,129 @@ pub struct BaseEncoder<'a, T: Types> {
 impl<'a, T: Types> BaseEncoder<'a, T> {
     /// Start encoding a new message.
     ///
-    /// # Panics
+    /// The fragment length is at least one byte, and the message is split
+    /// in as many fragments of at most `max_fragment_length` bytes as
+    /// needed, so `max_fragment_length` must be large enough for the
+    /// fragments to fit in the sequence count limits.
     ///
-    /// This function panics if:
+    /// # Errors
+    ///
+    /// This function returns an error if:
     ///
     /// - The message is empty.
     /// - The maximum fragment length is zero.
-    /// - The maximum fragment length is large than what `T::Data` can
-    /// hold.
-    pub fn start(&mut self, message: &'a [u8], max_fragment_length: usize) {
-        assert!(!message.is_empty(), "message must not be empty");
-        assert_ne!(
-            max_fragment_length, 0,
-            "fragment length must be greater than zero"
-        );
+    /// - The message is longer than [`MAX_MESSAGE_LENGTH`], or it needs more
+    /// fragments than `T` can handle.
+    /// - The fragment length is larger than what `T::Data` can hold.
+    pub fn start(&mut self, message: &'a [u8], max_fragment_length: usize) -> Result<(), Error> {
+        if message.is_empty() {
+            return Err(Error::EmptyMessage);
+        }
 
-        self.fragment_length = fragment_length(message.len(), max_fragment_length);
-        self.message = Some(message);
+        if max_fragment_length == 0 {
+            return Err(Error::InvalidFragmentLength);
+        }
+
+        let fragment_length = fragment_length(message.len(), max_fragment_length);
+        self.setup(message, fragment_length, max_fragment_length)?;
         self.checksum = CRC32.checksum(message);
         self.current_sequence = 0;
 
+        Ok(())
+    }
+
+    /// Returns the state of the encoder, to continue the transmission
+    /// later with [`restore_state`](Self::restore_state).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the encoder was not started.
+    #[must_use]
+    pub fn save_state(&self) -> EncoderState {
+        assert!(self.message.is_some(), "encoder must be started");
+
+        EncoderState {
+            current_sequence: self.current_sequence,
+            checksum: self.checksum,
+            fragment_length: u32::try_from(self.fragment_length).unwrap(),
+        }
+    }
+
+    /// Continue encoding `message` from a state returned by
+    /// [`save_state`](Self::save_state).
+    ///
+    /// The next part emitted is the one that would have been emitted after
+    /// the state was saved. The emission schedule is not part of the state,
+    /// it has to be set again if it is not the default one.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the message is not the one the
+    /// state was saved for, and for the same reasons as
+    /// [`start`](Self::start).
+    pub fn restore_state(&mut self, message: &'a [u8], state: &EncoderState) -> Result<(), Error> {
+        if message.is_empty() {
+            return Err(Error::EmptyMessage);
+        }
+
+        let fragment_length = usize::try_from(state.fragment_length).unwrap();
+        if fragment_length == 0 || fragment_length > message.len() {
+            return Err(Error::InvalidState);
+        }
+
+        if CRC32.checksum(message) != state.checksum {
+            return Err(Error::InvalidState);
+        }
+
+        self.setup(message, fragment_length, fragment_length)?;
+        self.checksum = state.checksum;
+        self.current_sequence = state.current_sequence;
+
+        Ok(())
+    }
+
+    fn setup(
+        &mut self,
+        message: &'a [u8],
+        fragment_length: usize,
+        max_fragment_length: usize,
+    ) -> Result<(), Error> {
+        let max = MAX_MESSAGE_LENGTH.min(max_fragment_length.saturating_mul(T::MAX_SEQUENCE_COUNT));
+        if message.len() > max {
+            return Err(Error::MessageTooLong {
+                length: message.len(),
+                max,
+            });
+        }
+
         self.data.clear();
-        self.data
-            .try_resize(self.fragment_length, 0)
-            .expect("not enough capacity for part data");
+        if self.data.try_resize(fragment_length, 0).is_err() {
+            // Keep the previous message, its fragments fitted.
+            let _ = self.data.try_resize(self.fragment_length, 0);
+            return Err(Error::NotEnoughSpace {
+                needed: fragment_length,
+                capacity: self.data.capacity(),
+            });
+        }
+
+        self.fragment_length = fragment_length;
+        self.message = Some(message);
+
+        Ok(())
+    }
+
+    /// Set the order in which parts are emitted.
+    ///
+    /// The schedule is kept across calls to [`start`](Self::start).
+    pub fn set_emission_schedule(&mut self, schedule: EmissionSchedule) {
+        self.schedule = schedule;
+    }
+
+    /// Returns the order in which parts are emitted.
+    #[must_use]
+    #[inline]
+    pub fn emission_schedule(&self) -> EmissionSchedule {
+        self.schedule
     }
 
     /// Returns the current count of how many parts have been emitted.
@@ -135,13 +249,14 @@ impl<'a, T: Types> BaseEncoder<'a, T> {
     ///
     /// See the [`crate::fountain`] module documentation for an example.
     pub fn next_part(&mut self) -> Part {
+        let sequence = self
+            .schedule
+            .sequence(self.current_sequence, self.sequence_count());
         self.current_sequence = self.current_sequence.wrapping_add(1);
 
-        self.indexes = self.chooser.choose_fragments(
-            self.current_sequence,
-            self.sequence_count(),
-            self.checksum,
-        );
+        self.indexes =
+            self.chooser
+                .choose_fragments(sequence, self.sequence_count(), self.checksum);
 
         self.data.fill(0);
         for &index in self.indexes.iter() {
@@ -158,7 +273,7 @@ impl<'a, T: Types> BaseEncoder<'a, T> {
         }
 
         Part {
-            sequence: self.current_sequence,
+            sequence,
             sequence_count: self.sequence_count(),
             message_length: self.message.unwrap().len(),
             checksum: self.checksum,
@@ -167,8 +282,190 @@ impl<'a, T: Types> BaseEncoder<'a, T> {
     }
 }
 
+/// Order in which a [`BaseEncoder`] emits its parts.
+///
+/// Every part carries its own sequence number so any schedule can be
+/// decoded by a standard decoder.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EmissionSchedule {
+    /// Emit the parts in sequence number order, as the standard does.
+    #[default]
+    Sequential,
+    /// Interleaved redundancy order, for lossy displays and slow cameras.
+    ///
+    /// The simple parts are first emitted in a permuted order, so that a
+    /// burst of lost frames does not lose adjacent fragments. Afterwards
+    /// every `interval`-th frame repeats a simple part, in the same permuted
+    /// order, between the mixed parts. An `interval` of zero never repeats
+    /// the simple parts.
+    Interleaved {
+        /// Period, in frames, of the repeated simple parts.
+        interval: u32,
+    },
+    /// Simple parts first, for slow cameras.
+    ///
+    /// The simple parts are first emitted in sequence number order.
+    /// Afterwards every `interval`-th frame is the next simple part, cycling
+    /// in the same order, between the mixed parts. An `interval` of zero
+    /// never repeats the simple parts, as in [`Sequential`](Self::Sequential).
+    SimpleFirst {
+        /// Period, in frames, of the repeated simple parts.
+        interval: u32,
+    },
+}
+
+impl EmissionSchedule {
+    /// Returns the sequence number of the part emitted at position `index`
+    /// (starting at zero) for a message split into `sequence_count`
+    /// fragments.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `sequence_count` is zero.
+    #[must_use]
+    pub fn sequence(&self, index: u32, sequence_count: u32) -> u32 {
+        assert_ne!(sequence_count, 0, "sequence count must not be zero");
+
+        let (interval, order): (u32, fn(u32, u32) -> u32) = match *self {
+            EmissionSchedule::Sequential => return index.wrapping_add(1),
+            EmissionSchedule::Interleaved { interval } => (interval, permute),
+            EmissionSchedule::SimpleFirst { interval } => (interval, |index, _| index),
+        };
+
+        if index < sequence_count {
+            return order(index, sequence_count) + 1;
+        }
+
+        // Position after all the simple parts were emitted once, starting
+        // at one.
+        let frame = index - sequence_count + 1;
+        if interval > 0 && frame % interval == 0 {
+            let simple = (frame / interval - 1) % sequence_count;
+            order(simple, sequence_count) + 1
+        } else {
+            let repeated = frame.checked_div(interval).unwrap_or(0);
+            sequence_count.wrapping_add(frame - repeated)
+        }
+    }
+}
+
+/// Permutes `index` in the `[0, count)` range by multiplying it with a
+/// stride coprime with `count`, so that consecutive indexes are spread
+/// apart.
+fn permute(index: u32, count: u32) -> u32 {
+    fn gcd(mut a: u32, mut b: u32) -> u32 {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+
+    let mut stride = count / 2 + 1;
+    while gcd(stride, count) != 1 {
+        stride += 1;
+    }
+
+    let permuted = (u64::from(index) * u64::from(stride)) % u64::from(count);
+    u32::try_from(permuted).unwrap()
+}
+
+/// State of a [`BaseEncoder`], to continue a transmission later.
+///
+/// See [`BaseEncoder::save_state`] and [`BaseEncoder::restore_state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct EncoderState {
+    /// Number of parts emitted.
+    pub current_sequence: u32,
+    /// Checksum of the message.
+    pub checksum: u32,
+    /// Length of the fragments.
+    pub fragment_length: u32,
+}
+
+impl EncoderState {
+    /// Length of the serialized state, in bytes.
+    pub const LEN: usize = 12;
+
+    /// Serialize the state.
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0; Self::LEN];
+        bytes[0..4].copy_from_slice(&self.current_sequence.to_be_bytes());
+        bytes[4..8].copy_from_slice(&self.checksum.to_be_bytes());
+        bytes[8..12].copy_from_slice(&self.fragment_length.to_be_bytes());
+        bytes
+    }
+
+    /// Deserialize a state returned by [`to_bytes`](Self::to_bytes).
+    #[must_use]
+    pub fn from_bytes(bytes: &[u8; Self::LEN]) -> Self {
+        let u32_at = |i: usize| u32::from_be_bytes(bytes[i..i + 4].try_into().unwrap());
+        Self {
+            current_sequence: u32_at(0),
+            checksum: u32_at(4),
+            fragment_length: u32_at(8),
+        }
+    }
+}
+
+/// Maximum length of a message, in bytes.
+///
+/// The message length is encoded as a 32-bit integer in the parts.
+pub const MAX_MESSAGE_LENGTH: usize = u32::MAX as usize;
+
+/// Errors that can happen when starting to encode a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Error {
+    /// The message is empty.
+    EmptyMessage,
+    /// The maximum fragment length is zero.
+    InvalidFragmentLength,
+    /// The message is too long for the maximum fragment length.
+    MessageTooLong {
+        /// Length of the message.
+        length: usize,
+        /// Maximum length of a message for the maximum fragment length.
+        max: usize,
+    },
+    /// Not enough space to hold a fragment.
+    NotEnoughSpace {
+        /// Needed space.
+        needed: usize,
+        /// Current capacity.
+        capacity: usize,
+    },
+    /// The saved state is not valid for the message.
+    InvalidState,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::EmptyMessage => write!(f, "The message is empty"),
+            Error::InvalidFragmentLength => {
+                write!(f, "The maximum fragment length must be greater than zero")
+            }
+            Error::MessageTooLong { length, max } => {
+                write!(f, "Message too long: length {length}, maximum {max}")
+            }
+            Error::NotEnoughSpace { needed, capacity } => {
+                write!(f, "Not enough space: needed {needed}, capacity {capacity}")
+            }
+            Error::InvalidState => write!(f, "The saved state doesn't match the message"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 /// Types for [`BaseEncoder`].
 pub trait Types: Default {
+    /// Maximum number of fragments a message can be split into.
+    const MAX_SEQUENCE_COUNT: usize;
+
     /// Fragment chooser types.
     type Chooser: chooser::Types;
 
@@ -186,6 +483,8 @@ pub struct Alloc;
 
 #[cfg(feature = "alloc")]
 impl Types for Alloc {
+    const MAX_SEQUENCE_COUNT: usize = u32::MAX as usize;
+
     type Chooser = chooser::Alloc;
     type Data = alloc::vec::Vec<u8>;
     type Indexes = alloc::collections::BTreeSet<usize>;
@@ -198,6 +497,8 @@ pub struct Heapless<const MAX_FRAGMENT_LEN: usize, const MAX_SEQUENCE_COUNT: usi
 impl<const MAX_FRAGMENT_LEN: usize, const MAX_SEQUENCE_COUNT: usize> Types
     for Heapless<MAX_FRAGMENT_LEN, MAX_SEQUENCE_COUNT>
 {
+    const MAX_SEQUENCE_COUNT: usize = MAX_SEQUENCE_COUNT;
+
     type Chooser = chooser::Heapless<MAX_SEQUENCE_COUNT>;
     type Data = heapless::Vec<u8, MAX_FRAGMENT_LEN>;
     type Indexes = heapless::FnvIndexSet<usize, MAX_SEQUENCE_COUNT>;
@@ -207,7 +508,7 @@ impl<const MAX_FRAGMENT_LEN: usize, const MAX_SEQUENCE_COUNT: usize> Types
 #[cfg(feature = "alloc")]
 pub mod tests {
     use super::*;
-    use crate::xoshiro::test_utils::make_message;
+    use crate::prng::test_utils::make_message;
 
     #[test]
     fn test_encoder_fragment_split() {
@@ -227,7 +528,7 @@ pub mod tests {
 
         let message = make_message("Wolf", 1024);
         let mut encoder = Encoder::new();
-        encoder.start(&message, 100);
+        encoder.start(&message, 100).unwrap();
 
         assert_eq!(
             usize::try_from(encoder.sequence_count()).unwrap(),
@@ -266,7 +567,7 @@ pub mod tests {
 
         let message = make_message("Wolf", 256);
         let mut encoder = Encoder::new();
-        encoder.start(&message, 30);
+        encoder.start(&message, 30).unwrap();
 
         for (i, data) in EXPECTED_DATA
             .iter()
@@ -295,7 +596,7 @@ pub mod tests {
     fn test_fountain_encoder_is_complete() {
         let message = make_message("Wolf", 256);
         let mut encoder = Encoder::new();
-        encoder.start(&message, 30);
+        encoder.start(&message, 30).unwrap();
         for _ in 0..encoder.sequence_count() {
             encoder.next_part();
         }
@@ -332,7 +633,7 @@ pub mod tests {
 
         let message = make_message("Wolf", MESSAGE_SIZE);
         let mut encoder = Encoder::new();
-        encoder.start(&message, MAX_FRAGMENT_LENGTH);
+        encoder.start(&message, MAX_FRAGMENT_LENGTH).unwrap();
         assert_eq!(
             encoder.sequence_count(),
             u32::try_from(SEQUENCE_COUNT).unwrap()
@@ -350,16 +651,142 @@ pub mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "fragment length must be greater than zero")]
-    fn test_encoder_zero_max_length() {
+    fn test_emission_schedule() {
+        const INTERLEAVED: [u32; 20] = [
+            1, 6, 2, 7, 3, 8, 4, 9, 5, 10, 11, 1, 12, 13, 6, 14, 15, 2, 16, 17,
+        ];
+
+        for (index, &expected) in INTERLEAVED.iter().enumerate() {
+            let index = u32::try_from(index).unwrap();
+            assert_eq!(EmissionSchedule::Sequential.sequence(index, 9), index + 1);
+            assert_eq!(
+                EmissionSchedule::Interleaved { interval: 3 }.sequence(index, 9),
+                expected
+            );
+        }
+
+        let schedule = EmissionSchedule::Interleaved { interval: 0 };
+        assert_eq!(schedule.sequence(8, 9), 5);
+        assert_eq!(schedule.sequence(9, 9), 10);
+        assert_eq!(schedule.sequence(100, 9), 101);
+        assert_eq!(schedule.sequence(0, 1), 1);
+    }
+
+    #[test]
+    fn test_emission_schedule_simple_first() {
+        const SIMPLE_FIRST: [u32; 20] = [
+            1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1, 11, 2, 12, 3, 13, 4, 14, 5, 15,
+        ];
+
+        for (index, &expected) in SIMPLE_FIRST.iter().enumerate() {
+            let index = u32::try_from(index).unwrap();
+            assert_eq!(
+                EmissionSchedule::SimpleFirst { interval: 2 }.sequence(index, 9),
+                expected
+            );
+            assert_eq!(
+                EmissionSchedule::SimpleFirst { interval: 0 }.sequence(index, 9),
+                index + 1
+            );
+        }
+
+        let schedule = EmissionSchedule::SimpleFirst { interval: 1 };
+        assert_eq!(schedule.sequence(9, 9), 1);
+        assert_eq!(schedule.sequence(17, 9), 9);
+        assert_eq!(schedule.sequence(18, 9), 1);
+    }
+
+    #[test]
+    fn test_encoder_interleaved() {
+        let message = make_message("Wolf", 256);
+        let mut encoder = Encoder::new();
+        encoder.set_emission_schedule(EmissionSchedule::Interleaved { interval: 3 });
+        encoder.start(&message, 30).unwrap();
+
+        let mut sequential = Encoder::new();
+        sequential.start(&message, 30).unwrap();
+        let expected: alloc::vec::Vec<_> = (0..20)
+            .map(|_| sequential.next_part().data.to_vec())
+            .collect();
+
+        let mut decoder = crate::fountain::Decoder::default();
+        for index in 0..20 {
+            assert_eq!(encoder.current_sequence(), index);
+            let part = encoder.next_part();
+            let data = &expected[usize::try_from(part.sequence - 1).unwrap()];
+            assert_eq!(part.data, data.as_slice());
+
+            // Lose a burst of frames at the beginning.
+            if !(2..5).contains(&index) {
+                decoder.receive(&part).unwrap();
+            }
+        }
+        assert_eq!(decoder.message().unwrap(), Some(message.as_slice()));
+    }
+
+    #[test]
+    fn test_encoder_start_errors() {
         let mut encoder = Encoder::new();
-        encoder.start("foo".as_bytes(), 0);
+        assert_eq!(
+            encoder.start("foo".as_bytes(), 0),
+            Err(Error::InvalidFragmentLength)
+        );
+        assert_eq!(encoder.start("".as_bytes(), 20), Err(Error::EmptyMessage));
+
+        let mut encoder: HeaplessEncoder<4, 4> = HeaplessEncoder::new();
+        assert_eq!(
+            encoder.start(&[0; 17], 4),
+            Err(Error::MessageTooLong {
+                length: 17,
+                max: 16
+            })
+        );
+        assert_eq!(
+            encoder.start(&[0; 8], 8),
+            Err(Error::NotEnoughSpace {
+                needed: 8,
+                capacity: 4
+            })
+        );
+        encoder.start(&[0; 16], 4).unwrap();
+        assert_eq!(encoder.sequence_count(), 4);
+
+        // A failed start keeps the previous message.
+        assert!(encoder.start(&[0; 8], 8).is_err());
+        assert_eq!(encoder.next_part().data, &[0; 4]);
+        assert_eq!(encoder.sequence_count(), 4);
     }
 
     #[test]
-    #[should_panic(expected = "message must not be empty")]
-    fn test_encoder_empty_message() {
+    fn test_encoder_restore_state() {
+        let message = make_message("Wolf", 256);
         let mut encoder = Encoder::new();
-        encoder.start("".as_bytes(), 20);
+        encoder.start(&message, 30).unwrap();
+        for _ in 0..12 {
+            encoder.next_part();
+        }
+
+        let state = encoder.save_state();
+        assert_eq!(EncoderState::from_bytes(&state.to_bytes()), state);
+        let expected = encoder.next_part().clone();
+
+        let mut restored: HeaplessEncoder<30, 16> = HeaplessEncoder::new();
+        restored.restore_state(&message, &state).unwrap();
+        assert_eq!(restored.current_sequence(), 12);
+        assert_eq!(restored.next_part(), expected);
+
+        let other = make_message("Other", 256);
+        assert_eq!(
+            restored.restore_state(&other, &state),
+            Err(Error::InvalidState)
+        );
+        let invalid = EncoderState {
+            fragment_length: 0,
+            ..state
+        };
+        assert_eq!(
+            restored.restore_state(&message, &invalid),
+            Err(Error::InvalidState)
+        );
     }
 }
@@ -19,10 +19,10 @@ mod util;
 
 #[cfg(feature = "alloc")]
 pub use self::decoder::Decoder;
-pub use self::decoder::{BaseDecoder, HeaplessDecoder};
+pub use self::decoder::{BaseDecoder, CapacityReport, HeaplessDecoder};
 
 #[cfg(feature = "alloc")]
 pub use self::encoder::Encoder;
-pub use self::encoder::{BaseEncoder, HeaplessEncoder};
+pub use self::encoder::{BaseEncoder, EmissionSchedule, EncoderState, HeaplessEncoder};
 
 pub use self::util::fragment_length;
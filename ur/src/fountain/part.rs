@@ -16,6 +16,7 @@ use crate::{
 ///
 /// This structure is a subset of the information of a [`Part`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub struct MessageDescription {
     /// The total sequence count.
     pub sequence_count: u32,
@@ -187,6 +188,8 @@ impl<'b, C> minicbor::Decode<'b, C> for Part<'b> {
 /// A part with the indexes of the simple parts mixed.
 #[derive(Debug, Clone)]
 pub struct IndexedPart<D, I> {
+    /// The sequence number of the part this one originates from.
+    pub sequence: u32,
     /// The data of this part.
     pub data: D,
     /// The indexes contained in this part.
@@ -194,10 +197,15 @@ pub struct IndexedPart<D, I> {
 }
 
 impl<D, I> IndexedPart<D, I> {
-    /// Create a new [`IndexedPart`] from `data` and the indexes of the parts
-    /// mixed in `data`.
-    pub fn new(data: D, indexes: I) -> Self {
-        Self { data, indexes }
+    /// Create a new [`IndexedPart`] from the `sequence` number of the
+    /// received part, its `data` and the indexes of the parts mixed in
+    /// `data`.
+    pub fn new(sequence: u32, data: D, indexes: I) -> Self {
+        Self {
+            sequence,
+            data,
+            indexes,
+        }
     }
 
     /// Returns `true` if the part is simple.
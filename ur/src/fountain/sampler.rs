@@ -118,7 +118,7 @@ impl<T: Types> BaseWeighted<T> {
     #[allow(clippy::cast_possible_truncation)]
     #[allow(clippy::cast_precision_loss)]
     #[allow(clippy::cast_sign_loss)]
-    pub fn next(&mut self, xoshiro: &mut crate::xoshiro::Xoshiro256) -> u32 {
+    pub fn next(&mut self, xoshiro: &mut crate::prng::Xoshiro256) -> u32 {
         let r1 = xoshiro.next_double();
         let r2 = xoshiro.next_double();
         let n = self.probs.len();
@@ -212,7 +212,7 @@ mod tests {
     ];
 
     fn test_sampler<T: Types>(sampler: &mut BaseWeighted<T>) {
-        let mut xoshiro = crate::xoshiro::Xoshiro256::from("Wolf");
+        let mut xoshiro = crate::prng::Xoshiro256::from("Wolf");
         sampler.set(WEIGHTS.iter().copied());
 
         for &e in EXPECTED_SAMPLES {
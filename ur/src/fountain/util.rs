@@ -90,7 +90,7 @@ pub mod tests {
         const B: [u8; 10] = [0xf9, 0xcd, 0xa1, 0xa1, 0x03, 0x00, 0x26, 0xdd, 0xd4, 0x2e];
         const C: [u8; 10] = [0x68, 0xa3, 0x67, 0xfd, 0xf4, 0x7c, 0x8b, 0x28, 0x88, 0xf9];
 
-        let mut a = A.clone();
+        let mut a = A;
         xor_into(&mut a, &B);
         assert_eq!(a, C);
 
@@ -33,6 +33,11 @@ pub const fn max_fragment_len(
     max_sequence_number: usize,
     max_characters: usize,
 ) -> usize {
+    (max_characters - non_payload_len(max_ur_type, max_sequence_number)) / 2
+}
+
+/// Number of characters of a UR part that are not the fragment.
+pub(crate) const fn non_payload_len(max_ur_type: &str, max_sequence_number: usize) -> usize {
     let mut non_payload_characters = 0;
 
     non_payload_characters += "ur:".len();
@@ -45,7 +50,7 @@ pub const fn max_fragment_len(
     non_payload_characters += Part::max_encoded_len() * 2;
     non_payload_characters += 4 * 2;
 
-    (max_characters - non_payload_characters) / 2
+    non_payload_characters
 }
 
 // Calculate the number of digits in a number.
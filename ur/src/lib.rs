@@ -22,7 +22,7 @@
 //!
 //! let data = "Ten chars!".repeat(10);
 //!
-//! encoder.start("bytes", data.as_bytes(), MAX_FRAGMENT_LENGTH);
+//! encoder.start("bytes", data.as_bytes(), MAX_FRAGMENT_LENGTH).unwrap();
 //! assert_eq!(
 //!     encoder.next_part().to_string(),
 //!     "ur:bytes/1-20/lpadbbcsiecyvdidatkpfeghihjtcxiabdfevlms"
@@ -52,6 +52,18 @@
 //!    encoder, which splits up a byte payload into multiple segments and
 //!    emits an unbounded stream of parts which can be recombined at the
 //!    receiving decoder side.
+//!
+//!  - The [`prng`] module contains the seeded pseudo-random number generator
+//!    and shuffle used by the fountain encoder to choose fragments.
+//!
+//!  - The [`qr`] module gives the maximum length of the parts that fit in
+//!    a QR code, to choose the fragment length.
+//!
+//!  - The [`prelude`] module re-exports the types commonly needed to send
+//!    and receive Uniform Resources.
+//!
+//!  - The [`config`] module checks at compile time that the capacities of
+//!    the `heapless` encoders and decoders work together.
 #![forbid(unsafe_code)]
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
@@ -62,11 +74,14 @@ extern crate core;
 
 pub mod bytewords;
 pub mod collections;
+pub mod config;
 pub mod fountain;
+pub mod prelude;
+pub mod prng;
+pub mod qr;
 
 mod len;
 mod ur;
-mod xoshiro;
 
 pub use self::len::*;
 pub use self::ur::*;
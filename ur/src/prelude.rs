@@ -0,0 +1,25 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: MIT
+
+//! Commonly used types.
+//!
+//! Glob import this module to get the types needed to send and receive
+//! Uniform Resources:
+//!
+//! ```
+//! use foundation_ur::prelude::*;
+//!
+//! let mut encoder: HeaplessEncoder<32, 32> = HeaplessEncoder::new();
+//! encoder.start("bytes", b"Hello, world!", 10).unwrap();
+//! let part = encoder.next_part();
+//!
+//! let mut decoder: HeaplessDecoder<100, 32, 32, 32, 32, 32> = HeaplessDecoder::new();
+//! decoder.receive(part).unwrap();
+//! ```
+
+pub use crate::{
+    bytewords::Style, decoder::Error as DecodeError, max_fragment_len, HeaplessDecoder,
+    HeaplessEncoder, ParseURError, UR,
+};
+#[cfg(feature = "alloc")]
+pub use crate::{Decoder, Encoder};
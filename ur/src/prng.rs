@@ -2,8 +2,22 @@
 // SPDX-FileCopyrightText: © 2020 Dominik Spicher <dominikspicher@gmail.com>
 // SPDX-License-Identifier: MIT
 
+//! Seeded pseudo-random number generation.
+//!
+//! This is the exact PRNG used by the [`fountain`](crate::fountain) encoder
+//! and decoder to choose the fragments that make up each part, it is exposed
+//! so that other crates can reproduce the same sequences, for example to
+//! generate deterministic test data.
+
+use crate::collections::Vec;
 use rand_xoshiro::{rand_core::RngCore, rand_core::SeedableRng, Xoshiro256StarStar};
 
+/// Xoshiro256** pseudo-random number generator as specified in
+/// [BCR-2020-005].
+///
+/// Seeding from bytes or a string hashes the input with SHA-256 first.
+///
+/// [BCR-2020-005]: https://github.com/BlockchainCommons/Research/blob/master/papers/bcr-2020-005-ur.md
 #[allow(clippy::module_name_repetitions)]
 pub struct Xoshiro256 {
     inner: Xoshiro256StarStar,
@@ -25,19 +39,56 @@ impl From<&[u8]> for Xoshiro256 {
 #[allow(clippy::cast_precision_loss)]
 #[allow(clippy::cast_possible_truncation)]
 impl Xoshiro256 {
+    /// Returns the next random 64-bit integer.
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> u64 {
         self.inner.next_u64()
     }
 
+    /// Returns the next random number in the `[0, 1)` range.
     pub fn next_double(&mut self) -> f64 {
         self.next() as f64 / (u64::MAX as f64 + 1.0)
     }
 
+    /// Returns the next random integer in the `[low, high]` range.
     #[allow(clippy::cast_sign_loss)]
     pub fn next_int(&mut self, low: u64, high: u64) -> u64 {
         (self.next_double() * ((high - low + 1) as f64)) as u64 + low
     }
+
+    /// Returns the next random byte.
+    pub fn next_byte(&mut self) -> u8 {
+        self.next_int(0, 255) as u8
+    }
+}
+
+/// Seeded shuffle of `items` as specified in [BCR-2020-005].
+///
+/// Randomly removes elements from `items` and pushes them to `shuffled`
+/// until `shuffled` contains `count` elements.  Passing `items.len()` as
+/// `count` produces a full permutation.
+///
+/// # Panics
+///
+/// This function panics if `count` is bigger than the length of `items`
+/// or if `shuffled` has not enough capacity.
+///
+/// [BCR-2020-005]: https://github.com/BlockchainCommons/Research/blob/master/papers/bcr-2020-005-ur.md
+pub fn shuffle<T, I, O>(prng: &mut Xoshiro256, items: &mut I, shuffled: &mut O, count: usize)
+where
+    I: Vec<T>,
+    O: Vec<T>,
+{
+    assert!(count <= items.len());
+
+    shuffled.reserve(count);
+    while shuffled.len() < count {
+        let index = usize::try_from(prng.next_int(0, (items.len() - 1) as u64)).unwrap();
+        let item = items.remove(index);
+        if shuffled.try_push(item).is_err() {
+            panic!("not enough capacity to store shuffled item");
+        }
+    }
 }
 
 impl From<&str> for Xoshiro256 {
@@ -69,13 +120,9 @@ impl From<[u8; 32]> for Xoshiro256 {
 #[cfg(feature = "alloc")]
 pub mod test_utils {
     use super::*;
+    use alloc::vec::Vec;
 
     impl Xoshiro256 {
-        #[allow(clippy::cast_possible_truncation)]
-        fn next_byte(&mut self) -> u8 {
-            self.next_int(0, 255) as u8
-        }
-
         pub fn next_bytes(&mut self, n: usize) -> Vec<u8> {
             (0..n).map(|_| self.next_byte()).collect()
         }
@@ -122,4 +169,41 @@ mod tests {
             assert_eq!(rng.next_int(1, 10), e);
         }
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_shuffle() {
+        const COUNT: usize = 10;
+        const EXPECTED: &[[usize; COUNT]] = &[
+            [6, 4, 9, 3, 10, 5, 7, 8, 1, 2],
+            [10, 8, 6, 5, 1, 2, 3, 9, 7, 4],
+            [6, 4, 5, 8, 9, 3, 2, 1, 7, 10],
+            [7, 3, 5, 1, 10, 9, 4, 8, 2, 6],
+            [8, 5, 7, 10, 2, 1, 4, 3, 9, 6],
+            [4, 3, 5, 6, 10, 2, 7, 8, 9, 1],
+            [5, 1, 3, 9, 4, 6, 2, 10, 7, 8],
+            [2, 1, 10, 8, 9, 4, 7, 6, 3, 5],
+            [6, 7, 10, 4, 8, 9, 2, 3, 1, 5],
+            [10, 2, 1, 7, 9, 5, 6, 3, 4, 8],
+        ];
+
+        let mut prng = Xoshiro256::from("Wolf");
+        for &expected in EXPECTED {
+            let mut items: alloc::vec::Vec<usize> = (1..=COUNT).collect();
+            let mut shuffled = alloc::vec::Vec::new();
+            shuffle(&mut prng, &mut items, &mut shuffled, COUNT);
+
+            assert_eq!(shuffled, expected);
+        }
+    }
+
+    #[test]
+    fn test_shuffle_heapless() {
+        let mut prng = Xoshiro256::from("Wolf");
+        let mut items: heapless::Vec<usize, 10> = (1..=10).collect();
+        let mut shuffled: heapless::Vec<usize, 10> = heapless::Vec::new();
+        shuffle(&mut prng, &mut items, &mut shuffled, 10);
+
+        assert_eq!(shuffled, [6, 4, 9, 3, 10, 5, 7, 8, 1, 2]);
+    }
 }
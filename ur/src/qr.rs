@@ -0,0 +1,300 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: MIT
+
+//! QR code capacities.
+//!
+//! Capacity of the QR code versions for each error correction level and
+//! encoding mode, as in ISO/IEC 18004, and maximum length of the Uniform
+//! Resource parts that fit in a QR code version to choose the fragment length
+//! of the [encoder](crate::BaseEncoder), see
+//! [`BaseEncoder::start_for_qr`](crate::BaseEncoder::start_for_qr).
+//!
+//! The Uniform Resources only use characters of the alphanumeric mode when
+//! they are in uppercase, which is more compact than the byte mode.
+//!
+//! # Examples
+//!
+//! ```
+//! # use foundation_ur::qr::{max_part_len, ErrorCorrection, Mode};
+//! assert_eq!(max_part_len(10, ErrorCorrection::Low, Mode::Alphanumeric), Some(395));
+//! assert_eq!(max_part_len(10, ErrorCorrection::Low, Mode::Byte), Some(271));
+//! assert_eq!(max_part_len(41, ErrorCorrection::Low, Mode::Byte), None);
+//! ```
+
+use crate::len::non_payload_len;
+
+/// Smallest QR code version.
+pub const MIN_VERSION: u8 = 1;
+
+/// Largest QR code version.
+pub const MAX_VERSION: u8 = 40;
+
+/// Number of data codewords of each version, for each error correction
+/// level in the order of [`ErrorCorrection`].
+const DATA_CODEWORDS: [[u16; 4]; MAX_VERSION as usize] = [
+    [19, 16, 13, 9],
+    [34, 28, 22, 16],
+    [55, 44, 34, 26],
+    [80, 64, 48, 36],
+    [108, 86, 62, 46],
+    [136, 108, 76, 60],
+    [156, 124, 88, 66],
+    [194, 154, 110, 86],
+    [232, 182, 132, 100],
+    [274, 216, 154, 122],
+    [324, 254, 180, 140],
+    [370, 290, 206, 158],
+    [428, 334, 244, 180],
+    [461, 365, 261, 197],
+    [523, 415, 295, 223],
+    [589, 453, 325, 253],
+    [647, 507, 367, 283],
+    [721, 563, 397, 313],
+    [795, 627, 445, 341],
+    [861, 669, 485, 385],
+    [932, 714, 512, 406],
+    [1006, 782, 568, 442],
+    [1094, 860, 614, 464],
+    [1174, 914, 664, 514],
+    [1276, 1000, 718, 538],
+    [1370, 1062, 754, 596],
+    [1468, 1128, 808, 628],
+    [1531, 1193, 871, 661],
+    [1631, 1267, 911, 701],
+    [1735, 1373, 985, 745],
+    [1843, 1455, 1033, 793],
+    [1955, 1541, 1115, 845],
+    [2071, 1631, 1171, 901],
+    [2191, 1725, 1231, 961],
+    [2306, 1812, 1286, 986],
+    [2434, 1914, 1354, 1054],
+    [2566, 1992, 1426, 1096],
+    [2702, 2102, 1502, 1142],
+    [2812, 2216, 1582, 1222],
+    [2956, 2334, 1666, 1276],
+];
+
+/// Length of the mode indicator, in bits.
+const MODE_INDICATOR_BITS: usize = 4;
+
+/// Error correction level, from the lowest to the highest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ErrorCorrection {
+    /// Recovers 7% of the data.
+    Low,
+    /// Recovers 15% of the data.
+    Medium,
+    /// Recovers 25% of the data.
+    Quartile,
+    /// Recovers 30% of the data.
+    High,
+}
+
+impl ErrorCorrection {
+    /// All the levels, from the lowest to the highest.
+    pub const ALL: [Self; 4] = [Self::Low, Self::Medium, Self::Quartile, Self::High];
+}
+
+/// Encoding mode of the QR code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Mode {
+    /// Decimal digits.
+    Numeric,
+    /// Digits, uppercase letters, space and `$%*+-./:`.
+    Alphanumeric,
+    /// Arbitrary bytes.
+    Byte,
+}
+
+impl Mode {
+    /// Returns the most compact mode that can encode `data`.
+    pub fn of(data: &[u8]) -> Self {
+        if data.iter().all(u8::is_ascii_digit) {
+            Mode::Numeric
+        } else if data.iter().all(|&b| is_alphanumeric(b)) {
+            Mode::Alphanumeric
+        } else {
+            Mode::Byte
+        }
+    }
+
+    /// Length of the character count indicator, in bits.
+    const fn count_bits(self, version: u8) -> usize {
+        let group = if version < 10 {
+            0
+        } else if version < 27 {
+            1
+        } else {
+            2
+        };
+
+        match self {
+            Mode::Numeric => [10, 12, 14][group],
+            Mode::Alphanumeric => [9, 11, 13][group],
+            Mode::Byte => [8, 16, 16][group],
+        }
+    }
+
+    /// Number of characters that fit in `bits`.
+    const fn characters(self, bits: usize) -> usize {
+        match self {
+            // 3 digits in 10 bits, the rest in 4 or 7 bits.
+            Mode::Numeric => {
+                let rest = match bits % 10 {
+                    7.. => 2,
+                    4.. => 1,
+                    _ => 0,
+                };
+                bits / 10 * 3 + rest
+            }
+            // 2 characters in 11 bits, the last one in 6 bits.
+            Mode::Alphanumeric => bits / 11 * 2 + if bits % 11 >= 6 { 1 } else { 0 },
+            Mode::Byte => bits / 8,
+        }
+    }
+}
+
+fn is_alphanumeric(b: u8) -> bool {
+    matches!(b, b'0'..=b'9' | b'A'..=b'Z' | b' ' | b'$' | b'%' | b'*' | b'+' | b'-' | b'.' | b'/' | b':')
+}
+
+/// Number of data bits of a QR code.
+///
+/// # Panics
+///
+/// Panics if `version` is not between [`MIN_VERSION`] and [`MAX_VERSION`].
+pub const fn data_bits(version: u8, ecc: ErrorCorrection) -> usize {
+    assert!(version >= MIN_VERSION && version <= MAX_VERSION);
+
+    DATA_CODEWORDS[version as usize - 1][ecc as usize] as usize * 8
+}
+
+/// Number of characters that fit in a QR code with a single segment.
+///
+/// The characters are bytes for [`Mode::Byte`].
+///
+/// # Panics
+///
+/// Panics if `version` is not between [`MIN_VERSION`] and [`MAX_VERSION`].
+///
+/// # Examples
+///
+/// ```
+/// # use foundation_ur::qr::{capacity, ErrorCorrection, Mode};
+/// assert_eq!(capacity(1, ErrorCorrection::Low, Mode::Numeric), 41);
+/// assert_eq!(capacity(40, ErrorCorrection::Low, Mode::Byte), 2953);
+/// ```
+pub const fn capacity(version: u8, ecc: ErrorCorrection, mode: Mode) -> usize {
+    let bits = data_bits(version, ecc) - MODE_INDICATOR_BITS - mode.count_bits(version);
+    mode.characters(bits)
+}
+
+/// Returns the maximum length of a UR part that fits in a QR code with a
+/// single segment, or `None` if `version` is not between [`MIN_VERSION`]
+/// and [`MAX_VERSION`].
+pub const fn max_part_len(version: u8, ecc: ErrorCorrection, mode: Mode) -> Option<usize> {
+    if version < MIN_VERSION || version > MAX_VERSION {
+        return None;
+    }
+
+    Some(capacity(version, ecc, mode))
+}
+
+/// Returns the maximum fragment length of the parts of a `message_len`
+/// bytes message that fit in an alphanumeric QR code, or `None` if no
+/// fragment fits.
+pub(crate) fn max_fragment_len_for_qr(
+    ur_type: &str,
+    message_len: usize,
+    version: u8,
+    ecc: ErrorCorrection,
+) -> Option<usize> {
+    let max_characters = max_part_len(version, ecc, Mode::Alphanumeric)?;
+
+    // Each fragment has at least one byte, so there are at most as many
+    // fragments as bytes in the message.
+    let fragment_len = max_characters.checked_sub(non_payload_len(ur_type, message_len))? / 2;
+    Some(fragment_len).filter(|&len| len > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_part_len() {
+        use ErrorCorrection::*;
+
+        assert_eq!(max_part_len(1, Low, Mode::Alphanumeric), Some(25));
+        assert_eq!(max_part_len(1, High, Mode::Byte), Some(7));
+        assert_eq!(max_part_len(27, Medium, Mode::Alphanumeric), Some(1637));
+        assert_eq!(max_part_len(40, Low, Mode::Alphanumeric), Some(4296));
+        assert_eq!(max_part_len(40, Low, Mode::Byte), Some(2953));
+        assert_eq!(max_part_len(0, Low, Mode::Byte), None);
+    }
+
+    #[test]
+    fn test_capacity() {
+        use ErrorCorrection::*;
+        use Mode::*;
+
+        // Values from the capacity tables of the standard.
+        assert_eq!(capacity(1, Low, Numeric), 41);
+        assert_eq!(capacity(1, Low, Alphanumeric), 25);
+        assert_eq!(capacity(1, Low, Byte), 17);
+        assert_eq!(capacity(1, High, Byte), 7);
+        assert_eq!(capacity(2, Medium, Alphanumeric), 38);
+        assert_eq!(capacity(10, Medium, Byte), 213);
+        assert_eq!(capacity(40, Low, Numeric), 7089);
+        assert_eq!(capacity(40, Low, Alphanumeric), 4296);
+        assert_eq!(capacity(40, Low, Byte), 2953);
+        assert_eq!(capacity(40, High, Byte), 1273);
+
+        for ecc in ErrorCorrection::ALL {
+            for mode in [Numeric, Alphanumeric, Byte] {
+                for version in MIN_VERSION..MAX_VERSION {
+                    assert!(capacity(version, ecc, mode) < capacity(version + 1, ecc, mode));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mode_of() {
+        assert_eq!(Mode::of(b"0123"), Mode::Numeric);
+        assert_eq!(Mode::of(b"UR:BYTES/AEAD"), Mode::Alphanumeric);
+        assert_eq!(Mode::of(b"ur:bytes/aead"), Mode::Byte);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_start_for_qr() {
+        use crate::{fountain::encoder::Error, Encoder};
+
+        let message = [0x5A; 2000];
+        for version in [5, 10, 25, 40] {
+            let max_len = max_part_len(version, ErrorCorrection::Medium, Mode::Alphanumeric);
+
+            let mut encoder = Encoder::new();
+            encoder
+                .start_for_qr("crypto-psbt", &message, version, ErrorCorrection::Medium)
+                .unwrap();
+            for _ in 0..encoder.sequence_count() + 4 {
+                let part = alloc::string::ToString::to_string(&encoder.next_part());
+                assert!(part.len() <= max_len.unwrap());
+            }
+        }
+
+        let mut encoder = Encoder::new();
+        assert_eq!(
+            encoder.start_for_qr("crypto-psbt", &message, 1, ErrorCorrection::High),
+            Err(Error::InvalidFragmentLength)
+        );
+        assert_eq!(
+            encoder.start_for_qr("crypto-psbt", &message, 41, ErrorCorrection::Low),
+            Err(Error::InvalidFragmentLength)
+        );
+    }
+}
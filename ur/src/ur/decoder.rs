@@ -60,6 +60,7 @@ impl<
             fountain: fountain::decoder::HeaplessDecoder::new(),
             fragment: heapless::Vec::new(),
             ur_type: heapless::Vec::new(),
+            last_activity: None,
         }
     }
 }
@@ -74,6 +75,7 @@ pub struct BaseDecoder<T: Types> {
     fountain: fountain::decoder::BaseDecoder<T::Decoder>,
     fragment: T::Fragment,
     ur_type: T::URType,
+    last_activity: Option<u64>,
 }
 
 impl<T: Types> BaseDecoder<T> {
@@ -93,21 +95,77 @@ impl<T: Types> BaseDecoder<T> {
     ///  - The CBOR-encoded fountain part may be inconsistent with previously received ones
     ///
     /// In all these cases, an error will be returned.
+    ///
+    /// [`UR::validate_fragment`] can be used before to discard corrupted
+    /// fragments without touching the decoder state.
     pub fn receive(&mut self, ur: UR) -> Result<(), Error> {
         if !ur.is_multi_part() {
             return Err(Error::NotMultiPart);
         }
 
-        if self.ur_type.is_empty() {
+        let ur_type = ur.as_type();
+        let first = self.ur_type.is_empty();
+        if first {
             self.ur_type
-                .try_extend_from_slice(ur.as_type().as_bytes())
+                .try_extend_from_slice(ur_type.as_bytes())
                 .map_err(|_| Error::URTypeTooBig {
-                    size: ur.as_type().as_bytes().len(),
+                    size: ur_type.len(),
                 })?;
-        } else if (&self.ur_type as &[_]) != ur.as_type().as_bytes() {
-            return Err(Error::InconsistentType);
+        } else if (&self.ur_type as &[_]) != ur_type.as_bytes() {
+            return Err(Error::MismatchedType {
+                expected: type_name(self.ur_type().unwrap()),
+                received: type_name(ur_type),
+            });
+        }
+
+        let result = self.receive_part(&ur);
+        // The type is only kept once a part was accepted, so that an
+        // invalid first part doesn't set it.
+        if result.is_err() && first {
+            self.ur_type.clear();
         }
+        result
+    }
+
+    /// Receives a part like [`receive`](Self::receive), recording `now` as
+    /// the time of the last activity when the part is accepted.
+    ///
+    /// `now` is a timestamp of the caller, in any unit, see
+    /// [`is_stale`](Self::is_stale).
+    pub fn receive_at(&mut self, ur: UR, now: u64) -> Result<(), Error> {
+        self.receive(ur)?;
+        self.last_activity = Some(now);
+        Ok(())
+    }
+
+    /// Returns the time a part was last accepted by
+    /// [`receive_at`](Self::receive_at).
+    #[inline]
+    pub fn last_activity(&self) -> Option<u64> {
+        self.last_activity
+    }
 
+    /// Returns `true` if no part was accepted by
+    /// [`receive_at`](Self::receive_at) for more than `timeout`.
+    ///
+    /// An abandoned scan can then be [cleared](Self::clear) so that its
+    /// parts don't collide with the ones of the next scan. `now` and
+    /// `timeout` are in the unit of the timestamps given to
+    /// [`receive_at`](Self::receive_at).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use foundation_ur::HeaplessDecoder;
+    /// let decoder: HeaplessDecoder<8, 8, 8, 8, 8, 8> = HeaplessDecoder::new();
+    /// assert!(!decoder.is_stale(1000, 30));
+    /// ```
+    pub fn is_stale(&self, now: u64, timeout: u64) -> bool {
+        self.last_activity
+            .is_some_and(|last_activity| now.saturating_sub(last_activity) > timeout)
+    }
+
+    fn receive_part(&mut self, ur: &UR) -> Result<(), Error> {
         let part = if !ur.is_deserialized() {
             let bytewords = ur
                 .as_bytewords()
@@ -141,7 +199,10 @@ impl<T: Types> BaseDecoder<T> {
         self.fountain.is_complete()
     }
 
-    /// Returns the UR type.
+    /// Returns the UR type of the parts received.
+    ///
+    /// The type is known once a part was received, afterwards parts of a
+    /// different type are rejected with [`Error::MismatchedType`].
     pub fn ur_type(&self) -> Option<&str> {
         if !self.ur_type.is_empty() {
             Some(str::from_utf8(&self.ur_type).unwrap())
@@ -167,12 +228,44 @@ impl<T: Types> BaseDecoder<T> {
         self.fountain.message().map_err(Error::from)
     }
 
+    /// Returns the longest prefix of the message decoded so far.
+    ///
+    /// See [`fountain::decoder::BaseDecoder::contiguous_message`].
+    #[inline]
+    pub fn contiguous_message(&self) -> &[u8] {
+        self.fountain.contiguous_message()
+    }
+
+    /// Returns the sequence numbers of the parts that may be corrupted.
+    ///
+    /// See [`fountain::decoder::BaseDecoder::suspected_sequences`].
+    #[inline]
+    pub fn suspected_sequences(&self) -> impl Iterator<Item = u32> + '_ {
+        self.fountain.suspected_sequences()
+    }
+
     /// Calculate estimated percentage of completion.
     #[inline]
     pub fn estimated_percent_complete(&self) -> f64 {
         self.fountain.estimated_percent_complete()
     }
 
+    /// Returns how much of the decoder storage is in use.
+    ///
+    /// See [`fountain::decoder::BaseDecoder::capacity_report`].
+    #[inline]
+    pub fn capacity_report(&self) -> fountain::CapacityReport {
+        self.fountain.capacity_report()
+    }
+
+    /// Limit the number of mixed parts stored.
+    ///
+    /// See [`fountain::decoder::BaseDecoder::set_max_mixed_parts`].
+    #[inline]
+    pub fn set_max_mixed_parts(&mut self, max_mixed_parts: Option<usize>) {
+        self.fountain.set_max_mixed_parts(max_mixed_parts);
+    }
+
     /// Returns `true` if the decoder doesn't contain any data.
     ///
     /// Once a part is successfully [received](Self::receive) this method will
@@ -195,6 +288,7 @@ impl<T: Types> BaseDecoder<T> {
         self.fountain.clear();
         self.fragment.clear();
         self.ur_type.clear();
+        self.last_activity = None;
     }
 }
 
@@ -265,6 +359,23 @@ impl<
     type URType = heapless::Vec<u8, MAX_UR_TYPE>;
 }
 
+/// Maximum length of the UR types in [`Error::MismatchedType`].
+pub const MAX_TYPE_NAME_LEN: usize = 32;
+
+/// A UR type in an [`Error`].
+pub type TypeName = heapless::String<MAX_TYPE_NAME_LEN>;
+
+fn type_name(ur_type: &str) -> TypeName {
+    let mut len = ur_type.len().min(MAX_TYPE_NAME_LEN);
+    while !ur_type.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    let mut name = TypeName::new();
+    name.push_str(&ur_type[..len]).unwrap();
+    name
+}
+
 /// Errors that can happen during decoding.
 #[derive(Debug)]
 pub enum Error {
@@ -286,8 +397,18 @@ pub enum Error {
         /// The size of the UR type.
         size: usize,
     },
-    /// The UR type of this fragment is not consistent.
-    InconsistentType,
+    /// The UR type of this fragment is not the type of the previous ones.
+    ///
+    /// The types are truncated to [`MAX_TYPE_NAME_LEN`] bytes.
+    MismatchedType {
+        /// The UR type of the previous fragments.
+        expected: TypeName,
+        /// The UR type of this fragment.
+        received: TypeName,
+    },
+    /// The sequence numbers of the URI are not consistent with the ones of
+    /// the fragment.
+    InconsistentIndices,
 }
 
 #[cfg(feature = "std")]
@@ -307,9 +428,13 @@ impl fmt::Display for Error {
             Error::URTypeTooBig { size } => {
                 write!(f, "The UR type ({size} bytes) is too big for the decoder")
             }
-            Error::InconsistentType => write!(
+            Error::MismatchedType { expected, received } => write!(
+                f,
+                "The type of the received fragment ({received}) is not the type of the previous fragments ({expected})"
+            ),
+            Error::InconsistentIndices => write!(
                 f,
-                "The received fragment is not consistent with the type of the previous fragments"
+                "The sequence numbers of the fragment are not consistent with the ones of the URI"
             ),
         }
     }
@@ -4,7 +4,7 @@
 
 //! Encoder.
 
-use crate::{fountain, ur::UR};
+use crate::{fountain, qr, ur::UR};
 
 use core::str;
 
@@ -61,13 +61,106 @@ impl<'a, 'b, T: fountain::encoder::Types> BaseEncoder<'a, 'b, T> {
     ///
     /// See the [`crate`] documentation for an example.
     ///
+    /// # Errors
+    ///
+    /// See [`fountain::encoder::BaseEncoder::start`] for the errors
+    /// returned.
+    pub fn start(
+        &mut self,
+        ur_type: &'a str,
+        message: &'b [u8],
+        max_fragment_length: usize,
+    ) -> Result<(), fountain::encoder::Error> {
+        self.fountain.start(message, max_fragment_length)?;
+        self.ur_type = Some(ur_type);
+        Ok(())
+    }
+
+    /// Creates a new encoder for the given message payload, with the
+    /// largest fragments whose parts fit in a QR code of `qr_version` with
+    /// the `ecc` error correction level.
+    ///
+    /// The parts must be displayed in uppercase so that the QR code uses
+    /// the alphanumeric mode, see [`qr`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use foundation_ur::{qr::ErrorCorrection, HeaplessEncoder};
+    /// # let mut encoder: HeaplessEncoder<256, 64> = HeaplessEncoder::new();
+    /// let message = [0xAB; 1000];
+    /// encoder.start_for_qr("bytes", &message, 10, ErrorCorrection::Low).unwrap();
+    ///
+    /// let part = encoder.next_part().to_string().to_uppercase();
+    /// assert!(part.len() <= 395);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns
+    /// [`InvalidFragmentLength`](fountain::encoder::Error::InvalidFragmentLength)
+    /// if the QR code version is invalid or too small for any fragment. See
+    /// [`fountain::encoder::BaseEncoder::start`] for the other errors
+    /// returned.
+    pub fn start_for_qr(
+        &mut self,
+        ur_type: &'a str,
+        message: &'b [u8],
+        qr_version: u8,
+        ecc: qr::ErrorCorrection,
+    ) -> Result<(), fountain::encoder::Error> {
+        let max_fragment_length =
+            qr::max_fragment_len_for_qr(ur_type, message.len(), qr_version, ecc)
+                .ok_or(fountain::encoder::Error::InvalidFragmentLength)?;
+        self.start(ur_type, message, max_fragment_length)
+    }
+
+    /// Returns the state of the encoder, to continue the transmission
+    /// later with [`restore_state`](Self::restore_state).
+    ///
     /// # Panics
     ///
-    /// This function panics if `ur_type` or `message` is empty, or if
-    /// `max_fragment_length` is zero.
-    pub fn start(&mut self, ur_type: &'a str, message: &'b [u8], max_fragment_length: usize) {
+    /// This function panics if the encoder was not started.
+    #[must_use]
+    #[inline]
+    pub fn save_state(&self) -> fountain::EncoderState {
+        self.fountain.save_state()
+    }
+
+    /// Continue encoding `message` from a state returned by
+    /// [`save_state`](Self::save_state).
+    ///
+    /// # Errors
+    ///
+    /// See [`fountain::encoder::BaseEncoder::restore_state`] for the errors
+    /// returned.
+    pub fn restore_state(
+        &mut self,
+        ur_type: &'a str,
+        message: &'b [u8],
+        state: &fountain::EncoderState,
+    ) -> Result<(), fountain::encoder::Error> {
+        self.fountain.restore_state(message, state)?;
         self.ur_type = Some(ur_type);
-        self.fountain.start(message, max_fragment_length);
+        Ok(())
+    }
+
+    /// Set the order in which the parts are emitted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use foundation_ur::{fountain::EmissionSchedule, HeaplessEncoder};
+    /// # let mut encoder: HeaplessEncoder<8, 8> = HeaplessEncoder::new();
+    /// encoder.set_emission_schedule(EmissionSchedule::Interleaved { interval: 3 });
+    /// encoder.start("bytes", "data".as_bytes(), 1).unwrap();
+    ///
+    /// assert!(encoder.next_part().to_string().starts_with("ur:bytes/1-4/"));
+    /// assert!(encoder.next_part().to_string().starts_with("ur:bytes/4-4/"));
+    /// ```
+    #[inline]
+    pub fn set_emission_schedule(&mut self, schedule: fountain::EmissionSchedule) {
+        self.fountain.set_emission_schedule(schedule);
     }
 
     /// Returns the current count of already emitted parts.
@@ -77,7 +170,7 @@ impl<'a, 'b, T: fountain::encoder::Types> BaseEncoder<'a, 'b, T> {
     /// ```
     /// # use foundation_ur::HeaplessEncoder;
     /// # let mut encoder: HeaplessEncoder<8, 8> = HeaplessEncoder::new();
-    /// encoder.start("bytes", "data".as_bytes(), 5);
+    /// encoder.start("bytes", "data".as_bytes(), 5).unwrap();
     ///
     /// assert_eq!(encoder.current_sequence(), 0);
     /// encoder.next_part();
@@ -95,7 +188,7 @@ impl<'a, 'b, T: fountain::encoder::Types> BaseEncoder<'a, 'b, T> {
     /// ```
     /// # use foundation_ur::HeaplessEncoder;
     /// # let mut encoder: HeaplessEncoder<8, 8> = HeaplessEncoder::new();
-    /// encoder.start("bytes", "data".as_bytes(), 3);
+    /// encoder.start("bytes", "data".as_bytes(), 3).unwrap();
     /// assert_eq!(encoder.sequence_count(), 2);
     /// ```
     #[inline]
@@ -153,7 +246,7 @@ pub mod tests {
             encoder: &mut BaseEncoder<'static, 'a, T>,
             ur: &'a [u8],
         ) {
-            encoder.start("bytes", &ur, 30);
+            encoder.start("bytes", ur, 30).unwrap();
             assert_eq!(encoder.sequence_count(), 9);
             for (index, &part) in TEST_VECTORS.iter().enumerate() {
                 assert_eq!(encoder.current_sequence(), index.try_into().unwrap());
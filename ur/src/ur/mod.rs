@@ -14,8 +14,8 @@ pub use self::encoder::Encoder;
 pub use self::encoder::{BaseEncoder, HeaplessEncoder};
 
 use crate::{
-    bytewords::{Bytewords, Style},
-    fountain::part::Part,
+    bytewords::{self, Bytewords, Style},
+    fountain::{self, part::Part},
 };
 use core::{fmt, num::ParseIntError};
 
@@ -176,6 +176,120 @@ impl<'a> UR<'a> {
             _ => None,
         }
     }
+
+    /// Validate a multi-part fragment without decoding it.
+    ///
+    /// Verifies the bytewords checksum and the CBOR structure of the part
+    /// using a small stack buffer, so that corrupted fragments can be
+    /// discarded before passing them to a [`BaseDecoder`].
+    ///
+    /// # Errors
+    ///
+    /// - [`decoder::Error::NotMultiPart`] if the resource is single-part.
+    /// - [`decoder::Error::Bytewords`] if the bytewords are invalid.
+    /// - [`decoder::Error::Cbor`] if the part is not valid CBOR.
+    /// - [`decoder::Error::Fountain`] if the part is not valid.
+    /// - [`decoder::Error::InconsistentIndices`] if the sequence numbers of
+    ///   the URI don't match the ones of the part.
+    pub fn validate_fragment(&self) -> Result<(), decoder::Error> {
+        let (sequence, sequence_count, part) = match self {
+            UR::MultiPart {
+                fragment,
+                sequence,
+                sequence_count,
+                ..
+            } => {
+                let mut head = [0u8; Part::max_encoded_len()];
+                let len = bytewords::validate_with_head(fragment, Style::Minimal, &mut head)?;
+                let part = part_description(&head[..len.min(head.len())], len)?;
+                (*sequence, *sequence_count, part)
+            }
+            UR::MultiPartDeserialized { fragment, .. } => (
+                fragment.sequence,
+                fragment.sequence_count,
+                (
+                    fragment.sequence,
+                    fragment.sequence_count,
+                    fragment.message_length,
+                    fragment.data.len(),
+                ),
+            ),
+            _ => return Err(decoder::Error::NotMultiPart),
+        };
+
+        let (part_sequence, part_sequence_count, message_length, data_len) = part;
+        if part_sequence == 0
+            || part_sequence_count == 0
+            || message_length == 0
+            || data_len == 0
+            || data_len > message_length
+        {
+            return Err(fountain::decoder::Error::InvalidPart.into());
+        }
+
+        if sequence != part_sequence || sequence_count != part_sequence_count {
+            return Err(decoder::Error::InconsistentIndices);
+        }
+
+        Ok(())
+    }
+}
+
+/// Decode the sequence number, the sequence count, the message length and
+/// the data length of a CBOR encoded [`Part`] of `len` bytes from its
+/// first bytes in `head`.
+fn part_description(
+    head: &[u8],
+    len: usize,
+) -> Result<(u32, u32, usize, usize), minicbor::decode::Error> {
+    let mut d = minicbor::Decoder::new(head);
+    if !matches!(d.array()?, Some(5)) {
+        return Err(minicbor::decode::Error::message(
+            "invalid CBOR array length",
+        ));
+    }
+
+    let sequence = d.u32()?;
+    let sequence_count = d.u32()?;
+    let message_length = d.u32()?;
+    d.u32()?;
+
+    let position = d.position();
+    let (header_len, data_len) = bytes_header(&head[position..])
+        .ok_or_else(|| minicbor::decode::Error::message("expected bytes").at(position))?;
+    let message_length = usize::try_from(message_length)
+        .map_err(|_| minicbor::decode::Error::message("expected usize"))?;
+    let data_len = usize::try_from(data_len)
+        .ok()
+        .filter(|&data_len| Some(len) == (position + header_len).checked_add(data_len))
+        .ok_or_else(|| minicbor::decode::Error::message("invalid data length").at(position))?;
+
+    Ok((sequence, sequence_count, message_length, data_len))
+}
+
+/// Parse a CBOR byte string header, returns its length and the length of
+/// the byte string.
+fn bytes_header(i: &[u8]) -> Option<(usize, u64)> {
+    let (&initial, i) = i.split_first()?;
+    if initial >> 5 != 2 {
+        return None;
+    }
+
+    let n = match initial & 0x1F {
+        n @ 0..=23 => return Some((1, u64::from(n))),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        _ => return None,
+    };
+
+    let mut value = 0;
+    for &b in i.get(..n)? {
+        value = (value << 8) | u64::from(b);
+    }
+
+    Some((1 + n, value))
 }
 
 impl<'a> fmt::Display for UR<'a> {
@@ -251,7 +365,21 @@ impl From<ParseIntError> for ParseURError {
 pub fn to_string(ur_type: &str, message: &[u8]) -> alloc::string::String {
     let ur = UR::SinglePartDeserialized { ur_type, message };
 
-    ur.to_string()
+    alloc::string::ToString::to_string(&ur)
+}
+
+/// Encode a single part UR to a fixed capacity string.
+///
+/// Returns an error if the UR doesn't fit in `N` bytes.
+pub fn to_heapless_string<const N: usize>(
+    ur_type: &str,
+    message: &[u8],
+) -> Result<heapless::String<N>, fmt::Error> {
+    let ur = UR::SinglePartDeserialized { ur_type, message };
+
+    let mut s = heapless::String::new();
+    fmt::Write::write_fmt(&mut s, format_args!("{ur}"))?;
+    Ok(s)
 }
 
 #[cfg(test)]
@@ -261,7 +389,7 @@ mod tests {
 
     #[cfg(feature = "alloc")]
     pub fn make_message_ur(length: usize, seed: &str) -> Vec<u8> {
-        let message = crate::xoshiro::test_utils::make_message(seed, length);
+        let message = crate::prng::test_utils::make_message(seed, length);
         minicbor::to_vec(minicbor::bytes::ByteVec::from(message)).unwrap()
     }
 
@@ -285,7 +413,7 @@ mod tests {
     fn test_ur_roundtrip() {
         let ur = make_message_ur(32767, "Wolf");
         let mut encoder = Encoder::new();
-        encoder.start("bytes", &ur, 1000);
+        encoder.start("bytes", &ur, 1000).unwrap();
 
         let mut decoder = Decoder::default();
         while !decoder.is_complete() {
@@ -295,12 +423,120 @@ mod tests {
         assert_eq!(decoder.message().unwrap(), Some(ur.as_slice()));
     }
 
+    #[test]
+    fn test_to_heapless_string() {
+        const EXPECTED: &str = "ur:bytes/hdeymejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtgwdpfnsboxgwlbaawzuefywkdplrsrjynbvygabwjldapfcsdwkbrkch";
+
+        let mut message = [0; 52];
+        let message = match UR::parse(EXPECTED).unwrap() {
+            UR::SinglePart { message: words, .. } => {
+                let len = crate::bytewords::decode_to_slice(
+                    words,
+                    &mut message,
+                    crate::bytewords::Style::Minimal,
+                )
+                .unwrap();
+                &message[..len]
+            }
+            _ => unreachable!(),
+        };
+
+        let encoded = to_heapless_string::<128>("bytes", message).unwrap();
+        assert_eq!(encoded.as_str(), EXPECTED);
+        #[cfg(feature = "alloc")]
+        assert_eq!(to_string("bytes", message), encoded.as_str());
+
+        assert!(to_heapless_string::<64>("bytes", message).is_err());
+    }
+
     #[test]
     fn test_parser() {
         UR::parse("ur:bytes/aeadaolazmjendeoti").unwrap();
         UR::parse("ur:whatever-12/aeadaolazmjendeoti").unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_decoder_mismatched_type() {
+        const BYTES: &str = "ur:bytes/1-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh";
+        const CORRUPTED: &str = "ur:crypto-psbt/1-9/lpadascfadaxcywenbpljkhdcahkadadmejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh";
+        const PSBT: &str = "ur:crypto-psbt/1-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh";
+
+        let mut decoder = Decoder::default();
+
+        // A part that can't be decoded doesn't set the type.
+        assert!(decoder.receive(UR::parse(CORRUPTED).unwrap()).is_err());
+        assert_eq!(decoder.ur_type(), None);
+
+        decoder.receive(UR::parse(BYTES).unwrap()).unwrap();
+        assert_eq!(decoder.ur_type(), Some("bytes"));
+
+        match decoder.receive(UR::parse(PSBT).unwrap()) {
+            Err(decoder::Error::MismatchedType { expected, received }) => {
+                assert_eq!(expected, "bytes");
+                assert_eq!(received, "crypto-psbt");
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_decoder_is_stale() {
+        const BYTES: &str = "ur:bytes/1-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh";
+        const PSBT: &str = "ur:crypto-psbt/1-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh";
+
+        let mut decoder = Decoder::default();
+        assert_eq!(decoder.last_activity(), None);
+        assert!(!decoder.is_stale(100, 10));
+
+        decoder.receive_at(UR::parse(BYTES).unwrap(), 100).unwrap();
+        assert_eq!(decoder.last_activity(), Some(100));
+        assert!(!decoder.is_stale(110, 10));
+        assert!(decoder.is_stale(111, 10));
+
+        // Rejected parts are not an activity.
+        assert!(decoder.receive_at(UR::parse(PSBT).unwrap(), 105).is_err());
+        assert_eq!(decoder.last_activity(), Some(100));
+
+        decoder.clear();
+        assert_eq!(decoder.last_activity(), None);
+        assert!(!decoder.is_stale(1000, 10));
+    }
+
+    #[test]
+    fn test_validate_fragment() {
+        const VALID: &str = "ur:bytes/1-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh";
+        const CORRUPTED: &str = "ur:bytes/1-9/lpadascfadaxcywenbpljkhdcahkadadmejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh";
+        const WRONG_INDICES: &str = "ur:bytes/2-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh";
+
+        UR::parse(VALID).unwrap().validate_fragment().unwrap();
+
+        assert!(matches!(
+            UR::parse(CORRUPTED).unwrap().validate_fragment(),
+            Err(decoder::Error::Bytewords(
+                bytewords::DecodeError::InvalidChecksum { .. }
+            ))
+        ));
+
+        assert!(matches!(
+            UR::parse(WRONG_INDICES).unwrap().validate_fragment(),
+            Err(decoder::Error::InconsistentIndices)
+        ));
+
+        let ur = UR::parse("ur:bytes/1-1/aeadaolazmjendeoti").unwrap();
+        assert!(matches!(
+            ur.validate_fragment(),
+            Err(decoder::Error::Cbor(_))
+        ));
+
+        let ur = UR::parse("ur:bytes/aeadaolazmjendeoti").unwrap();
+        assert!(matches!(
+            ur.validate_fragment(),
+            Err(decoder::Error::NotMultiPart)
+        ));
+    }
+
     #[test]
     fn test_parser_errors() {
         const TEST_VECTORS: &[(&str, ParseURError)] = &[
@@ -317,7 +553,7 @@ mod tests {
         ];
 
         for (input, error) in TEST_VECTORS {
-            assert_eq!(UR::parse(&input).unwrap_err(), error.clone());
+            assert_eq!(UR::parse(input).unwrap_err(), error.clone());
         }
 
         match UR::parse("ur:bytes/1-1/toomuch/aeadaolazmjendeoti") {
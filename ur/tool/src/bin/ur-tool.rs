@@ -0,0 +1,195 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::{bail, Context, Result};
+use clap::{command, value_parser, Arg, ArgAction, ArgMatches, Command};
+use faster_hex::hex_string;
+use foundation_ur::{bytewords, bytewords::Style, Decoder, Encoder, UR};
+use foundation_urtypes::value::Value;
+use std::{
+    fs,
+    io::{self, BufRead, Read, Write},
+    path::PathBuf,
+};
+
+fn main() -> Result<()> {
+    let input = Arg::new("input")
+        .value_name("file-name")
+        .value_parser(value_parser!(PathBuf))
+        .help("Input file name, standard input if not specified");
+
+    let matches = command!()
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("encode")
+                .about("Encode a file to a list of UR parts, one per line")
+                .arg(input.clone())
+                .arg(
+                    Arg::new("type")
+                        .short('t')
+                        .long("type")
+                        .default_value("bytes")
+                        .help("UR type"),
+                )
+                .arg(
+                    Arg::new("cbor")
+                        .long("cbor")
+                        .action(ArgAction::SetTrue)
+                        .help("The file is already CBOR encoded, do not wrap it as a byte string"),
+                )
+                .arg(
+                    Arg::new("max-fragment-length")
+                        .short('l')
+                        .long("max-fragment-length")
+                        .value_parser(value_parser!(usize))
+                        .default_value("200")
+                        .help("Maximum length of a fragment, in bytes"),
+                )
+                .arg(
+                    Arg::new("extra-parts")
+                        .short('e')
+                        .long("extra-parts")
+                        .value_parser(value_parser!(u32))
+                        .default_value("0")
+                        .help("Number of parts to emit after the pure fragments"),
+                ),
+        )
+        .subcommand(
+            Command::new("decode")
+                .about("Decode a list of UR parts, one per line, to its CBOR payload")
+                .arg(input.clone())
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Output file name, standard output if not specified"),
+                ),
+        )
+        .subcommand(
+            Command::new("inspect")
+                .about("Decode a list of UR parts, one per line, and print the registry value")
+                .arg(input),
+        )
+        .get_matches();
+
+    match matches.subcommand() {
+        Some(("encode", matches)) => encode(matches),
+        Some(("decode", matches)) => decode(matches),
+        Some(("inspect", matches)) => inspect(matches),
+        _ => unreachable!("a subcommand is required"),
+    }
+}
+
+fn encode(matches: &ArgMatches) -> Result<()> {
+    let ur_type = matches.get_one::<String>("type").unwrap();
+    let max_fragment_length = *matches.get_one::<usize>("max-fragment-length").unwrap();
+    let extra_parts = *matches.get_one::<u32>("extra-parts").unwrap();
+
+    let data = read_input(matches.get_one::<PathBuf>("input"))?;
+    let message = if matches.get_flag("cbor") {
+        data
+    } else {
+        minicbor::to_vec(minicbor::bytes::ByteVec::from(data))
+            .context("failed to encode byte string")?
+    };
+
+    let mut encoder = Encoder::new();
+    encoder.start(ur_type, &message, max_fragment_length)?;
+
+    let mut stdout = io::stdout().lock();
+    if encoder.sequence_count() == 1 && extra_parts == 0 {
+        writeln!(stdout, "{}", UR::new(ur_type, &message))?;
+        return Ok(());
+    }
+
+    for _ in 0..encoder.sequence_count() + extra_parts {
+        writeln!(stdout, "{}", encoder.next_part())?;
+    }
+
+    Ok(())
+}
+
+fn decode(matches: &ArgMatches) -> Result<()> {
+    let (_, message) = receive(matches.get_one::<PathBuf>("input"))?;
+
+    match matches.get_one::<PathBuf>("output") {
+        Some(file_name) => fs::write(file_name, message).context("failed to write output")?,
+        None => io::stdout().lock().write_all(&message)?,
+    }
+
+    Ok(())
+}
+
+fn inspect(matches: &ArgMatches) -> Result<()> {
+    let (ur_type, message) = receive(matches.get_one::<PathBuf>("input"))?;
+
+    println!("{:>8}: {ur_type}", "Type");
+    println!("{:>8}: {} bytes", "Length", message.len());
+    println!("{:>8}: {}", "CBOR", hex_string(&message));
+
+    match Value::from_ur(&ur_type, &message) {
+        Ok(Value::Bytes(bytes)) => println!("{:>8}: {}", "Value", hex_string(bytes)),
+        Ok(Value::Psbt(psbt)) => println!("{:>8}: {}", "Value", hex_string(psbt.as_bytes())),
+        Ok(value) => println!("{:>8}: {value:#?}", "Value"),
+        Err(e) => println!("{:>8}: {e}", "Value"),
+    }
+
+    Ok(())
+}
+
+/// Receive the UR parts from the input until the message is complete.
+fn receive(file_name: Option<&PathBuf>) -> Result<(String, Vec<u8>)> {
+    let input = read_input(file_name)?;
+    let mut decoder = Decoder::default();
+
+    for (n, line) in input.as_slice().lines().enumerate() {
+        let line = line.context("failed to read input")?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let ur = UR::parse(line).with_context(|| format!("line {}: invalid UR", n + 1))?;
+        if ur.is_single_part() {
+            let bytewords = ur
+                .as_bytewords()
+                .expect("parsed resource should contain bytewords");
+            let message = bytewords::decode(bytewords, Style::Minimal)
+                .with_context(|| format!("line {}: invalid bytewords", n + 1))?;
+            return Ok((ur.as_type().to_string(), message));
+        }
+
+        decoder
+            .receive(ur)
+            .with_context(|| format!("line {}: failed to receive part", n + 1))?;
+        if decoder.is_complete() {
+            let ur_type = decoder.ur_type().unwrap().to_string();
+            let message = decoder
+                .message()
+                .context("failed to decode message")?
+                .unwrap()
+                .to_vec();
+            return Ok((ur_type, message));
+        }
+    }
+
+    bail!(
+        "the message is incomplete ({:.0}% received)",
+        decoder.estimated_percent_complete() * 100.0
+    )
+}
+
+fn read_input(file_name: Option<&PathBuf>) -> Result<Vec<u8>> {
+    match file_name {
+        Some(file_name) => fs::read(file_name).context("failed to read input"),
+        None => {
+            let mut buf = Vec::new();
+            io::stdin()
+                .lock()
+                .read_to_end(&mut buf)
+                .context("failed to read standard input")?;
+            Ok(buf)
+        }
+    }
+}
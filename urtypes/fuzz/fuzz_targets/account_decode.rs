@@ -0,0 +1,27 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+#![no_main]
+
+use foundation_arena::Arena;
+use foundation_urtypes::registry::Account;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mut buf = [0; 4096];
+    let b: Arena<_, 32> = Arena::new();
+    let a: Arena<_, 32> = Arena::new();
+    let Ok(account) = minicbor::decode_with::<_, Account>(data, &mut &a) else {
+        return;
+    };
+
+    // What is decoded must be encoded and decoded back to the same value.
+    let mut e = minicbor::Encoder::new(&mut buf[..]);
+    if e.encode(&account).is_err() {
+        return;
+    }
+    let len = 4096 - e.writer().len();
+
+    let decoded: Account = minicbor::decode_with(&buf[..len], &mut &b).unwrap();
+    assert_eq!(account, decoded);
+});
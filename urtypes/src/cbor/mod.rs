@@ -3,7 +3,9 @@
 
 //! Standard CBOR encodable types.
 
+mod slice;
 mod timestamp;
 pub mod uuid;
 
+pub use self::slice::{encoded_len, to_slice, ToSliceError};
 pub use self::timestamp::Timestamp;
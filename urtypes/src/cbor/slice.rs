@@ -0,0 +1,165 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use core::{convert::Infallible, fmt};
+
+use minicbor::{
+    encode::{self, Write},
+    Encode,
+};
+
+/// Calculate the exact length in bytes of the CBOR encoding of `value`.
+///
+/// The value is encoded into a writer that only counts the bytes, so no
+/// memory is allocated.
+///
+/// # Examples
+///
+/// ```rust
+/// use foundation_urtypes::{
+///     cbor::{encoded_len, to_slice, ToSliceError},
+///     registry::Seed,
+/// };
+///
+/// let seed = Seed {
+///     payload: &[0xAA; 16],
+///     creation_date: None,
+///     name: None,
+///     note: None,
+/// };
+/// assert_eq!(encoded_len(&seed).unwrap(), 18);
+///
+/// let mut buf = [0; 16];
+/// assert!(matches!(
+///     to_slice(&seed, &mut buf),
+///     Err(ToSliceError::NotEnoughSpace { needed: 18, .. })
+/// ));
+/// ```
+pub fn encoded_len<T>(value: &T) -> Result<usize, encode::Error<Infallible>>
+where
+    T: Encode<()>,
+{
+    let mut counter = Counter(0);
+    minicbor::encode(value, &mut counter)?;
+    Ok(counter.0)
+}
+
+/// Encode `value` as CBOR into `buf`.
+///
+/// Returns the number of bytes written into `buf`.
+///
+/// # Errors
+///
+/// If `buf` is too small [`ToSliceError::NotEnoughSpace`] is returned with
+/// the length needed to encode `value`, so the caller can pick a bigger
+/// buffer. The contents of `buf` are unspecified in that case.
+pub fn to_slice<T>(value: &T, buf: &mut [u8]) -> Result<usize, ToSliceError>
+where
+    T: Encode<()>,
+{
+    let mut writer = SliceWriter { buf, len: 0 };
+    minicbor::encode(value, &mut writer).map_err(ToSliceError::Encode)?;
+
+    if writer.len > writer.buf.len() {
+        return Err(ToSliceError::NotEnoughSpace {
+            needed: writer.len,
+            available: writer.buf.len(),
+        });
+    }
+
+    Ok(writer.len)
+}
+
+/// Errors that can happen when encoding to a slice.
+#[derive(Debug)]
+pub enum ToSliceError {
+    /// The buffer is too small.
+    NotEnoughSpace {
+        /// Length of the CBOR encoding.
+        needed: usize,
+        /// Length of the buffer.
+        available: usize,
+    },
+    /// The value failed to encode.
+    Encode(encode::Error<Infallible>),
+}
+
+impl fmt::Display for ToSliceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToSliceError::NotEnoughSpace { needed, available } => write!(
+                f,
+                "not enough space to encode, needed {needed} but only {available} bytes available"
+            ),
+            ToSliceError::Encode(e) => write!(f, "failed to encode: {e}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ToSliceError {}
+
+struct Counter(usize);
+
+impl Write for Counter {
+    type Error = Infallible;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0 += buf.len();
+        Ok(())
+    }
+}
+
+/// Writes into a slice, counting the bytes that don't fit instead of
+/// failing so the needed length is known after a single pass.
+struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Write for SliceWriter<'a> {
+    type Error = Infallible;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        let start = self.len;
+        self.len += buf.len();
+
+        if self.len <= self.buf.len() {
+            self.buf[start..self.len].copy_from_slice(buf);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::Seed;
+
+    #[test]
+    fn test_to_slice() {
+        let seed = Seed {
+            payload: &[0x55; 32],
+            creation_date: None,
+            name: Some("Wallet"),
+            note: None,
+        };
+        let expected = minicbor::to_vec(&seed).unwrap();
+
+        assert_eq!(encoded_len(&seed).unwrap(), expected.len());
+
+        let mut buf = [0; 64];
+        let len = to_slice(&seed, &mut buf).unwrap();
+        assert_eq!(&buf[..len], expected);
+
+        let mut buf = [0; 16];
+        match to_slice(&seed, &mut buf) {
+            Err(ToSliceError::NotEnoughSpace { needed, available }) => {
+                assert_eq!(needed, expected.len());
+                assert_eq!(available, 16);
+            }
+            r => panic!("unexpected result: {r:?}"),
+        }
+    }
+}
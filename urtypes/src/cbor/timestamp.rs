@@ -11,7 +11,7 @@ use minicbor::{
 /// Epoch-Based Date/Time.
 ///
 /// See [RFC 8948](https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4.2).
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum Timestamp {
     /// Integer timestamp.
     Int(Int),
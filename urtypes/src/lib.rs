@@ -9,6 +9,9 @@ extern crate core;
 
 pub mod cbor;
 pub mod passport;
+pub mod prelude;
 pub mod registry;
 pub mod supply_chain_validation;
 pub mod value;
+
+pub use self::value::dispatch;
@@ -10,8 +10,43 @@
 //! passport-model-founders-edition = 1
 //! passport-model-batch2 = 2
 //! ```
+//!
+//! ## CDDL for Diagnostics Report
+//!
+//! ```cddl
+//! passport-diagnostics = {
+//!     passport-model: passport-model,
+//!     firmware-version: [major: uint, minor: uint, patch: uint],
+//!     battery-cycles: uint .size 4,
+//!     storage-health: [erase-cycles: uint .size 4, bad-blocks: uint .size 2, free-percent: uint .le 100],
+//!     last-errors: [0*8 [code: uint .size 2, uptime: uint .size 4]], ; oldest first.
+//! }
+//!
+//! passport-model = 1
+//! firmware-version = 2
+//! battery-cycles = 3
+//! storage-health = 4
+//! last-errors = 5
+//! ```
+//!
+//! ## CDDL for Firmware Chunk
+//!
+//! ```cddl
+//! passport-firmware-chunk = {
+//!     index: uint .size 4,
+//!     total: uint .size 4,
+//!     image-hash: bytes .size 32, ; SHA-256 of the whole image.
+//!     payload: bytes,
+//! }
+//!
+//! index = 1
+//! total = 2
+//! image-hash = 3
+//! payload = 4
+//! ```
 
-use minicbor::data::Tag;
+use bitcoin_hashes::{sha256, HashEngine};
+use minicbor::data::{Tag, Type};
 use minicbor::decode::Error;
 use minicbor::encode::Write;
 use minicbor::{Decode, Decoder, Encode, Encoder};
@@ -74,3 +109,578 @@ pub struct InvalidModelError {
     /// Erroneous model number.
     pub number: u32,
 }
+
+/// Maximum number of errors in a [`DiagnosticsReport`].
+pub const MAX_ERRORS: usize = 8;
+
+/// Diagnostics report of a Passport, exported to support as a
+/// `x-passport-diagnostics` UR.
+///
+/// # Redaction
+///
+/// The report can't contain key material by construction:
+///
+/// - Every field is a small integer or an enumeration, there are no byte or
+///   text strings to copy secrets into.
+/// - The number of errors is bounded by [`MAX_ERRORS`].
+/// - Decoding rejects unknown map entries, so a report that carries more
+///   than these fields is invalid.
+///
+/// New fields must keep these rules.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiagnosticsReport {
+    /// Passport model.
+    pub model: Model,
+    /// Firmware version.
+    pub firmware_version: FirmwareVersion,
+    /// Number of charge cycles of the battery.
+    pub battery_cycles: u32,
+    /// Health of the flash storage.
+    pub storage: StorageHealth,
+    /// Last errors, from the oldest to the newest.
+    pub last_errors: heapless::Vec<ErrorRecord, MAX_ERRORS>,
+}
+
+impl DiagnosticsReport {
+    /// Record an error, dropping the oldest one if the report is full.
+    pub fn push_error(&mut self, error: ErrorRecord) {
+        if self.last_errors.is_full() {
+            self.last_errors.remove(0);
+        }
+
+        // Can't fail, there's room for at least one error.
+        self.last_errors.push(error).ok();
+    }
+}
+
+impl<'b, C> Decode<'b, C> for DiagnosticsReport {
+    fn decode(d: &mut Decoder<'b>, ctx: &mut C) -> Result<Self, Error> {
+        let mut model = None;
+        let mut firmware_version = None;
+        let mut battery_cycles = None;
+        let mut storage = None;
+        let mut last_errors = heapless::Vec::new();
+
+        let mut len = d.map()?;
+        loop {
+            match len {
+                Some(0) => break,
+                Some(n) => len = Some(n - 1),
+                None => {
+                    if d.datatype()? == Type::Break {
+                        break;
+                    }
+                }
+            }
+
+            match d.u32()? {
+                1 => model = Some(Model::decode(d, ctx)?),
+                2 => firmware_version = Some(FirmwareVersion::decode(d, ctx)?),
+                3 => battery_cycles = Some(d.u32()?),
+                4 => storage = Some(StorageHealth::decode(d, ctx)?),
+                5 => {
+                    let len = decode_array_len(d)?;
+                    if len > MAX_ERRORS {
+                        return Err(Error::message("too many errors"));
+                    }
+
+                    last_errors.clear();
+                    for _ in 0..len {
+                        // Can't fail, the length was checked.
+                        last_errors.push(ErrorRecord::decode(d, ctx)?).ok();
+                    }
+                }
+                _ => return Err(Error::message("unknown map entry")),
+            }
+        }
+
+        Ok(Self {
+            model: model.ok_or_else(|| Error::message("passport-model is missing"))?,
+            firmware_version: firmware_version
+                .ok_or_else(|| Error::message("firmware-version is missing"))?,
+            battery_cycles: battery_cycles
+                .ok_or_else(|| Error::message("battery-cycles is missing"))?,
+            storage: storage.ok_or_else(|| Error::message("storage-health is missing"))?,
+            last_errors,
+        })
+    }
+}
+
+impl<C> Encode<C> for DiagnosticsReport {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.map(5)?;
+
+        e.u8(1)?;
+        self.model.encode(e, ctx)?;
+        e.u8(2)?;
+        self.firmware_version.encode(e, ctx)?;
+        e.u8(3)?.u32(self.battery_cycles)?;
+        e.u8(4)?;
+        self.storage.encode(e, ctx)?;
+
+        e.u8(5)?.array(self.last_errors.len() as u64)?;
+        for error in &self.last_errors {
+            error.encode(e, ctx)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Firmware version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FirmwareVersion {
+    /// Major version.
+    pub major: u16,
+    /// Minor version.
+    pub minor: u16,
+    /// Patch version.
+    pub patch: u16,
+}
+
+impl<'b, C> Decode<'b, C> for FirmwareVersion {
+    fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, Error> {
+        if decode_array_len(d)? != 3 {
+            return Err(Error::message("invalid firmware-version length"));
+        }
+
+        Ok(Self {
+            major: d.u16()?,
+            minor: d.u16()?,
+            patch: d.u16()?,
+        })
+    }
+}
+
+impl<C> Encode<C> for FirmwareVersion {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(3)?
+            .u16(self.major)?
+            .u16(self.minor)?
+            .u16(self.patch)?;
+        Ok(())
+    }
+}
+
+/// Health of the flash storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StorageHealth {
+    /// Number of erase cycles of the most used block.
+    pub erase_cycles: u32,
+    /// Number of bad blocks.
+    pub bad_blocks: u16,
+    /// Free space, in percent.
+    pub free_percent: u8,
+}
+
+impl<'b, C> Decode<'b, C> for StorageHealth {
+    fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, Error> {
+        if decode_array_len(d)? != 3 {
+            return Err(Error::message("invalid storage-health length"));
+        }
+
+        let erase_cycles = d.u32()?;
+        let bad_blocks = d.u16()?;
+        let free_percent = d.u8()?;
+        if free_percent > 100 {
+            return Err(Error::message("free-percent out of range"));
+        }
+
+        Ok(Self {
+            erase_cycles,
+            bad_blocks,
+            free_percent,
+        })
+    }
+}
+
+impl<C> Encode<C> for StorageHealth {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(3)?
+            .u32(self.erase_cycles)?
+            .u16(self.bad_blocks)?
+            .u8(self.free_percent)?;
+        Ok(())
+    }
+}
+
+/// An error that happened on the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorRecord {
+    /// Error code.
+    pub code: u16,
+    /// Seconds since boot when the error happened.
+    pub uptime: u32,
+}
+
+impl<'b, C> Decode<'b, C> for ErrorRecord {
+    fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, Error> {
+        if decode_array_len(d)? != 2 {
+            return Err(Error::message("invalid error record length"));
+        }
+
+        Ok(Self {
+            code: d.u16()?,
+            uptime: d.u32()?,
+        })
+    }
+}
+
+impl<C> Encode<C> for ErrorRecord {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(2)?.u16(self.code)?.u32(self.uptime)?;
+        Ok(())
+    }
+}
+
+/// A chunk of a firmware image, transmitted as a `x-passport-firmware-chunk`
+/// UR to update a Passport without a cable.
+///
+/// The chunks of an image are assembled with a [`FirmwareAssembler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FirmwareChunk<'a> {
+    /// Index of the chunk, starting from zero.
+    pub index: u32,
+    /// Total number of chunks of the image.
+    pub total: u32,
+    /// SHA-256 of the whole image.
+    pub image_hash: [u8; 32],
+    /// Bytes of the image in this chunk.
+    pub payload: &'a [u8],
+}
+
+impl<'b, C> Decode<'b, C> for FirmwareChunk<'b> {
+    fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, Error> {
+        let mut index = None;
+        let mut total = None;
+        let mut image_hash = None;
+        let mut payload = None;
+
+        let mut len = d.map()?;
+        loop {
+            match len {
+                Some(0) => break,
+                Some(n) => len = Some(n - 1),
+                None => {
+                    if d.datatype()? == Type::Break {
+                        break;
+                    }
+                }
+            }
+
+            match d.u32()? {
+                1 => index = Some(d.u32()?),
+                2 => total = Some(d.u32()?),
+                3 => {
+                    image_hash = Some(
+                        <[u8; 32]>::try_from(d.bytes()?)
+                            .map_err(|_| Error::message("invalid image-hash length"))?,
+                    )
+                }
+                4 => payload = Some(d.bytes()?),
+                _ => return Err(Error::message("unknown map entry")),
+            }
+        }
+
+        let index = index.ok_or_else(|| Error::message("index is missing"))?;
+        let total = total.ok_or_else(|| Error::message("total is missing"))?;
+        if index >= total {
+            return Err(Error::message("index out of range"));
+        }
+
+        Ok(Self {
+            index,
+            total,
+            image_hash: image_hash.ok_or_else(|| Error::message("image-hash is missing"))?,
+            payload: payload.ok_or_else(|| Error::message("payload is missing"))?,
+        })
+    }
+}
+
+impl<'a, C> Encode<C> for FirmwareChunk<'a> {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.map(4)?
+            .u8(1)?
+            .u32(self.index)?
+            .u8(2)?
+            .u32(self.total)?
+            .u8(3)?
+            .bytes(&self.image_hash)?
+            .u8(4)?
+            .bytes(self.payload)?;
+        Ok(())
+    }
+}
+
+/// Assembles the [`FirmwareChunk`]s of an image, hashing them as they are
+/// received.
+///
+/// The chunks must be pushed in order. The assembler doesn't store the
+/// image, the payload of each accepted chunk has to be written somewhere
+/// else by the caller, for example to the external flash.
+///
+/// # Examples
+///
+/// ```rust
+/// use foundation_urtypes::passport::{FirmwareAssembler, FirmwareChunk};
+///
+/// # let chunks: [FirmwareChunk; 0] = [];
+/// let mut assembler = FirmwareAssembler::new();
+/// for chunk in chunks {
+///     if assembler.push(&chunk)? {
+///         // Write chunk.payload to the flash.
+///     }
+/// }
+/// # Ok::<(), foundation_urtypes::passport::AssembleError>(())
+/// ```
+#[derive(Clone, Default)]
+pub struct FirmwareAssembler {
+    image_hash: Option<[u8; 32]>,
+    total: u32,
+    received: u32,
+    engine: sha256::HashEngine,
+    verified: bool,
+}
+
+impl FirmwareAssembler {
+    /// Construct a new [`FirmwareAssembler`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push a chunk.
+    ///
+    /// Returns `true` if the chunk was accepted and its payload must be
+    /// written, or `false` if the chunk was already received, which is
+    /// common when scanning an animated QR code.
+    ///
+    /// When the last chunk is pushed the hash of the image is verified.
+    pub fn push(&mut self, chunk: &FirmwareChunk<'_>) -> Result<bool, AssembleError> {
+        match self.image_hash {
+            None => {
+                self.image_hash = Some(chunk.image_hash);
+                self.total = chunk.total;
+            }
+            Some(image_hash) => {
+                if image_hash != chunk.image_hash || self.total != chunk.total {
+                    return Err(AssembleError::MismatchedImage);
+                }
+            }
+        }
+
+        if chunk.index < self.received {
+            return Ok(false);
+        }
+
+        if chunk.index > self.received {
+            return Err(AssembleError::OutOfOrder {
+                expected: self.received,
+            });
+        }
+
+        self.engine.input(chunk.payload);
+        self.received += 1;
+
+        if self.received == self.total {
+            let hash = sha256::Hash::from_engine(self.engine.clone());
+            if hash.to_byte_array() != chunk.image_hash {
+                return Err(AssembleError::HashMismatch);
+            }
+            self.verified = true;
+        }
+
+        Ok(true)
+    }
+
+    /// Returns `true` if all the chunks were received and the hash of the
+    /// image is valid.
+    pub fn is_complete(&self) -> bool {
+        self.verified
+    }
+
+    /// Returns the number of chunks received.
+    pub fn received(&self) -> u32 {
+        self.received
+    }
+
+    /// Returns the total number of chunks, or `None` if no chunk was
+    /// received yet.
+    pub fn total(&self) -> Option<u32> {
+        self.image_hash.map(|_| self.total)
+    }
+
+    /// Returns the SHA-256 of the image being assembled.
+    pub fn image_hash(&self) -> Option<&[u8; 32]> {
+        self.image_hash.as_ref()
+    }
+
+    /// Start assembling a new image.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Errors that can happen when assembling a firmware image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssembleError {
+    /// The chunk belongs to another image.
+    MismatchedImage,
+    /// A chunk was skipped.
+    OutOfOrder {
+        /// Index of the next chunk.
+        expected: u32,
+    },
+    /// The hash of the assembled image doesn't match.
+    HashMismatch,
+}
+
+impl core::fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MismatchedImage => write!(f, "the chunk belongs to another image"),
+            Self::OutOfOrder { expected } => write!(f, "out of order chunk, expected {expected}"),
+            Self::HashMismatch => write!(f, "the image hash doesn't match"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AssembleError {}
+
+fn decode_array_len(d: &mut Decoder<'_>) -> Result<usize, Error> {
+    d.array()?
+        .and_then(|len| usize::try_from(len).ok())
+        .ok_or_else(|| Error::message("indefinite-length arrays are not supported"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> DiagnosticsReport {
+        DiagnosticsReport {
+            model: Model::Batch2,
+            firmware_version: FirmwareVersion {
+                major: 2,
+                minor: 3,
+                patch: 1,
+            },
+            battery_cycles: 120,
+            storage: StorageHealth {
+                erase_cycles: 4000,
+                bad_blocks: 2,
+                free_percent: 85,
+            },
+            last_errors: heapless::Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_diagnostics_report() {
+        let mut report = report();
+        for code in 0..10 {
+            report.push_error(ErrorRecord {
+                code,
+                uptime: u32::from(code) * 60,
+            });
+        }
+        assert_eq!(report.last_errors.len(), MAX_ERRORS);
+        assert_eq!(report.last_errors[0].code, 2);
+
+        let encoded = minicbor::to_vec(&report).unwrap();
+        let decoded: DiagnosticsReport = minicbor::decode(&encoded).unwrap();
+        assert_eq!(decoded, report);
+    }
+
+    #[test]
+    fn test_diagnostics_report_rejects_extra_data() {
+        let mut encoded = minicbor::to_vec(report()).unwrap();
+        // Bump the map length and append a byte string entry.
+        encoded[0] += 1;
+        encoded.extend_from_slice(&[0x06, 0x44, 0xde, 0xad, 0xbe, 0xef]);
+        assert!(minicbor::decode::<DiagnosticsReport>(&encoded).is_err());
+    }
+
+    fn split(image: &[u8], image_hash: [u8; 32]) -> impl Iterator<Item = FirmwareChunk<'_>> {
+        let total = image.chunks(100).count() as u32;
+        image
+            .chunks(100)
+            .enumerate()
+            .map(move |(index, payload)| FirmwareChunk {
+                index: index as u32,
+                total,
+                image_hash,
+                payload,
+            })
+    }
+
+    #[test]
+    fn test_roundtrip_firmware_chunk() {
+        let chunk = FirmwareChunk {
+            index: 1,
+            total: 3,
+            image_hash: [0xaa; 32],
+            payload: &[1, 2, 3],
+        };
+
+        let encoded = minicbor::to_vec(chunk).unwrap();
+        let decoded: FirmwareChunk = minicbor::decode(&encoded).unwrap();
+        assert_eq!(decoded, chunk);
+
+        let chunk = FirmwareChunk { index: 3, ..chunk };
+        let encoded = minicbor::to_vec(chunk).unwrap();
+        assert!(minicbor::decode::<FirmwareChunk>(&encoded).is_err());
+    }
+
+    #[test]
+    fn test_firmware_assembler() {
+        let image = [0x5a; 250];
+        let image_hash = sha256::Hash::hash(&image).to_byte_array();
+        let chunks: heapless::Vec<FirmwareChunk, 3> = split(&image, image_hash).collect();
+
+        let mut assembler = FirmwareAssembler::new();
+        assert_eq!(
+            assembler.push(&chunks[1]),
+            Err(AssembleError::OutOfOrder { expected: 0 })
+        );
+        assert_eq!(assembler.push(&chunks[0]), Ok(true));
+        assert_eq!(assembler.push(&chunks[0]), Ok(false));
+        assert_eq!(assembler.push(&chunks[1]), Ok(true));
+        assert!(!assembler.is_complete());
+        assert_eq!(assembler.push(&chunks[2]), Ok(true));
+        assert!(assembler.is_complete());
+        assert_eq!(assembler.received(), 3);
+
+        let mut assembler = FirmwareAssembler::new();
+        for chunk in split(&image, [0; 32]).take(2) {
+            assert_eq!(assembler.push(&chunk), Ok(true));
+        }
+        assert_eq!(
+            assembler.push(&chunks[2]),
+            Err(AssembleError::MismatchedImage)
+        );
+        let last = split(&image, [0; 32]).last().unwrap();
+        assert_eq!(assembler.push(&last), Err(AssembleError::HashMismatch));
+        assert!(!assembler.is_complete());
+    }
+}
@@ -0,0 +1,30 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Commonly used types.
+//!
+//! Glob import this module to get the registry types most wallets need
+//! along with the [`Value`] dispatcher:
+//!
+//! ```
+//! use foundation_urtypes::prelude::*;
+//!
+//! // As a UR: ur:bytes/gdaebycpeofygoiyktlonlpkrksfutwyzmwmfyeozs
+//! const PAYLOAD: &[u8] = &[
+//!     0x50, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD,
+//!     0xEE, 0xFF,
+//! ];
+//!
+//! let value = Value::from_ur("bytes", PAYLOAD).unwrap();
+//! assert_eq!(value.ur_type(), "bytes");
+//! ```
+
+#[cfg(feature = "alloc")]
+pub use crate::registry::{DerivedKey, HDKey, Keypath};
+pub use crate::{
+    registry::{
+        Address, CoinInfo, CoinType, DerivedKeyRef, ECKey, HDKeyRef, KeypathRef, MasterKey,
+        PassportRequest, PassportResponse, Seed,
+    },
+    value::{dispatch, Error as ValueError, Value},
+};
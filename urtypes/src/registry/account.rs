@@ -0,0 +1,206 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! # Account (BCR-2020-015).
+//!
+//! ## CDDL.
+//!
+//! ```cddl
+//! crypto-account = {
+//!     master-fingerprint: uint32,
+//!     output-descriptors: [+ crypto-output]
+//! }
+//!
+//! master-fingerprint = 1
+//! output-descriptors = 2
+//! ```
+
+use minicbor::{
+    data::{Tag, Type},
+    decode::Error,
+    encode::Write,
+    Decode, Decoder, Encode, Encoder,
+};
+
+use crate::registry::{Terminal, TerminalContext};
+
+/// Maximum number of output descriptors in an [`Account`].
+pub const MAX_OUTPUT_DESCRIPTORS: usize = 8;
+
+/// Output descriptors of an account, one for each script type.
+pub type OutputDescriptors<'a, 'b> = heapless::Vec<Terminal<'a, 'b>, MAX_OUTPUT_DESCRIPTORS>;
+
+/// An account, the output descriptors derived from a master key.
+#[doc(alias("crypto-account"))]
+#[derive(Debug, PartialEq)]
+pub struct Account<'a, 'b> {
+    /// Fingerprint of the master key.
+    pub master_fingerprint: u32,
+    /// The output descriptors, at least one is required.
+    pub output_descriptors: OutputDescriptors<'a, 'b>,
+}
+
+impl<'a, 'b> Account<'a, 'b> {
+    /// The CBOR tag used when [`Account`] is embedded in other CBOR types.
+    pub const TAG: Tag = Tag::new(311);
+}
+
+impl<'a, 'b, const N: usize> Decode<'b, &'a TerminalContext<'a, 'b, N>> for Account<'a, 'b> {
+    fn decode(
+        d: &mut Decoder<'b>,
+        ctx: &mut &'a TerminalContext<'a, 'b, N>,
+    ) -> Result<Self, Error> {
+        let mut master_fingerprint = None;
+        let mut output_descriptors = None;
+
+        let mut len = d.map()?;
+        loop {
+            match len {
+                Some(0) => break,
+                Some(n) => len = Some(n - 1),
+                None => {
+                    if d.datatype()? == Type::Break {
+                        break;
+                    }
+                }
+            }
+
+            match d.u32()? {
+                1 => master_fingerprint = Some(d.u32()?),
+                2 => output_descriptors = Some(decode_output_descriptors(d, ctx)?),
+                _ => return Err(Error::message("unknown map entry")),
+            }
+        }
+
+        Ok(Self {
+            master_fingerprint: master_fingerprint
+                .ok_or_else(|| Error::message("master-fingerprint is not present"))?,
+            output_descriptors: output_descriptors
+                .ok_or_else(|| Error::message("output-descriptors is not present"))?,
+        })
+    }
+}
+
+fn decode_output_descriptors<'a, 'b, const N: usize>(
+    d: &mut Decoder<'b>,
+    ctx: &mut &'a TerminalContext<'a, 'b, N>,
+) -> Result<OutputDescriptors<'a, 'b>, Error> {
+    let mut output_descriptors = OutputDescriptors::new();
+
+    let mut len = d.array()?;
+    loop {
+        match len {
+            Some(0) => break,
+            Some(n) => len = Some(n - 1),
+            None => {
+                if d.datatype()? == Type::Break {
+                    break;
+                }
+            }
+        }
+
+        if d.tag()? != Terminal::TAG {
+            return Err(Error::message("invalid tag for output descriptor"));
+        }
+
+        output_descriptors
+            .push(Terminal::decode(d, ctx)?)
+            .map_err(|_| Error::message("too many output descriptors"))?;
+    }
+
+    if output_descriptors.is_empty() {
+        return Err(Error::message("empty output descriptors array"));
+    }
+
+    Ok(output_descriptors)
+}
+
+impl<'a, 'b, C> Encode<C> for Account<'a, 'b> {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        debug_assert!(!self.output_descriptors.is_empty());
+
+        e.map(2)?;
+        e.u8(1)?.u32(self.master_fingerprint)?;
+        e.u8(2)?
+            .array(u64::try_from(self.output_descriptors.len()).unwrap())?;
+        for output_descriptor in &self.output_descriptors {
+            e.tag(Terminal::TAG)?;
+            output_descriptor.encode(e, ctx)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{ECKey, Key};
+
+    const KEY: &[u8] = &[
+        0x02, 0xc6, 0x04, 0x7f, 0x94, 0x41, 0xed, 0x7d, 0x6d, 0x30, 0x45, 0x40, 0x6e, 0x95, 0xc0,
+        0x7c, 0xd8, 0x5c, 0x77, 0x8e, 0x4b, 0x8c, 0xef, 0x3c, 0xa7, 0xab, 0xac, 0x09, 0xb9, 0x5c,
+        0x70, 0x9e, 0xe5,
+    ];
+
+    fn key() -> Key<'static> {
+        Key::ECKey(ECKey {
+            curve: ECKey::SECP256K1,
+            is_private: false,
+            data: KEY,
+        })
+    }
+
+    #[test]
+    fn test_account() {
+        const EXPECTED: &[u8] = &[
+            0xa2, 0x01, 0x1a, 0x37, 0xb5, 0xee, 0xd4, 0x02, 0x82, 0xd9, 0x01, 0x34, 0xd9, 0x01,
+            0x93, 0xd9, 0x01, 0x32, 0xa1, 0x03, 0x58, 0x21, 0x02, 0xc6, 0x04, 0x7f, 0x94, 0x41,
+            0xed, 0x7d, 0x6d, 0x30, 0x45, 0x40, 0x6e, 0x95, 0xc0, 0x7c, 0xd8, 0x5c, 0x77, 0x8e,
+            0x4b, 0x8c, 0xef, 0x3c, 0xa7, 0xab, 0xac, 0x09, 0xb9, 0x5c, 0x70, 0x9e, 0xe5, 0xd9,
+            0x01, 0x34, 0xd9, 0x01, 0x94, 0xd9, 0x01, 0x32, 0xa1, 0x03, 0x58, 0x21, 0x02, 0xc6,
+            0x04, 0x7f, 0x94, 0x41, 0xed, 0x7d, 0x6d, 0x30, 0x45, 0x40, 0x6e, 0x95, 0xc0, 0x7c,
+            0xd8, 0x5c, 0x77, 0x8e, 0x4b, 0x8c, 0xef, 0x3c, 0xa7, 0xab, 0xac, 0x09, 0xb9, 0x5c,
+            0x70, 0x9e, 0xe5,
+        ];
+
+        let a: TerminalContext<1> = TerminalContext::new();
+        let mut output_descriptors = OutputDescriptors::new();
+        output_descriptors
+            .push(Terminal::PublicKeyHash(key()))
+            .unwrap();
+        output_descriptors
+            .push(Terminal::WitnessPublicKeyHash(key()))
+            .unwrap();
+        let account = Account {
+            master_fingerprint: 0x37b5eed4,
+            output_descriptors,
+        };
+
+        let cbor = minicbor::to_vec(&account).unwrap();
+        assert_eq!(cbor, EXPECTED);
+
+        let decoded: Account = minicbor::decode_with(EXPECTED, &mut &a).unwrap();
+        assert_eq!(account, decoded);
+    }
+
+    #[test]
+    fn test_account_errors() {
+        let a: TerminalContext<1> = TerminalContext::new();
+
+        // No output descriptors.
+        const EMPTY: &[u8] = &[0xa2, 0x01, 0x1a, 0x37, 0xb5, 0xee, 0xd4, 0x02, 0x80];
+        assert!(minicbor::decode_with::<_, Account>(EMPTY, &mut &a).is_err());
+
+        // Missing the crypto-output tag.
+        const UNTAGGED: &[u8] = &[
+            0xa2, 0x01, 0x1a, 0x37, 0xb5, 0xee, 0xd4, 0x02, 0x81, 0xd9, 0x01, 0x93, 0xd9, 0x01,
+            0x32, 0xa1, 0x03, 0x41, 0x02,
+        ];
+        assert!(minicbor::decode_with::<_, Account>(UNTAGGED, &mut &a).is_err());
+    }
+}
@@ -105,7 +105,7 @@ mod tests {
                 data: &crypto_eckey_vector.data,
             };
 
-            let cbor = minicbor::to_vec(&crypto_eckey).unwrap();
+            let cbor = minicbor::to_vec(crypto_eckey).unwrap();
             assert_eq!(vector.as_cbor, cbor);
         }
     }
@@ -192,8 +192,12 @@ impl<'b, C> Decode<'b, C> for MasterKey {
                 3 => {
                     let mut data = [0; 32];
 
+                    // Private keys are prefixed with a zero byte.
                     let bytes: [u8; 33] = DecodeBytes::decode_bytes(d, ctx)?;
-                    data.copy_from_slice(&bytes[..32]);
+                    if bytes[0] != 0 {
+                        return Err(Error::message("invalid key-data prefix"));
+                    }
+                    data.copy_from_slice(&bytes[1..]);
                     key_data = Some(data)
                 }
                 4 => chain_code = Some(DecodeBytes::decode_bytes(d, ctx)?),
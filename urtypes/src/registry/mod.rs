@@ -3,6 +3,7 @@
 
 //! Uniform Resources registry.
 
+mod account;
 mod address;
 mod coininfo;
 mod eckey;
@@ -10,8 +11,10 @@ mod hdkey;
 mod keypath;
 mod output_descriptor;
 mod passport;
+mod psbt;
 mod seed;
 
+pub use self::account::*;
 pub use self::address::*;
 pub use self::coininfo::*;
 pub use self::eckey::*;
@@ -19,4 +22,5 @@ pub use self::hdkey::*;
 pub use self::keypath::*;
 pub use self::output_descriptor::*;
 pub use self::passport::*;
+pub use self::psbt::*;
 pub use self::seed::*;
@@ -55,6 +55,10 @@ pub enum Terminal<'a, 'b> {
 }
 
 impl<'a, 'b> Terminal<'a, 'b> {
+    /// The CBOR tag used when an output descriptor (`crypto-output`) is
+    /// embedded in other CBOR types.
+    pub const TAG: Tag = Tag::new(308);
+
     const TAG_SCRIPT_HASH: Tag = Tag::new(400);
     const TAG_WITNESS_SCRIPT_HASH: Tag = Tag::new(401);
     const TAG_PUBLIC_KEY: Tag = Tag::new(402);
@@ -384,7 +388,7 @@ pub mod tests {
         let cbor = minicbor::to_vec(&descriptor).unwrap();
         assert_eq!(cbor, EXPECTED);
 
-        let decoded: Terminal = minicbor::decode_with(&EXPECTED, &mut &a).unwrap();
+        let decoded: Terminal = minicbor::decode_with(EXPECTED, &mut &a).unwrap();
 
         assert_eq!(descriptor, decoded);
     }
@@ -418,7 +422,7 @@ pub mod tests {
         let cbor = minicbor::to_vec(&descriptor).unwrap();
         assert_eq!(cbor, EXPECTED);
 
-        let decoded: Terminal = minicbor::decode_with(&EXPECTED, &mut &a).unwrap();
+        let decoded: Terminal = minicbor::decode_with(EXPECTED, &mut &a).unwrap();
 
         assert_eq!(descriptor, decoded);
     }
@@ -463,7 +467,7 @@ pub mod tests {
         let cbor = minicbor::to_vec(&descriptor).unwrap();
         assert_eq!(cbor, EXPECTED);
 
-        let decoded: Terminal = minicbor::decode_with(&EXPECTED, &mut &a).unwrap();
+        let decoded: Terminal = minicbor::decode_with(EXPECTED, &mut &a).unwrap();
         assert_eq!(descriptor, decoded);
     }
 }
@@ -295,7 +295,7 @@ mod tests {
         };
 
         let encoded = &minicbor::to_vec(&request).unwrap();
-        let decoded: PassportRequest = minicbor::decode(&encoded).unwrap();
+        let decoded: PassportRequest = minicbor::decode(encoded).unwrap();
 
         assert_eq!(decoded, request);
     }
@@ -328,7 +328,7 @@ mod tests {
         };
 
         let encoded = &minicbor::to_vec(&response).unwrap();
-        let decoded: PassportResponse = minicbor::decode(&encoded).unwrap();
+        let decoded: PassportResponse = minicbor::decode(encoded).unwrap();
 
         assert_eq!(decoded, response);
     }
@@ -336,7 +336,7 @@ mod tests {
     #[test]
     fn test_request_decode() {
         const TEST_VECTOR: &str = "a201d8255083816f6064ff4046b93a85687f8f608202d902c6a30178403338633663303561633639613166623737626366333736333330383835326364336530383066653165343630346361613831623534383236653264613062643202788037393035306564663562386636343937663936626661633031333363396365663561303764613363343835613432373466646666396433616637346632393833636566386432303337663164626636613435356431356530666236346162313665333664643336353062363533323265333239303138313639633631356636610378603045022079050ec39f5bc28f64c297c3b96bc3bac380133cc29cc3af5a07c39a3c485a4274c3bdc3bfc29d3ac3b74f29c283022100c38ec3b8c392037f1dc2bf6a455d15c3a0c3bb64c2ab16c3a36dc393650b65322e32c2901816c29c615f6a";
-        let mut cbor = vec![0; TEST_VECTOR.len() / 2];
+        let mut cbor = [0; TEST_VECTOR.len() / 2];
         hex_decode(TEST_VECTOR.as_bytes(), &mut cbor).unwrap();
         minicbor::decode::<'_, PassportRequest>(&cbor).unwrap();
     }
@@ -0,0 +1,90 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! # PSBT.
+//!
+//! ## CDDL.
+//!
+//! ```cddl
+//! crypto-psbt = bytes
+//! ```
+//!
+//! The same encoding is used for the `psbt` and the deprecated
+//! `crypto-psbt` UR types.
+
+use core::ops::Deref;
+
+use minicbor::{data::Tag, decode::Error, encode::Write, Decode, Decoder, Encode, Encoder};
+
+/// Partially Signed Bitcoin Transaction (non owned, zero copy).
+///
+/// The PSBT is kept serialized, it is not parsed.
+#[doc(alias("crypto-psbt"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Psbt<'a>(pub &'a [u8]);
+
+impl<'a> Psbt<'a> {
+    /// The CBOR tag used when [`Psbt`] is embedded in other CBOR types.
+    pub const TAG: Tag = Tag::new(310);
+
+    /// The serialized PSBT.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl<'a> Deref for Psbt<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<'a> AsRef<[u8]> for Psbt<'a> {
+    fn as_ref(&self) -> &[u8] {
+        self.0
+    }
+}
+
+impl<'a> From<&'a [u8]> for Psbt<'a> {
+    fn from(psbt: &'a [u8]) -> Self {
+        Self(psbt)
+    }
+}
+
+impl<'b, C> Decode<'b, C> for Psbt<'b> {
+    fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, Error> {
+        d.bytes().map(Self)
+    }
+}
+
+impl<'a, C> Encode<C> for Psbt<'a> {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.bytes(self.0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        const PSBT: &[u8] = &[0x70, 0x73, 0x62, 0x74, 0xff, 0x01, 0x00];
+        const EXPECTED: &[u8] = &[0x47, 0x70, 0x73, 0x62, 0x74, 0xff, 0x01, 0x00];
+
+        let cbor = minicbor::to_vec(Psbt(PSBT)).unwrap();
+        assert_eq!(cbor, EXPECTED);
+
+        let decoded: Psbt = minicbor::decode(EXPECTED).unwrap();
+        assert_eq!(decoded.as_bytes(), PSBT);
+
+        assert!(minicbor::decode::<Psbt>(&[0x64, 0x70, 0x73, 0x62, 0x74]).is_err());
+    }
+}
@@ -7,7 +7,7 @@ use crate::cbor::Timestamp;
 
 /// Cryptographic Seed.
 #[doc(alias("seed"))]
-#[derive(Debug, Decode, Encode)]
+#[derive(Debug, PartialEq, Decode, Encode)]
 pub struct Seed<'a> {
     /// Seed entropy.
     #[cbor(n(0), with = "payload")]
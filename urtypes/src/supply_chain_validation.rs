@@ -26,6 +26,26 @@
 //! scv-solution-word3 = 3
 //! scv-solution-word4 = 4
 //! ```
+//!
+//! ## CDDL for Supply Chain Validation Response.
+//!
+//! ```cddl
+//! scv-response = {
+//!     scv-response-id: text .size 64,         ; hex encoded string.
+//!     scv-response-signature: text .size 128, ; hex encoded string.
+//! }
+//!
+//! scv-response-id = 1
+//! scv-response-signature = 2
+//! ```
+//!
+//! ## Challenge-response
+//!
+//! With the `signing` feature the device checks that a [`Challenge`] was
+//! signed by Foundation and answers it by signing its ID with the device
+//! secret key, see [`respond`], and the factory tooling checks the
+//! [`Response`] with the device public key, see [`verify_response`]. Both
+//! signed messages are the SHA-256 of the ID.
 
 use core::str;
 
@@ -34,6 +54,13 @@ use minicbor::data::{Tag, Type};
 use minicbor::decode::Error;
 use minicbor::encode::Write;
 use minicbor::{Decode, Decoder, Encode, Encoder};
+#[cfg(feature = "signing")]
+use {
+    bitcoin_hashes::sha256,
+    foundation_ur::bytewords,
+    minicbor::encode::write::Cursor,
+    secp256k1::{ecdsa, Message, PublicKey, Secp256k1, SecretKey, Signing, Verification},
+};
 
 /// Supply Chain Validation challenge.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -47,70 +74,117 @@ pub struct Challenge {
 impl Challenge {
     /// Tag for embedding [`Challenge`] in other types.
     pub const TAG: Tag = Tag::new(710);
+
+    /// The UR type of a [`Challenge`].
+    pub const UR_TYPE: &'static str = "x-scv-challenge";
 }
 
 impl<'b, C> Decode<'b, C> for Challenge {
     fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, Error> {
-        let mut id = None;
-        let mut signature = None;
-
-        macro_rules! decode_inner {
-            () => {
-                match d.u32()? {
-                    1 => {
-                        let mut buf = [0; 32];
-                        hex_decode(d.str()?.as_bytes(), &mut buf)
-                            .map_err(|_| Error::message("invalid hex string (id)"))?;
-                        id = Some(buf);
-                    }
-                    2 => {
-                        let mut buf = [0; 64];
-                        hex_decode(d.str()?.as_bytes(), &mut buf)
-                            .map_err(|_| Error::message("invalid hex string (signature)"))?;
-                        signature = Some(buf);
-                    }
-                    3 => (),
-                    _ => return Err(Error::message("unknown map entry")),
-                }
-            };
-        }
+        let (id, signature) = decode_signed_id(d)?;
+        Ok(Self { id, signature })
+    }
+}
 
-        if let Some(len) = d.map()? {
-            for _ in 0..len {
-                decode_inner!();
-            }
-        } else {
-            while d.datatype()? != Type::Break {
-                decode_inner!();
-            }
-        }
+impl<C> Encode<C> for Challenge {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        encode_signed_id(e, &self.id, &self.signature)
+    }
+}
 
-        Ok(Self {
-            id: id.ok_or_else(|| Error::message("id is missing"))?,
-            signature: signature.ok_or_else(|| Error::message("signature is missing"))?,
-        })
+/// Supply Chain Validation response.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Response {
+    /// The ID of the challenge.
+    pub id: [u8; 32],
+    /// The signature of the challenge ID by the device, in compact format.
+    pub signature: [u8; 64],
+}
+
+impl Response {
+    /// The UR type of a [`Response`].
+    pub const UR_TYPE: &'static str = "x-scv-response";
+}
+
+impl<'b, C> Decode<'b, C> for Response {
+    fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, Error> {
+        let (id, signature) = decode_signed_id(d)?;
+        Ok(Self { id, signature })
     }
 }
 
-impl<C> Encode<C> for Challenge {
+impl<C> Encode<C> for Response {
     fn encode<W: Write>(
         &self,
         e: &mut Encoder<W>,
         _ctx: &mut C,
     ) -> Result<(), minicbor::encode::Error<W::Error>> {
-        let mut id = [0; 64];
-        let mut signature = [0; 128];
+        encode_signed_id(e, &self.id, &self.signature)
+    }
+}
 
-        // unreachable errors.
-        let id = hex_encode(&self.id, &mut id).unwrap();
-        let signature = hex_encode(&self.signature, &mut signature).unwrap();
+fn decode_signed_id(d: &mut Decoder<'_>) -> Result<([u8; 32], [u8; 64]), Error> {
+    let mut id = None;
+    let mut signature = None;
 
-        e.map(2)?;
-        e.u8(1)?.str(id)?;
-        e.u8(2)?.str(signature)?;
+    macro_rules! decode_inner {
+        () => {
+            match d.u32()? {
+                1 => {
+                    let mut buf = [0; 32];
+                    hex_decode(d.str()?.as_bytes(), &mut buf)
+                        .map_err(|_| Error::message("invalid hex string (id)"))?;
+                    id = Some(buf);
+                }
+                2 => {
+                    let mut buf = [0; 64];
+                    hex_decode(d.str()?.as_bytes(), &mut buf)
+                        .map_err(|_| Error::message("invalid hex string (signature)"))?;
+                    signature = Some(buf);
+                }
+                3 => (),
+                _ => return Err(Error::message("unknown map entry")),
+            }
+        };
+    }
 
-        Ok(())
+    if let Some(len) = d.map()? {
+        for _ in 0..len {
+            decode_inner!();
+        }
+    } else {
+        while d.datatype()? != Type::Break {
+            decode_inner!();
+        }
     }
+
+    Ok((
+        id.ok_or_else(|| Error::message("id is missing"))?,
+        signature.ok_or_else(|| Error::message("signature is missing"))?,
+    ))
+}
+
+fn encode_signed_id<W: Write>(
+    e: &mut Encoder<W>,
+    id: &[u8; 32],
+    signature: &[u8; 64],
+) -> Result<(), minicbor::encode::Error<W::Error>> {
+    let mut id_buf = [0; 64];
+    let mut signature_buf = [0; 128];
+
+    // unreachable errors.
+    let id = hex_encode(id, &mut id_buf).unwrap();
+    let signature = hex_encode(signature, &mut signature_buf).unwrap();
+
+    e.map(2)?;
+    e.u8(1)?.str(id)?;
+    e.u8(2)?.str(signature)?;
+
+    Ok(())
 }
 
 /// Supply Chain Validation solution.
@@ -135,3 +209,228 @@ impl<'a> Solution<'a> {
     /// Tag for embedding [`Solution`] in other types.
     pub const TAG: Tag = Tag::new(711);
 }
+
+/// Answer the challenge in the single-part `challenge_ur` with the device
+/// `secret_key`, returning the [`Response`] encoded as a UR.
+///
+/// The challenge must be signed by `challenge_public_key`, the Foundation
+/// challenge-signing key, otherwise nothing is signed.
+#[cfg(feature = "signing")]
+pub fn respond<C: Signing + Verification, const N: usize>(
+    secp: &Secp256k1<C>,
+    challenge_ur: &str,
+    challenge_public_key: &PublicKey,
+    secret_key: &SecretKey,
+) -> Result<heapless::String<N>, ScvError> {
+    let mut buf = [0; MAX_CBOR_LEN];
+    let challenge: Challenge = decode_ur(challenge_ur, Challenge::UR_TYPE, &mut buf)?;
+
+    ecdsa::Signature::from_compact(&challenge.signature)
+        .and_then(|mut signature| {
+            // The challenges are not always signed with normalized
+            // signatures, which libsecp256k1 refuses.
+            signature.normalize_s();
+            secp.verify_ecdsa(&message(&challenge.id), &signature, challenge_public_key)
+        })
+        .map_err(ScvError::InvalidChallengeSignature)?;
+
+    let signature = secp.sign_ecdsa(&message(&challenge.id), secret_key);
+    let response = Response {
+        id: challenge.id,
+        signature: signature.serialize_compact(),
+    };
+
+    let mut cursor = Cursor::new(&mut buf[..]);
+    minicbor::encode(&response, &mut cursor).map_err(|_| ScvError::BufferTooSmall)?;
+    let len = cursor.position();
+
+    foundation_ur::to_heapless_string(Response::UR_TYPE, &buf[..len])
+        .map_err(|_| ScvError::BufferTooSmall)
+}
+
+/// Verify that the single-part `response_ur` answers the challenge with
+/// `challenge_id` and was signed by the device `public_key`.
+#[cfg(feature = "signing")]
+pub fn verify_response<C: Verification>(
+    secp: &Secp256k1<C>,
+    response_ur: &str,
+    challenge_id: &[u8; 32],
+    public_key: &PublicKey,
+) -> Result<(), ScvError> {
+    let mut buf = [0; MAX_CBOR_LEN];
+    let response: Response = decode_ur(response_ur, Response::UR_TYPE, &mut buf)?;
+
+    if &response.id != challenge_id {
+        return Err(ScvError::MismatchedChallenge);
+    }
+
+    let signature = ecdsa::Signature::from_compact(&response.signature)?;
+    secp.verify_ecdsa(&message(&response.id), &signature, public_key)?;
+
+    Ok(())
+}
+
+/// Maximum length of the CBOR of a [`Challenge`] or a [`Response`].
+#[cfg(feature = "signing")]
+const MAX_CBOR_LEN: usize = 256;
+
+#[cfg(feature = "signing")]
+fn message(id: &[u8; 32]) -> Message {
+    Message::from_digest(sha256::Hash::hash(id).to_byte_array())
+}
+
+#[cfg(feature = "signing")]
+fn decode_ur<'a, T: Decode<'a, ()>>(
+    ur: &str,
+    ur_type: &str,
+    buf: &'a mut [u8],
+) -> Result<T, ScvError> {
+    let (received, message) = match foundation_ur::UR::parse(ur)? {
+        foundation_ur::UR::SinglePart { ur_type, message } => (ur_type, message),
+        _ => return Err(ScvError::NotSinglePart),
+    };
+
+    if received != ur_type {
+        return Err(ScvError::UnexpectedType);
+    }
+
+    let len = bytewords::decode_to_slice(message, buf, bytewords::Style::Minimal)?;
+    Ok(minicbor::decode(&buf[..len])?)
+}
+
+/// Errors that can happen when answering or verifying a challenge.
+#[cfg(feature = "signing")]
+#[derive(Debug)]
+pub enum ScvError {
+    /// The UR could not be parsed.
+    Ur(foundation_ur::ParseURError),
+    /// The UR is a multi-part UR.
+    NotSinglePart,
+    /// The UR has an unexpected type.
+    UnexpectedType,
+    /// The bytewords of the UR are invalid.
+    Bytewords(bytewords::DecodeError),
+    /// The CBOR payload of the UR is invalid.
+    InvalidCbor(minicbor::decode::Error),
+    /// The response doesn't fit in the output.
+    BufferTooSmall,
+    /// The response is for another challenge.
+    MismatchedChallenge,
+    /// The signature is invalid.
+    InvalidSignature(secp256k1::Error),
+    /// The signature of the challenge is invalid.
+    InvalidChallengeSignature(secp256k1::Error),
+}
+
+#[cfg(feature = "signing")]
+impl core::fmt::Display for ScvError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Ur(_) => write!(f, "invalid UR"),
+            Self::NotSinglePart => write!(f, "the UR is not a single-part UR"),
+            Self::UnexpectedType => write!(f, "unexpected UR type"),
+            Self::Bytewords(_) => write!(f, "invalid bytewords"),
+            Self::InvalidCbor(_) => write!(f, "failed to decode CBOR payload"),
+            Self::BufferTooSmall => write!(f, "the buffer is too small"),
+            Self::MismatchedChallenge => write!(f, "the response is for another challenge"),
+            Self::InvalidSignature(_) => write!(f, "invalid signature"),
+            Self::InvalidChallengeSignature(_) => write!(f, "invalid challenge signature"),
+        }
+    }
+}
+
+#[cfg(all(feature = "signing", feature = "std"))]
+impl std::error::Error for ScvError {}
+
+#[cfg(feature = "signing")]
+impl From<foundation_ur::ParseURError> for ScvError {
+    fn from(e: foundation_ur::ParseURError) -> Self {
+        Self::Ur(e)
+    }
+}
+
+#[cfg(feature = "signing")]
+impl From<bytewords::DecodeError> for ScvError {
+    fn from(e: bytewords::DecodeError) -> Self {
+        Self::Bytewords(e)
+    }
+}
+
+#[cfg(feature = "signing")]
+impl From<minicbor::decode::Error> for ScvError {
+    fn from(e: minicbor::decode::Error) -> Self {
+        Self::InvalidCbor(e)
+    }
+}
+
+#[cfg(feature = "signing")]
+impl From<secp256k1::Error> for ScvError {
+    fn from(e: secp256k1::Error) -> Self {
+        Self::InvalidSignature(e)
+    }
+}
+
+#[cfg(all(test, feature = "signing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_challenge_response() {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[0xcd; 32]).unwrap();
+        let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+        let factory_key = SecretKey::from_slice(&[0xab; 32]).unwrap();
+        let factory_public_key = PublicKey::from_secret_key(&secp, &factory_key);
+
+        let id = [0x11; 32];
+        let challenge = Challenge {
+            id,
+            signature: secp
+                .sign_ecdsa(&message(&id), &factory_key)
+                .serialize_compact(),
+        };
+        let cbor = minicbor::to_vec(&challenge).unwrap();
+        let challenge_ur =
+            foundation_ur::to_heapless_string::<512>(Challenge::UR_TYPE, &cbor).unwrap();
+
+        let response_ur =
+            respond::<_, 512>(&secp, &challenge_ur, &factory_public_key, &secret_key).unwrap();
+        assert!(response_ur.starts_with("ur:x-scv-response/"));
+        verify_response(&secp, &response_ur, &challenge.id, &public_key).unwrap();
+
+        assert!(matches!(
+            verify_response(&secp, &response_ur, &[0x12; 32], &public_key),
+            Err(ScvError::MismatchedChallenge)
+        ));
+        let other_key =
+            PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[0xef; 32]).unwrap());
+        assert!(matches!(
+            verify_response(&secp, &response_ur, &challenge.id, &other_key),
+            Err(ScvError::InvalidSignature(_))
+        ));
+        assert!(matches!(
+            respond::<_, 512>(&secp, &challenge_ur, &other_key, &secret_key),
+            Err(ScvError::InvalidChallengeSignature(_))
+        ));
+        assert!(matches!(
+            respond::<_, 512>(&secp, &response_ur, &factory_public_key, &secret_key),
+            Err(ScvError::UnexpectedType)
+        ));
+        assert!(matches!(
+            respond::<_, 64>(&secp, &challenge_ur, &factory_public_key, &secret_key),
+            Err(ScvError::BufferTooSmall)
+        ));
+
+        let forged = Challenge {
+            id,
+            signature: [0x22; 64],
+        };
+        let cbor = minicbor::to_vec(forged).unwrap();
+        let forged_ur =
+            foundation_ur::to_heapless_string::<512>(Challenge::UR_TYPE, &cbor).unwrap();
+        assert!(matches!(
+            respond::<_, 512>(&secp, &forged_ur, &factory_public_key, &secret_key),
+            Err(ScvError::InvalidChallengeSignature(_))
+        ));
+    }
+}
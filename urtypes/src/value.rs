@@ -30,7 +30,10 @@ use core::fmt::{Display, Formatter};
 
 use minicbor::{bytes::ByteSlice, encode::Write, Encode, Encoder};
 
-use crate::registry::{HDKeyRef, PassportRequest, PassportResponse};
+use crate::{
+    passport::{DiagnosticsReport, FirmwareChunk},
+    registry::{Address, ECKey, HDKeyRef, PassportRequest, PassportResponse, Psbt, Seed},
+};
 
 #[derive(Debug, PartialEq)]
 pub enum Value<'a> {
@@ -39,11 +42,21 @@ pub enum Value<'a> {
     /// crypto-hdkey.
     HDKey(HDKeyRef<'a>),
     /// crypto-psbt.
-    Psbt(&'a [u8]),
+    Psbt(Psbt<'a>),
+    /// crypto-seed.
+    Seed(Seed<'a>),
+    /// crypto-address.
+    Address(Address<'a>),
+    /// crypto-eckey.
+    ECKey(ECKey<'a>),
     /// crypto-request for Passport.
     PassportRequest(PassportRequest),
     /// crypto-response for Passport.
     PassportResponse(PassportResponse<'a>),
+    /// Passport diagnostics report.
+    PassportDiagnostics(DiagnosticsReport),
+    /// Passport firmware update chunk.
+    PassportFirmwareChunk(FirmwareChunk<'a>),
 }
 
 impl<'a> Value<'a> {
@@ -52,7 +65,10 @@ impl<'a> Value<'a> {
         let value = match ur_type {
             "bytes" => Self::Bytes(minicbor::decode::<&ByteSlice>(payload)?),
             "hdkey" | "crypto-hdkey" => Self::HDKey(minicbor::decode(payload)?),
-            "psbt" | "crypto-psbt" => Self::Psbt(minicbor::decode::<&ByteSlice>(payload)?),
+            "psbt" | "crypto-psbt" => Self::Psbt(minicbor::decode(payload)?),
+            "seed" | "crypto-seed" => Self::Seed(minicbor::decode(payload)?),
+            "address" | "crypto-address" => Self::Address(minicbor::decode(payload)?),
+            "eckey" | "crypto-eckey" => Self::ECKey(minicbor::decode(payload)?),
             // TODO: Remove crypto-request and crypto-response, these have
             // been removed from the UR registry standard (BCR-2020-006).
             "x-passport-request" | "crypto-request" => {
@@ -61,6 +77,8 @@ impl<'a> Value<'a> {
             "x-passport-response" | "crypto-response" => {
                 Self::PassportResponse(minicbor::decode(payload)?)
             }
+            "x-passport-diagnostics" => Self::PassportDiagnostics(minicbor::decode(payload)?),
+            "x-passport-firmware-chunk" => Self::PassportFirmwareChunk(minicbor::decode(payload)?),
             _ => return Err(Error::UnsupportedResource),
         };
 
@@ -81,8 +99,13 @@ impl<'a> Value<'a> {
             Value::Bytes(_) => "bytes",
             Value::HDKey(_) => "hdkey",
             Value::Psbt(_) => "crypto-psbt",
+            Value::Seed(_) => "crypto-seed",
+            Value::Address(_) => "crypto-address",
+            Value::ECKey(_) => "crypto-eckey",
             Value::PassportRequest(_) => "crypto-request",
             Value::PassportResponse(_) => "crypto-response",
+            Value::PassportDiagnostics(_) => "x-passport-diagnostics",
+            Value::PassportFirmwareChunk(_) => "x-passport-firmware-chunk",
         }
     }
 }
@@ -96,13 +119,25 @@ impl<'a, C> Encode<C> for Value<'a> {
         match self {
             Value::Bytes(v) => minicbor::bytes::encode(v, e, ctx),
             Value::HDKey(v) => v.encode(e, ctx),
-            Value::Psbt(v) => minicbor::bytes::encode(v, e, ctx),
+            Value::Psbt(v) => v.encode(e, ctx),
+            Value::Seed(v) => v.encode(e, ctx),
+            Value::Address(v) => v.encode(e, ctx),
+            Value::ECKey(v) => v.encode(e, ctx),
             Value::PassportRequest(v) => v.encode(e, ctx),
             Value::PassportResponse(v) => v.encode(e, ctx),
+            Value::PassportDiagnostics(v) => v.encode(e, ctx),
+            Value::PassportFirmwareChunk(v) => v.encode(e, ctx),
         }
     }
 }
 
+/// Decode the payload of a completed UR into a [`Value`] based on its type.
+///
+/// This is the same as [`Value::from_ur`].
+pub fn dispatch<'a>(ur_type: &str, payload: &'a [u8]) -> Result<Value<'a>, Error> {
+    Value::from_ur(ur_type, payload)
+}
+
 /// Errors that can occur when parsing a value.
 #[derive(Debug)]
 pub enum Error {
@@ -158,4 +193,28 @@ mod tests {
         let cbor = minicbor::to_vec(&value).unwrap();
         assert_eq!(cbor, CBOR_PAYLOAD);
     }
+
+    #[test]
+    fn test_dispatch() {
+        let eckey = ECKey {
+            curve: ECKey::SECP256K1,
+            is_private: true,
+            data: &[0x8c; 32],
+        };
+        let cbor = minicbor::to_vec(eckey).unwrap();
+
+        let value = dispatch("crypto-eckey", &cbor).unwrap();
+        assert_eq!(value, Value::ECKey(eckey));
+        assert_eq!(value.ur_type(), "crypto-eckey");
+        assert_eq!(minicbor::to_vec(&value).unwrap(), cbor);
+
+        assert!(matches!(
+            dispatch("crypto-unknown", &cbor),
+            Err(Error::UnsupportedResource)
+        ));
+        assert!(matches!(
+            dispatch("crypto-seed", &cbor),
+            Err(Error::InvalidCbor(_))
+        ));
+    }
 }
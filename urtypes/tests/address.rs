@@ -19,7 +19,7 @@ fn test_roundtrip() {
             AddressVector::Ethereum(a) => Address {
                 info: Some(CoinInfo::new(CoinType::new(0x3c), 1)),
                 kind: None,
-                data: (a as &[_]).into(),
+                data: a as &[_],
             },
         };
 
@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Differential tests against the reference encodings of the registry.
+//!
+//! Every reference CBOR is decoded and encoded again, the result must be
+//! identical byte by byte so that third-party wallets read back what we
+//! emit.
+
+use foundation_test_vectors::{OutputDescriptorVector, URVector, UR};
+use foundation_urtypes::registry::{
+    Account, ECKey, HDKeyRef, OutputDescriptors, Terminal, TerminalContext,
+};
+use minicbor::Encoder;
+
+#[test]
+fn test_hdkey() {
+    for vector in URVector::new()
+        .iter()
+        .filter(|v| matches!(v.ur, UR::HDKey(_)))
+    {
+        let hdkey: HDKeyRef = minicbor::decode(&vector.as_cbor).unwrap();
+        let cbor = minicbor::to_vec(&hdkey).unwrap();
+        assert_eq!(cbor, vector.as_cbor, "{}", vector.name);
+    }
+}
+
+#[test]
+fn test_eckey() {
+    for vector in URVector::new()
+        .iter()
+        .filter(|v| matches!(v.ur, UR::ECKey(_)))
+    {
+        let expected = vector.ur.unwrap_eckey();
+        let eckey: ECKey = minicbor::decode(&vector.as_cbor).unwrap();
+        assert_eq!(eckey.is_private, expected.is_private, "{}", vector.name);
+        assert_eq!(eckey.data, expected.data, "{}", vector.name);
+
+        let cbor = minicbor::to_vec(eckey).unwrap();
+        assert_eq!(cbor, vector.as_cbor, "{}", vector.name);
+    }
+}
+
+#[test]
+fn test_output_descriptor() {
+    for vector in OutputDescriptorVector::new() {
+        let a: TerminalContext<8> = TerminalContext::new();
+        let descriptor: Terminal = minicbor::decode_with(&vector.as_cbor, &mut &a).unwrap();
+        let cbor = minicbor::to_vec(&descriptor).unwrap();
+        assert_eq!(cbor, vector.as_cbor, "{}", vector.descriptor);
+    }
+}
+
+#[test]
+fn test_account() {
+    const MASTER_FINGERPRINT: u32 = 0x37b5eed4;
+
+    let vectors = OutputDescriptorVector::new();
+    let b: TerminalContext<8> = TerminalContext::new();
+    let a: TerminalContext<8> = TerminalContext::new();
+
+    // Build the account from the reference output descriptors, in the
+    // field order given by BCR-2020-015.
+    let mut expected = Vec::new();
+    let mut e = Encoder::new(&mut expected);
+    e.map(2).unwrap();
+    e.u8(1).unwrap().u32(MASTER_FINGERPRINT).unwrap();
+    e.u8(2).unwrap().array(vectors.len() as u64).unwrap();
+    for vector in &vectors {
+        e.tag(Terminal::TAG).unwrap();
+        e.writer_mut().extend_from_slice(&vector.as_cbor);
+    }
+
+    let account: Account = minicbor::decode_with(&expected, &mut &a).unwrap();
+    assert_eq!(account.master_fingerprint, MASTER_FINGERPRINT);
+    assert_eq!(account.output_descriptors.len(), vectors.len());
+
+    let cbor = minicbor::to_vec(&account).unwrap();
+    assert_eq!(cbor, expected);
+
+    // The output descriptors are the same as when decoded alone.
+    let mut output_descriptors = OutputDescriptors::new();
+    for vector in &vectors {
+        output_descriptors
+            .push(minicbor::decode_with(&vector.as_cbor, &mut &b).unwrap())
+            .unwrap();
+    }
+    assert_eq!(account.output_descriptors, output_descriptors);
+}
@@ -0,0 +1,118 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Firmware verification.
+
+use foundation_firmware::{header, Header, KeySet, Verifier, HEADER_LEN};
+use nom::Finish;
+use secp256k1::{PublicKey, Secp256k1};
+
+#[cfg(feature = "wasm-bindgen")]
+use wasm_bindgen::prelude::*;
+
+/// The information of a verified firmware header.
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen(getter_with_clone))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirmwareHeader {
+    /// The time stamp of the firmware.
+    pub timestamp: u32,
+    /// The date of the firmware.
+    pub date: String,
+    /// The version of the firmware.
+    pub version: String,
+    /// The length of the firmware, in bytes, excluding the header.
+    pub length: u32,
+    /// Whether the firmware was signed by the user instead of Foundation.
+    #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen(js_name = signedByUser))]
+    pub signed_by_user: bool,
+}
+
+impl From<&Header> for FirmwareHeader {
+    fn from(header: &Header) -> Self {
+        Self {
+            timestamp: header.information.timestamp,
+            date: header.information.date.as_str().into(),
+            version: header.information.version.as_str().into(),
+            length: header.information.length,
+            signed_by_user: header.is_signed_by_user(),
+        }
+    }
+}
+
+/// Parse and verify a firmware header.
+///
+/// `header` can be the full firmware image, only the first [`HEADER_LEN`]
+/// bytes are used. The signatures are not verified, see
+/// [`verify_firmware`].
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen(js_name = parseFirmwareHeader))]
+pub fn parse_firmware_header(header: &[u8]) -> Result<FirmwareHeader, String> {
+    parse_header(header).map(|header| FirmwareHeader::from(&header))
+}
+
+/// Verify a firmware image, including its header and signatures.
+///
+/// `user_public_key` is the public key of the user, in SEC1 format, used for
+/// user signed firmware.
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen(js_name = verifyFirmware))]
+pub fn verify_firmware(
+    image: &[u8],
+    user_public_key: Option<Vec<u8>>,
+) -> Result<FirmwareHeader, String> {
+    let header = parse_header(image)?;
+    let user_public_key = user_public_key
+        .map(|public_key| PublicKey::from_slice(&public_key))
+        .transpose()
+        .map_err(|e| format!("invalid user public key: {e}"))?;
+
+    let mut verifier = Verifier::new(&header);
+    verifier.update(image);
+
+    let secp = Secp256k1::verification_only();
+    verifier
+        .finalize(&secp, &KeySet::PRODUCTION, user_public_key.as_ref())
+        .map_err(|e| format!("signature verification failed: {e}"))?;
+
+    Ok(FirmwareHeader::from(&header))
+}
+
+fn parse_header(image: &[u8]) -> Result<Header, String> {
+    let header_len = usize::try_from(HEADER_LEN).unwrap();
+    if image.len() < header_len {
+        return Err(format!(
+            "firmware is {} bytes, which is smaller than the header ({header_len} bytes)",
+            image.len()
+        ));
+    }
+
+    let (_, header) = header(&image[..header_len])
+        .finish()
+        .map_err(|_| String::from("failed to parse header"))?;
+    header
+        .verify()
+        .map_err(|e| format!("header verification failed: {e}"))?;
+
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foundation_test_vectors::firmware::{INVALID_MAGIC, VALID_HEADER};
+
+    #[test]
+    fn test_parse_firmware_header() {
+        let header = parse_firmware_header(VALID_HEADER).unwrap();
+        assert_eq!(header.version, "2.3.0");
+        assert!(!header.signed_by_user);
+
+        assert!(parse_firmware_header(INVALID_MAGIC).is_err());
+        assert!(parse_firmware_header(&VALID_HEADER[..100]).is_err());
+    }
+
+    #[test]
+    fn test_verify_firmware() {
+        // Only the header is available, the length doesn't match.
+        assert!(verify_firmware(VALID_HEADER, None).is_err());
+        assert!(verify_firmware(VALID_HEADER, Some(vec![0x02; 33])).is_err());
+    }
+}
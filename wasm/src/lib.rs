@@ -0,0 +1,16 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! WebAssembly bindings for companion applications.
+//!
+//! Exposes the firmware header parsing and verification and the Uniform
+//! Resources decoder so that they can be used from JavaScript with the same
+//! code that runs on the device.
+//!
+//! The bindings are generated when the `wasm-bindgen` feature is enabled,
+//! otherwise this is a regular Rust library, which is useful for testing.
+
+#![warn(missing_docs)]
+
+pub mod firmware;
+pub mod ur;
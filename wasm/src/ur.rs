@@ -0,0 +1,118 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Uniform Resources decoding.
+
+use foundation_ur::{
+    bytewords::{self, Style},
+    Decoder, UR,
+};
+
+#[cfg(feature = "wasm-bindgen")]
+use wasm_bindgen::prelude::*;
+
+/// Decoder of single-part and multi-part Uniform Resources.
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen)]
+#[derive(Default)]
+pub struct UrDecoder {
+    decoder: Decoder,
+    single_part: Option<(String, Vec<u8>)>,
+}
+
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen)]
+impl UrDecoder {
+    /// Construct a new [`UrDecoder`].
+    #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen(constructor))]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receive a scanned UR string.
+    ///
+    /// Returns `true` when the message is complete.
+    pub fn receive(&mut self, part: &str) -> Result<bool, String> {
+        let ur = UR::parse(part).map_err(|e| e.to_string())?;
+
+        if ur.is_single_part() {
+            let bytewords = ur
+                .as_bytewords()
+                .expect("parsed resource should contain bytewords");
+            let message =
+                bytewords::decode(bytewords, Style::Minimal).map_err(|e| e.to_string())?;
+            self.single_part = Some((ur.as_type().into(), message));
+        } else {
+            self.decoder.receive(ur).map_err(|e| e.to_string())?;
+        }
+
+        Ok(self.is_complete())
+    }
+
+    /// Returns `true` if the message is complete.
+    #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen(js_name = isComplete))]
+    pub fn is_complete(&self) -> bool {
+        self.single_part.is_some() || self.decoder.is_complete()
+    }
+
+    /// Estimated percentage of completion, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        if self.single_part.is_some() {
+            1.0
+        } else {
+            self.decoder.estimated_percent_complete()
+        }
+    }
+
+    /// The UR type of the message, if known.
+    #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen(js_name = urType))]
+    pub fn ur_type(&self) -> Option<String> {
+        match self.single_part {
+            Some((ref ur_type, _)) => Some(ur_type.clone()),
+            None => self.decoder.ur_type().map(String::from),
+        }
+    }
+
+    /// The CBOR encoded message, if complete.
+    pub fn message(&self) -> Result<Option<Vec<u8>>, String> {
+        match self.single_part {
+            Some((_, ref message)) => Ok(Some(message.clone())),
+            None => self
+                .decoder
+                .message()
+                .map(|message| message.map(Vec::from))
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Clear the decoder to receive a new message.
+    pub fn clear(&mut self) {
+        self.decoder.clear();
+        self.single_part = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ur_decoder() {
+        let message = b"Hello, world!";
+        let mut encoder = foundation_ur::Encoder::new();
+        encoder.start("bytes", message, 5).unwrap();
+
+        let mut decoder = UrDecoder::new();
+        while !decoder.receive(&encoder.next_part().to_string()).unwrap() {}
+        assert_eq!(decoder.ur_type().as_deref(), Some("bytes"));
+        assert_eq!(decoder.message().unwrap().as_deref(), Some(&message[..]));
+
+        decoder.clear();
+        assert!(!decoder.is_complete());
+        decoder
+            .receive(&foundation_ur::to_string("bytes", message))
+            .unwrap();
+        assert_eq!(decoder.progress(), 1.0);
+        assert_eq!(decoder.message().unwrap().as_deref(), Some(&message[..]));
+
+        assert!(decoder.receive("ur:bytes/1-3/invalid").is_err());
+    }
+}
@@ -0,0 +1,330 @@
+// SPDX-FileCopyrightText: © 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Development tasks for the workspace.
+//!
+//! Run `cargo xtask help` for the list of tasks.
+
+use std::{
+    env, fmt,
+    path::{Path, PathBuf},
+    process::Command as Process,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::{command, Arg, ArgAction, ArgMatches, Command};
+
+/// Target used to check that the `no_std` crates build for the devices.
+const EMBEDDED_TARGET: &str = "thumbv7em-none-eabihf";
+
+/// A crate of the build matrix.
+struct Package {
+    name: &'static str,
+    /// Features to combine, every combination is built.
+    features: &'static [&'static str],
+    /// Features that need `std`, these are only built for the host.
+    std_features: &'static [&'static str],
+    /// Pairs of features that can't be enabled at the same time.
+    conflicts: &'static [(&'static str, &'static str)],
+}
+
+/// The crates of the build matrix.
+///
+/// `foundation-ffi` is not here as it needs a nightly compiler, and
+/// neither are the tools, fuzzers and test vectors as they only run on the
+/// host, nor `foundation-wasm` which is built for WebAssembly in the CI.
+const PACKAGES: &[Package] = &[
+    Package {
+        name: "foundation-arena",
+        features: &[],
+        std_features: &[],
+        conflicts: &[],
+    },
+    Package {
+        name: "foundation-bip39",
+        features: &["std"],
+        std_features: &["std"],
+        conflicts: &[],
+    },
+    Package {
+        name: "foundation-codecs",
+        features: &[],
+        std_features: &[],
+        conflicts: &[],
+    },
+    Package {
+        name: "foundation-crypto",
+        features: &[],
+        std_features: &[],
+        conflicts: &[],
+    },
+    Package {
+        name: "foundation-firmware",
+        features: &["std", "binary", "defmt-03", "signing"],
+        std_features: &["std", "binary"],
+        conflicts: &[],
+    },
+    Package {
+        name: "embedded-storage-nom",
+        features: &["std", "saturating", "log"],
+        std_features: &["std"],
+        conflicts: &[],
+    },
+    Package {
+        name: "stratum-v1",
+        features: &["std", "defmt-03", "log"],
+        std_features: &["std"],
+        conflicts: &[("defmt-03", "log")],
+    },
+    Package {
+        name: "foundation-ur",
+        features: &["std", "alloc", "defmt-03"],
+        std_features: &["std"],
+        conflicts: &[],
+    },
+    Package {
+        name: "foundation-urtypes",
+        features: &["std", "alloc", "signing"],
+        std_features: &["std"],
+        conflicts: &[],
+    },
+];
+
+impl Package {
+    /// All the combinations of features to build, for the host or for the
+    /// embedded target.
+    fn feature_sets(&self, embedded: bool) -> Vec<Vec<&'static str>> {
+        let features: Vec<_> = self
+            .features
+            .iter()
+            .copied()
+            .filter(|feature| !embedded || !self.std_features.contains(feature))
+            .collect();
+
+        (0..1u32 << features.len())
+            .map(|mask| {
+                features
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| mask & (1 << i) != 0)
+                    .map(|(_, &feature)| feature)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|set| {
+                !self
+                    .conflicts
+                    .iter()
+                    .any(|(a, b)| set.contains(a) && set.contains(b))
+            })
+            .collect()
+    }
+}
+
+/// A single build of the matrix.
+struct Step {
+    package: &'static str,
+    features: Vec<&'static str>,
+    /// `None` for the host, the tests are run too.
+    target: Option<&'static str>,
+}
+
+impl Step {
+    fn args(&self) -> Vec<String> {
+        let mut args = vec![
+            if self.target.is_some() {
+                "build"
+            } else {
+                "test"
+            }
+            .to_string(),
+            "--package".to_string(),
+            self.package.to_string(),
+            "--no-default-features".to_string(),
+        ];
+
+        if !self.features.is_empty() {
+            args.push("--features".to_string());
+            args.push(self.features.join(","));
+        }
+
+        if let Some(target) = self.target {
+            args.push("--lib".to_string());
+            args.push("--target".to_string());
+            args.push(target.to_string());
+        }
+
+        args
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cargo {}", self.args().join(" "))
+    }
+}
+
+fn main() -> Result<()> {
+    let matches = command!()
+        .bin_name("cargo xtask")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("build-matrix")
+                .about(
+                    "Build every crate for the host and the embedded target with \
+                     all the combinations of its features, and run the tests on the host",
+                )
+                .arg(
+                    Arg::new("package")
+                        .short('p')
+                        .long("package")
+                        .action(ArgAction::Append)
+                        .help("Only build this package, can be repeated"),
+                )
+                .arg(
+                    Arg::new("host-only")
+                        .long("host-only")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("embedded-only")
+                        .help("Only build for the host"),
+                )
+                .arg(
+                    Arg::new("embedded-only")
+                        .long("embedded-only")
+                        .action(ArgAction::SetTrue)
+                        .help(format!("Only build for {EMBEDDED_TARGET}")),
+                )
+                .arg(
+                    Arg::new("toolchain")
+                        .long("toolchain")
+                        .help("Rust toolchain to use, defaults to the one in rust-toolchain.toml"),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .action(ArgAction::SetTrue)
+                        .help("Print the commands without running them"),
+                ),
+        )
+        .get_matches();
+
+    match matches.subcommand() {
+        Some(("build-matrix", matches)) => build_matrix(matches),
+        _ => unreachable!("a subcommand is required"),
+    }
+}
+
+fn build_matrix(matches: &ArgMatches) -> Result<()> {
+    let selected: Vec<&String> = matches
+        .get_many::<String>("package")
+        .map(Iterator::collect)
+        .unwrap_or_default();
+    for name in &selected {
+        if !PACKAGES.iter().any(|package| package.name == name.as_str()) {
+            bail!("package {name} is not in the build matrix");
+        }
+    }
+
+    let host = !matches.get_flag("embedded-only");
+    let embedded = !matches.get_flag("host-only");
+
+    let mut steps = Vec::new();
+    for package in PACKAGES {
+        if !selected.is_empty() && !selected.iter().any(|name| *name == package.name) {
+            continue;
+        }
+
+        if host {
+            for features in package.feature_sets(false) {
+                steps.push(Step {
+                    package: package.name,
+                    features,
+                    target: None,
+                });
+            }
+        }
+
+        if embedded {
+            for features in package.feature_sets(true) {
+                steps.push(Step {
+                    package: package.name,
+                    features,
+                    target: Some(EMBEDDED_TARGET),
+                });
+            }
+        }
+    }
+
+    if matches.get_flag("dry-run") {
+        for step in &steps {
+            println!("{step}");
+        }
+        return Ok(());
+    }
+
+    let toolchain = matches.get_one::<String>("toolchain");
+    let root = workspace_root();
+    let mut failures = Vec::new();
+    for (n, step) in steps.iter().enumerate() {
+        eprintln!("[{}/{}] {step}", n + 1, steps.len());
+
+        let mut cargo = match toolchain {
+            Some(toolchain) => {
+                let mut cargo = Process::new("cargo");
+                cargo.arg(format!("+{toolchain}"));
+                cargo
+            }
+            None => Process::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into())),
+        };
+
+        let status = cargo
+            .args(step.args())
+            .current_dir(&root)
+            .status()
+            .context("failed to run cargo")?;
+        if !status.success() {
+            failures.push(step);
+        }
+    }
+
+    if !failures.is_empty() {
+        eprintln!("\n{} of {} builds failed:", failures.len(), steps.len());
+        for step in &failures {
+            eprintln!("  {step}");
+        }
+        bail!("the build matrix failed");
+    }
+
+    eprintln!("\nAll the {} builds passed.", steps.len());
+    Ok(())
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask is in the workspace")
+        .to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_sets() {
+        let stratum = PACKAGES
+            .iter()
+            .find(|package| package.name == "stratum-v1")
+            .unwrap();
+
+        let host = stratum.feature_sets(false);
+        assert_eq!(host.len(), 6);
+        assert!(host.contains(&vec![]));
+        assert!(host.contains(&vec!["std", "log"]));
+        assert!(!host
+            .iter()
+            .any(|set| set.contains(&"defmt-03") && set.contains(&"log")));
+
+        let embedded = stratum.feature_sets(true);
+        assert_eq!(embedded, vec![vec![], vec!["defmt-03"], vec!["log"]]);
+    }
+}